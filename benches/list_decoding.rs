@@ -0,0 +1,145 @@
+//! Decoding benchmarks for large transaction list pages.
+//!
+//! Compares the `Box<str>` list models of the crate with the `String` models they replaced, and
+//! the direct decoding with the one going through a `serde_json::Value`.
+//!
+//! Run with `cargo bench --bench list_decoding`.
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+
+use chapa_rust::models::transaction::GetTransactionsResponse;
+
+/// The list models as they were before switching their text fields to `Box<str>`, as the
+/// baseline of the benchmarks.
+mod string_models {
+    use chrono::{DateTime, Utc};
+    use serde::Deserialize;
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    pub struct GetTransactionsResponse {
+        pub message: String,
+        pub status: String,
+        pub data: GetTransactionsData,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    pub struct GetTransactionsData {
+        pub transactions: Vec<Transaction>,
+        pub pagination: Pagination,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    pub struct Customer {
+        pub id: u32,
+        pub first_name: Option<String>,
+        pub last_name: Option<String>,
+        pub email: Option<String>,
+        pub mobile: Option<String>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    pub struct Transaction {
+        pub status: String,
+        #[serde(alias = "reference", alias = "chapa_reference")]
+        pub ref_id: String,
+        pub r#type: String,
+        pub created_at: DateTime<Utc>,
+        pub currency: String,
+        pub amount: String,
+        pub charge: String,
+        pub trans_id: Option<String>,
+        pub payment_method: String,
+        pub customer: Customer,
+        #[serde(default, alias = "trx_ref")]
+        pub tx_ref: Option<String>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    pub struct Pagination {
+        pub per_page: u32,
+        pub current_page: u32,
+        pub first_page_url: String,
+        pub next_page_url: Option<String>,
+        pub prev_page_url: Option<String>,
+    }
+}
+
+/// Builds a `GET /transactions` page holding `count` transactions.
+fn transactions_page(count: usize) -> Vec<u8> {
+    let transactions: Vec<_> = (0..count)
+        .map(|i| {
+            serde_json::json!({
+                "status": "success",
+                "ref_id": format!("APfxkC4wuFSu{i}"),
+                "type": "Payment Link",
+                "created_at": "2024-06-17T12:38:39.000000Z",
+                "currency": "ETB",
+                "amount": "100.00",
+                "charge": "3.50",
+                "trans_id": format!("CHcuKjgnN0Dk{i}"),
+                "payment_method": "telebirr",
+                "customer": {
+                    "id": i,
+                    "first_name": "Abebe",
+                    "last_name": "Bikila",
+                    "email": "abebe@example.com",
+                    "mobile": "0912345678"
+                }
+            })
+        })
+        .collect();
+    serde_json::to_vec(&serde_json::json!({
+        "message": "Transaction details",
+        "status": "success",
+        "data": {
+            "transactions": transactions,
+            "pagination": {
+                "per_page": count,
+                "current_page": 1,
+                "first_page_url": "https://api.chapa.co/v1/transactions?page=1",
+                "next_page_url": "https://api.chapa.co/v1/transactions?page=2",
+                "prev_page_url": null
+            }
+        }
+    }))
+    .unwrap()
+}
+
+fn decode_transactions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_transactions");
+    for count in [10, 100, 500] {
+        let body = transactions_page(count);
+        group.throughput(Throughput::Bytes(body.len() as u64));
+        group.bench_with_input(BenchmarkId::new("direct", count), &body, |b, body| {
+            b.iter(|| serde_json::from_slice::<GetTransactionsResponse>(black_box(body)).unwrap())
+        });
+        // The baseline: the same page decoded into the former `String` models.
+        group.bench_with_input(
+            BenchmarkId::new("string_models", count),
+            &body,
+            |b, body| {
+                b.iter(|| {
+                    serde_json::from_slice::<string_models::GetTransactionsResponse>(black_box(
+                        body,
+                    ))
+                    .unwrap()
+                })
+            },
+        );
+        // The path taken when the raw response is retained: the body goes through a `Value` first.
+        group.bench_with_input(BenchmarkId::new("via_value", count), &body, |b, body| {
+            b.iter(|| {
+                let value: serde_json::Value = serde_json::from_slice(black_box(body)).unwrap();
+                serde_json::from_value::<GetTransactionsResponse>(value).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode_transactions);
+criterion_main!(benches);
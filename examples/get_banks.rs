@@ -5,7 +5,7 @@ async fn main() {
     dotenvy::dotenv().ok();
     // initialize a chapa client
     let config = ChapaConfigBuilder::new().build().unwrap();
-    let mut client = ChapaClient::from_config(config).unwrap();
+    let client = ChapaClient::from_config(config).unwrap();
 
     // call the get_banks method
     let result = client.get_banks().await;
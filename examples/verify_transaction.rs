@@ -5,7 +5,7 @@ async fn main() {
     dotenvy::dotenv().ok();
     // initialize a chapa client
     let config = ChapaConfigBuilder::new().build().unwrap();
-    let mut client = ChapaClient::from_config(config).unwrap();
+    let client = ChapaClient::from_config(config).unwrap();
 
     let tx_ref = String::from("mail_order_injera");
     let verification_result = client.verify_transaction(&tx_ref).await;
@@ -0,0 +1,346 @@
+//! # API Module
+//!
+//! The [`ChapaApi`] trait lists the Chapa API calls of [`ChapaClient`], so application code can
+//! depend on the trait and be unit-tested against a fake implementation, such as the
+//! `MockChapaClient` of the `mock` module (behind the `test-utils` feature).
+//!
+//! The trait covers one method per endpoint. The helpers built on top of them (checkout reuse,
+//! receipts, parallel bulk transfers, polling, ...) stay on [`ChapaClient`].
+//!
+//! The trait is object safe (its futures are boxed), so it can also be injected as a
+//! [`DynChapaApi`] trait object, e.g. in the state of a web application or across crate
+//! boundaries, without making every type generic over the client.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use chapa_rust::{api::ChapaApi, error::Result};
+//!
+//! /// Marks an order paid if Chapa confirms the payment.
+//! async fn confirm(chapa: &impl ChapaApi, tx_ref: &str) -> Result<bool> {
+//!     let response = chapa.verify_transaction(tx_ref).await?;
+//!     Ok(response.data.and_then(|data| data.status).as_deref() == Some("success"))
+//! }
+//! ```
+//!
+//! With a trait object:
+//! ```rust,no_run
+//! use std::sync::Arc;
+//!
+//! use chapa_rust::{api::DynChapaApi, client::ChapaClient};
+//!
+//! struct AppState {
+//!     chapa: DynChapaApi,
+//! }
+//!
+//! let state = AppState {
+//!     chapa: Arc::new(ChapaClient::new("CHASECK_TEST-your_secret_key").unwrap()),
+//! };
+//! ```
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    client::ChapaClient,
+    error::Result,
+    models::{
+        channel::PaymentChannel,
+        direct_charge::{
+            AuthorizeDirectChargeOptions, AuthorizeDirectChargeResponse, DirectChargeOptions,
+        },
+        payment::InitializeOptions,
+        response::{
+            BulkTransferResponse, CreateSubaccountResponse, DirectChargeResponse,
+            GetBalancesResponse, GetBanksResponse, GetSubaccountsResponse, InitializeResponse,
+            SwapResponse, TransactionLogsResponse, VerifyResponse, VerifyTransferResponse,
+        },
+        subaccount::CreateSubaccountOptions,
+        swap::SwapOptions,
+        transaction::GetTransactionsResponse,
+        transfer::{
+            BulkTransferOptions, GetTransfersResponse, Transfer, TransferOptions, TransferResponse,
+        },
+    },
+};
+
+/// A shared, type-erased [`ChapaApi`], for dependency injection.
+pub type DynChapaApi = Arc<dyn ChapaApi + Send + Sync>;
+
+/// The calls of the Chapa API, see the documentation of the [`ChapaClient`] methods of the
+/// same name.
+#[async_trait]
+pub trait ChapaApi: Send + Sync {
+    /// Retrieves the list of all banks supported by Chapa.
+    async fn get_banks(&self) -> Result<GetBanksResponse>;
+
+    /// Retrieves the balances of the merchant, one per currency.
+    async fn get_balances(&self) -> Result<GetBalancesResponse>;
+
+    /// Retrieves the payment channels enabled for the merchant account.
+    async fn get_payment_channels(&self) -> Result<Vec<PaymentChannel>>;
+
+    /// Initializes a transaction and returns its checkout URL.
+    async fn initialize_transaction(
+        &self,
+        transaction: InitializeOptions,
+    ) -> Result<InitializeResponse>;
+
+    /// Verifies the status of a transaction using its reference.
+    async fn verify_transaction(&self, tx_ref: &str) -> Result<VerifyResponse>;
+
+    /// Retrieves the logs of a transaction.
+    async fn get_transaction_logs(&self, tx_ref: &str) -> Result<TransactionLogsResponse>;
+
+    /// Retrieves a page of the transactions received by the merchant account.
+    async fn get_transactions(&self) -> Result<GetTransactionsResponse>;
+
+    /// Retrieves a page of the transfers made by the merchant account.
+    async fn get_transfers(&self) -> Result<GetTransfersResponse>;
+
+    /// Retrieves every transfer of a bulk transfer batch.
+    async fn get_bulk_transfers(&self, batch_id: u64) -> Result<Vec<Transfer>>;
+
+    /// Initiates a transfer to a bank account or mobile wallet.
+    async fn transfer(&self, options: TransferOptions) -> Result<TransferResponse>;
+
+    /// Verifies the status of a transfer using its reference.
+    async fn verify_transfer(&self, reference: &str) -> Result<VerifyTransferResponse>;
+
+    /// Queues a bulk transfer of up to 100 transfers in a single batch.
+    async fn bulk_transfer(&self, options: BulkTransferOptions) -> Result<BulkTransferResponse>;
+
+    /// Swaps an amount from one currency to another in the merchant balance.
+    async fn swap(&self, options: SwapOptions) -> Result<SwapResponse>;
+
+    /// Creates a subaccount for split payments.
+    async fn create_subaccount(
+        &self,
+        options: CreateSubaccountOptions,
+    ) -> Result<CreateSubaccountResponse>;
+
+    /// Retrieves the subaccounts of the merchant account.
+    async fn get_subaccounts(&self) -> Result<GetSubaccountsResponse>;
+
+    /// Initiates a direct charge on `channel`.
+    async fn direct_charge(
+        &self,
+        channel: PaymentChannel,
+        options: DirectChargeOptions,
+    ) -> Result<DirectChargeResponse>;
+
+    /// Authorizes a direct charge, e.g. with the OTP received by the customer.
+    async fn authorize_direct_charge(
+        &self,
+        channel: PaymentChannel,
+        options: AuthorizeDirectChargeOptions,
+    ) -> Result<AuthorizeDirectChargeResponse>;
+}
+
+#[async_trait]
+impl ChapaApi for ChapaClient {
+    async fn get_banks(&self) -> Result<GetBanksResponse> {
+        ChapaClient::get_banks(self).await
+    }
+
+    async fn get_balances(&self) -> Result<GetBalancesResponse> {
+        ChapaClient::get_balances(self).await
+    }
+
+    async fn get_payment_channels(&self) -> Result<Vec<PaymentChannel>> {
+        ChapaClient::get_payment_channels(self).await
+    }
+
+    async fn initialize_transaction(
+        &self,
+        transaction: InitializeOptions,
+    ) -> Result<InitializeResponse> {
+        ChapaClient::initialize_transaction(self, transaction).await
+    }
+
+    async fn verify_transaction(&self, tx_ref: &str) -> Result<VerifyResponse> {
+        ChapaClient::verify_transaction(self, tx_ref).await
+    }
+
+    async fn get_transaction_logs(&self, tx_ref: &str) -> Result<TransactionLogsResponse> {
+        ChapaClient::get_transaction_logs(self, tx_ref).await
+    }
+
+    async fn get_transactions(&self) -> Result<GetTransactionsResponse> {
+        ChapaClient::get_transactions(self).await
+    }
+
+    async fn get_transfers(&self) -> Result<GetTransfersResponse> {
+        ChapaClient::get_transfers(self).await
+    }
+
+    async fn get_bulk_transfers(&self, batch_id: u64) -> Result<Vec<Transfer>> {
+        ChapaClient::get_bulk_transfers(self, batch_id).await
+    }
+
+    async fn transfer(&self, options: TransferOptions) -> Result<TransferResponse> {
+        ChapaClient::transfer(self, options).await
+    }
+
+    async fn verify_transfer(&self, reference: &str) -> Result<VerifyTransferResponse> {
+        ChapaClient::verify_transfer(self, reference).await
+    }
+
+    async fn bulk_transfer(&self, options: BulkTransferOptions) -> Result<BulkTransferResponse> {
+        ChapaClient::bulk_transfer(self, options).await
+    }
+
+    async fn swap(&self, options: SwapOptions) -> Result<SwapResponse> {
+        ChapaClient::swap(self, options).await
+    }
+
+    async fn create_subaccount(
+        &self,
+        options: CreateSubaccountOptions,
+    ) -> Result<CreateSubaccountResponse> {
+        ChapaClient::create_subaccount(self, options).await
+    }
+
+    async fn get_subaccounts(&self) -> Result<GetSubaccountsResponse> {
+        ChapaClient::get_subaccounts(self).await
+    }
+
+    async fn direct_charge(
+        &self,
+        channel: PaymentChannel,
+        options: DirectChargeOptions,
+    ) -> Result<DirectChargeResponse> {
+        ChapaClient::direct_charge(self, channel, options).await
+    }
+
+    async fn authorize_direct_charge(
+        &self,
+        channel: PaymentChannel,
+        options: AuthorizeDirectChargeOptions,
+    ) -> Result<AuthorizeDirectChargeResponse> {
+        ChapaClient::authorize_direct_charge(self, channel, options).await
+    }
+}
+
+/// Forwards to the shared implementation, so a [`DynChapaApi`] can be passed where an
+/// `impl ChapaApi` is expected.
+#[async_trait]
+impl<T: ChapaApi + ?Sized> ChapaApi for Arc<T> {
+    async fn get_banks(&self) -> Result<GetBanksResponse> {
+        (**self).get_banks().await
+    }
+
+    async fn get_balances(&self) -> Result<GetBalancesResponse> {
+        (**self).get_balances().await
+    }
+
+    async fn get_payment_channels(&self) -> Result<Vec<PaymentChannel>> {
+        (**self).get_payment_channels().await
+    }
+
+    async fn initialize_transaction(
+        &self,
+        transaction: InitializeOptions,
+    ) -> Result<InitializeResponse> {
+        (**self).initialize_transaction(transaction).await
+    }
+
+    async fn verify_transaction(&self, tx_ref: &str) -> Result<VerifyResponse> {
+        (**self).verify_transaction(tx_ref).await
+    }
+
+    async fn get_transaction_logs(&self, tx_ref: &str) -> Result<TransactionLogsResponse> {
+        (**self).get_transaction_logs(tx_ref).await
+    }
+
+    async fn get_transactions(&self) -> Result<GetTransactionsResponse> {
+        (**self).get_transactions().await
+    }
+
+    async fn get_transfers(&self) -> Result<GetTransfersResponse> {
+        (**self).get_transfers().await
+    }
+
+    async fn get_bulk_transfers(&self, batch_id: u64) -> Result<Vec<Transfer>> {
+        (**self).get_bulk_transfers(batch_id).await
+    }
+
+    async fn transfer(&self, options: TransferOptions) -> Result<TransferResponse> {
+        (**self).transfer(options).await
+    }
+
+    async fn verify_transfer(&self, reference: &str) -> Result<VerifyTransferResponse> {
+        (**self).verify_transfer(reference).await
+    }
+
+    async fn bulk_transfer(&self, options: BulkTransferOptions) -> Result<BulkTransferResponse> {
+        (**self).bulk_transfer(options).await
+    }
+
+    async fn swap(&self, options: SwapOptions) -> Result<SwapResponse> {
+        (**self).swap(options).await
+    }
+
+    async fn create_subaccount(
+        &self,
+        options: CreateSubaccountOptions,
+    ) -> Result<CreateSubaccountResponse> {
+        (**self).create_subaccount(options).await
+    }
+
+    async fn get_subaccounts(&self) -> Result<GetSubaccountsResponse> {
+        (**self).get_subaccounts().await
+    }
+
+    async fn direct_charge(
+        &self,
+        channel: PaymentChannel,
+        options: DirectChargeOptions,
+    ) -> Result<DirectChargeResponse> {
+        (**self).direct_charge(channel, options).await
+    }
+
+    async fn authorize_direct_charge(
+        &self,
+        channel: PaymentChannel,
+        options: AuthorizeDirectChargeOptions,
+    ) -> Result<AuthorizeDirectChargeResponse> {
+        (**self).authorize_direct_charge(channel, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChapaConfigBuilder;
+
+    async fn bank_count(chapa: &impl ChapaApi) -> usize {
+        let banks = chapa.get_banks().await.unwrap();
+        banks.data.unwrap_or_default().len()
+    }
+
+    #[tokio::test]
+    async fn test_dyn_chapa_api() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/v1/banks")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"message":"Banks retrieved","data":[]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = ChapaConfigBuilder::new()
+            .base_url(server.url())
+            .allow_insecure()
+            .api_key("CHASECK_TEST-XXXXXXXXXXXXXXX")
+            .build()
+            .unwrap();
+        let chapa: DynChapaApi = Arc::new(ChapaClient::from_config(config).unwrap());
+
+        assert!(chapa.get_banks().await.unwrap().data.unwrap().is_empty());
+        assert_eq!(bank_count(&chapa).await, 0);
+
+        mock.assert_async().await;
+    }
+}
@@ -0,0 +1,350 @@
+//! # Audit Module
+//!
+//! An optional, tamper-evident log of the money-moving requests sent by the client (checkouts,
+//! transfers, bulk transfers, swaps and direct charges), for compliance-focused merchants.
+//! Attach an [`AuditTrail`] with
+//! [`ChapaConfigBuilder::audit_trail`](crate::config::ChapaConfigBuilder::audit_trail).
+//!
+//! Every request is recorded as an [`AuditEntry`] once its outcome is known, carrying:
+//! - the endpoint and the merchant reference of the request;
+//! - a SHA-256 digest of the payload with the customer and account details redacted, so the
+//!   log proves what was sent without holding personal data;
+//! - the outcome: accepted, rejected with an HTTP error status, or failed with the
+//!   [error code](crate::error::ChapaError::code) of the error;
+//! - the hash of the previous entry, and its own hash over all of the above.
+//!
+//! Since each entry is chained to the previous one, editing, removing or reordering entries is
+//! detected by [`AuditTrail::verify`]. Entries are kept by an [`AuditStore`]; the
+//! [`InMemoryAuditStore`] covers a single process, implement the trait on top of an
+//! append-only table or file to keep the log across restarts.
+//!
+//! Recording is best effort: a failing store doesn't fail the request, which was already sent.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::{audit::{AuditTrail, InMemoryAuditStore}, config::ChapaConfig};
+//!
+//! # async fn run() -> chapa_rust::error::Result<()> {
+//! let trail = AuditTrail::new(InMemoryAuditStore::new());
+//! let config = ChapaConfig::builder()
+//!     .api_key("CHASECK_TEST-xxxxxxxxxxxx")
+//!     .audit_trail(trail.clone())
+//!     .build()?;
+//! // ... send payments and transfers ...
+//! trail.verify().await?;
+//! trail.export_to_file("chapa-audit.json").await?;
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    fmt::Debug,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    endpoint::Endpoint,
+    error::{ChapaError, Result},
+};
+
+/// The `previous_hash` of the first entry.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+const REDACTED: &str = "[REDACTED]";
+/// Payload fields holding customer or account details.
+const SENSITIVE_FIELDS: [&str; 7] = [
+    "first_name",
+    "last_name",
+    "email",
+    "phone_number",
+    "mobile",
+    "account_name",
+    "account_number",
+];
+
+/// A recorded money-moving request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// The position of the entry in the trail, starting at 1.
+    pub sequence: u64,
+    /// When the outcome of the request was known.
+    pub at: DateTime<Utc>,
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The path template of the endpoint, e.g. `"transfers"`.
+    pub endpoint: String,
+    /// The `tx_ref` or `reference` of the payload, if any.
+    pub reference: Option<String>,
+    /// The hex SHA-256 digest of the redacted payload.
+    pub payload_digest: String,
+    /// The outcome of the request.
+    pub outcome: AuditOutcome,
+    /// The hash of the previous entry.
+    pub previous_hash: String,
+    /// The hex SHA-256 hash of this entry, over every other field.
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Computes the hash of the entry from its other fields.
+    fn compute_hash(&self) -> String {
+        let content = serde_json::json!({
+            "sequence": self.sequence,
+            "at": self.at,
+            "method": self.method,
+            "endpoint": self.endpoint,
+            "reference": self.reference,
+            "payload_digest": self.payload_digest,
+            "outcome": self.outcome,
+            "previous_hash": self.previous_hash,
+        });
+        hex(&Sha256::digest(content.to_string().as_bytes()))
+    }
+}
+
+/// The outcome of an audited request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// Chapa accepted the request.
+    Succeeded,
+    /// Chapa answered with an HTTP error status.
+    Rejected {
+        /// The HTTP status of the response.
+        status: u16,
+    },
+    /// No valid response was received, or the request was refused before being sent.
+    Failed {
+        /// The [error code](crate::error::ChapaError::code).
+        code: String,
+    },
+}
+
+/// An append-only store of [`AuditEntry`]s.
+#[async_trait]
+pub trait AuditStore: Debug + Send + Sync {
+    /// Appends `entry` after the existing entries.
+    async fn append(&self, entry: AuditEntry) -> Result<()>;
+
+    /// Returns every entry, oldest first.
+    async fn entries(&self) -> Result<Vec<AuditEntry>>;
+
+    /// Returns the newest entry, if any.
+    async fn last(&self) -> Result<Option<AuditEntry>> {
+        Ok(self.entries().await?.pop())
+    }
+}
+
+/// An [`AuditStore`] keeping the entries in memory, for the lifetime of the process.
+///
+/// Clones share the same entries.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryAuditStore {
+    entries: Arc<Mutex<Vec<AuditEntry>>>,
+}
+
+impl InMemoryAuditStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<AuditEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl AuditStore for InMemoryAuditStore {
+    async fn append(&self, entry: AuditEntry) -> Result<()> {
+        self.lock().push(entry);
+        Ok(())
+    }
+
+    async fn entries(&self) -> Result<Vec<AuditEntry>> {
+        Ok(self.lock().clone())
+    }
+
+    async fn last(&self) -> Result<Option<AuditEntry>> {
+        Ok(self.lock().last().cloned())
+    }
+}
+
+/// Chains the audited requests of a client into an [`AuditStore`].
+///
+/// Clones share the same store, so a trail can be attached to a client and kept to export it.
+#[derive(Debug, Clone)]
+pub struct AuditTrail {
+    store: Arc<dyn AuditStore>,
+    /// Serializes appends, so each entry is chained to the one before.
+    append: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl AuditTrail {
+    /// Creates a trail recording into `store`, continuing the entries already in it.
+    pub fn new(store: impl AuditStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+            append: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// Returns every entry, oldest first.
+    pub async fn entries(&self) -> Result<Vec<AuditEntry>> {
+        self.store.entries().await
+    }
+
+    /// Checks that every entry matches its hash and is chained to the previous one.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::AuditChainBroken`] with the sequence of the first entry that was
+    /// tampered with, or the error of the store.
+    pub async fn verify(&self) -> Result<()> {
+        let mut previous_hash = GENESIS_HASH.to_string();
+        for (index, entry) in self.entries().await?.iter().enumerate() {
+            let expected = index as u64 + 1;
+            if entry.sequence != expected
+                || entry.previous_hash != previous_hash
+                || entry.hash != entry.compute_hash()
+            {
+                return Err(ChapaError::AuditChainBroken(expected));
+            }
+            previous_hash = entry.hash.clone();
+        }
+        Ok(())
+    }
+
+    /// Returns the entries as a JSON array, for auditors.
+    pub async fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.entries().await?)?)
+    }
+
+    /// Writes the entries as a JSON array to the file at `path`.
+    /// # Errors
+    /// Returns [`ChapaError::IoError`] if the file cannot be written.
+    pub async fn export_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let json = self.to_json().await?;
+        tokio::fs::write(path.as_ref(), json)
+            .await
+            .map_err(|e| ChapaError::IoError(format!("{}: {}", path.as_ref().display(), e)))
+    }
+
+    /// Appends the outcome of a request to `endpoint` with `payload`.
+    pub(crate) async fn record(
+        &self,
+        endpoint: &Endpoint<'_>,
+        payload: Option<&Value>,
+        outcome: AuditOutcome,
+    ) -> Result<()> {
+        let _guard = self.append.lock().await;
+        let last = self.store.last().await?;
+        let mut entry = AuditEntry {
+            sequence: last.as_ref().map_or(1, |last| last.sequence + 1),
+            at: Utc::now(),
+            method: endpoint.method().to_string(),
+            endpoint: endpoint.template().to_string(),
+            reference: payload
+                .and_then(|payload| payload.get("tx_ref").or_else(|| payload.get("reference")))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            payload_digest: payload_digest(payload),
+            outcome,
+            previous_hash: last.map_or_else(|| GENESIS_HASH.to_string(), |last| last.hash),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        self.store.append(entry).await
+    }
+}
+
+/// The hex SHA-256 digest of `payload` with its sensitive fields redacted.
+fn payload_digest(payload: Option<&Value>) -> String {
+    let mut payload = payload.cloned().unwrap_or(Value::Null);
+    redact(&mut payload);
+    hex(&Sha256::digest(payload.to_string().as_bytes()))
+}
+
+/// Replaces the sensitive fields of `value`, at any depth.
+pub(crate) fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if SENSITIVE_FIELDS.contains(&key.as_str()) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_audit_trail_chain() {
+        let store = InMemoryAuditStore::new();
+        let trail = AuditTrail::new(store.clone());
+        let transfer = serde_json::json!({
+            "account_name": "Abebe Bikila",
+            "account_number": "0900123456",
+            "amount": "100",
+            "currency": "ETB",
+            "reference": "payout-1",
+            "bank_code": 128
+        });
+        trail
+            .record(
+                &Endpoint::Transfer,
+                Some(&transfer),
+                AuditOutcome::Succeeded,
+            )
+            .await
+            .unwrap();
+        trail
+            .record(
+                &Endpoint::Swap,
+                None,
+                AuditOutcome::Failed {
+                    code: "timeout".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let entries = trail.entries().await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].reference.as_deref(), Some("payout-1"));
+        assert_eq!(entries[0].previous_hash, GENESIS_HASH);
+        assert_eq!(entries[1].previous_hash, entries[0].hash);
+        assert_eq!(entries[1].reference, None);
+        assert!(!trail.to_json().await.unwrap().contains("0900123456"));
+        let mut other_account = transfer.clone();
+        other_account["account_number"] = "0911000000".into();
+        assert_eq!(
+            payload_digest(Some(&other_account)),
+            entries[0].payload_digest
+        );
+        trail.verify().await.unwrap();
+
+        // tampering with an entry breaks the chain at that entry.
+        store.lock()[0].outcome = AuditOutcome::Rejected { status: 400 };
+        assert!(matches!(
+            trail.verify().await,
+            Err(ChapaError::AuditChainBroken(1))
+        ));
+    }
+}
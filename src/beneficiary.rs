@@ -0,0 +1,245 @@
+//! # Beneficiary Module
+//!
+//! A local book of payout recipients, so repeat payouts reuse the bank code, account number and
+//! account holder name instead of re-entering them.
+//!
+//! Beneficiaries are validated against the bank list returned by
+//! [`ChapaClient::get_banks`](crate::client::ChapaClient::get_banks): the bank must exist and the
+//! account number must be made of digits, with the length the bank expects. The account holder
+//! name is taken as given by the caller; nothing checks it against the bank's records.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use chapa_rust::beneficiary::{Beneficiary, BeneficiaryBook};
+//!
+//! # #[cfg(feature = "async")]
+//! # async fn run(client: chapa_rust::client::ChapaClient) {
+//! let banks = client.get_banks().await.unwrap().data.unwrap_or_default();
+//! let book = BeneficiaryBook::new();
+//! let id = book
+//!     .add(Beneficiary::new("Israel Goytom", "0900123456", 855), &banks)
+//!     .unwrap();
+//!
+//! let payout = book.get(&id).unwrap().transfer("100", "ETB", "payout-2024-06-17");
+//! client.transfer(payout).await.unwrap();
+//! # }
+//! ```
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{ChapaError, Result},
+    models::{
+        bank::Bank,
+        transfer::{BulkData, TransferOptions},
+    },
+};
+
+/// A payout recipient.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Beneficiary {
+    /// The name of the account holder, as supplied by the caller. It is not checked against the
+    /// bank's records, only required to be non-empty.
+    pub account_name: String,
+    /// The bank account number or wallet phone number.
+    pub account_number: String,
+    /// The bank code of the recipient's bank, the `id` of a [`Bank`].
+    pub bank_code: u32,
+}
+
+impl Beneficiary {
+    /// Creates a beneficiary.
+    pub fn new(
+        account_name: impl Into<String>,
+        account_number: impl Into<String>,
+        bank_code: u32,
+    ) -> Self {
+        Self {
+            account_name: account_name.into(),
+            account_number: account_number.into(),
+            bank_code,
+        }
+    }
+
+    /// The identifier of the beneficiary in a [`BeneficiaryBook`], `{bank_code}:{account_number}`.
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.bank_code, self.account_number)
+    }
+
+    /// Checks the beneficiary against the banks supported by Chapa.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidBeneficiary`] if the name is empty, the bank is unknown or the
+    /// account number isn't made of digits or doesn't have the length the bank expects.
+    pub fn validate(&self, banks: &[Bank]) -> Result<()> {
+        let invalid = |reason: String| Err(ChapaError::InvalidBeneficiary(reason));
+        if self.account_name.trim().is_empty() {
+            return invalid("the account name is empty".to_string());
+        }
+        let Some(bank) = banks.iter().find(|bank| bank.id == self.bank_code) else {
+            return invalid(format!("bank code {} is not supported", self.bank_code));
+        };
+        if self.account_number.is_empty()
+            || !self.account_number.chars().all(|c| c.is_ascii_digit())
+        {
+            return invalid(format!(
+                "account number {:?} is not made of digits",
+                self.account_number
+            ));
+        }
+        let length = self.account_number.len() as u32;
+        if bank.acct_length != 0 && length != bank.acct_length {
+            return invalid(format!(
+                "{} account numbers have {} digits, got {}",
+                bank.name, bank.acct_length, length
+            ));
+        }
+        Ok(())
+    }
+
+    /// Builds a transfer of `amount` to the beneficiary.
+    pub fn transfer(
+        &self,
+        amount: impl Into<String>,
+        currency: impl Into<String>,
+        reference: impl Into<String>,
+    ) -> TransferOptions {
+        TransferOptions {
+            account_name: self.account_name.clone(),
+            account_number: self.account_number.clone(),
+            amount: amount.into(),
+            currency: currency.into(),
+            reference: reference.into(),
+            bank_code: self.bank_code,
+            narration: None,
+        }
+    }
+
+    /// Builds a bulk transfer entry of `amount` to the beneficiary.
+    pub fn bulk_data(&self, amount: impl Into<String>, reference: impl Into<String>) -> BulkData {
+        BulkData {
+            account_name: self.account_name.clone(),
+            account_number: self.account_number.clone(),
+            amount: amount.into(),
+            reference: reference.into(),
+            bank_code: self.bank_code,
+            narration: None,
+        }
+    }
+}
+
+/// A book of validated beneficiaries, keyed by [`Beneficiary::id`].
+///
+/// Clones share the same beneficiaries.
+#[derive(Debug, Clone, Default)]
+pub struct BeneficiaryBook {
+    beneficiaries: Arc<RwLock<BTreeMap<String, Beneficiary>>>,
+}
+
+impl BeneficiaryBook {
+    /// Creates an empty book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates `beneficiary` against `banks` and stores it, replacing any beneficiary with the
+    /// same bank and account number. Returns its identifier.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidBeneficiary`] if the beneficiary doesn't pass [`Beneficiary::validate`].
+    pub fn add(&self, beneficiary: Beneficiary, banks: &[Bank]) -> Result<String> {
+        beneficiary.validate(banks)?;
+        let id = beneficiary.id();
+        self.beneficiaries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id.clone(), beneficiary);
+        Ok(id)
+    }
+
+    /// Returns the beneficiary with identifier `id`.
+    pub fn get(&self, id: &str) -> Option<Beneficiary> {
+        self.beneficiaries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(id)
+            .cloned()
+    }
+
+    /// Returns every beneficiary, ordered by identifier.
+    pub fn list(&self) -> Vec<Beneficiary> {
+        self.beneficiaries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Removes and returns the beneficiary with identifier `id`.
+    pub fn remove(&self, id: &str) -> Option<Beneficiary> {
+        self.beneficiaries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn banks() -> Vec<Bank> {
+        serde_json::from_value(serde_json::json!([{
+            "id": 855,
+            "swift": "TELEBIRR",
+            "name": "telebirr",
+            "acct_length": 10,
+            "country_id": 1,
+            "created_at": "2023-01-24T04:28:30.000000Z",
+            "updated_at": "2024-08-03T08:10:24.000000Z",
+            "is_rtgs": null,
+            "is_mobilemoney": 1,
+            "currency": "ETB"
+        }]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_beneficiary_book() {
+        let book = BeneficiaryBook::new();
+        let id = book
+            .add(
+                Beneficiary::new("Israel Goytom", "0900123456", 855),
+                &banks(),
+            )
+            .unwrap();
+        assert_eq!(id, "855:0900123456");
+
+        let unknown_bank = book.add(Beneficiary::new("Abebe", "0900123456", 1), &banks());
+        assert!(matches!(
+            unknown_bank,
+            Err(ChapaError::InvalidBeneficiary(_))
+        ));
+        let wrong_length = book.add(Beneficiary::new("Abebe", "09001", 855), &banks());
+        assert!(matches!(
+            wrong_length,
+            Err(ChapaError::InvalidBeneficiary(_))
+        ));
+        let not_digits = book.add(Beneficiary::new("Abebe", "abcdefghij", 855), &banks());
+        assert!(matches!(not_digits, Err(ChapaError::InvalidBeneficiary(_))));
+        let empty = book.add(Beneficiary::new("Abebe", "", 855), &banks());
+        assert!(matches!(empty, Err(ChapaError::InvalidBeneficiary(_))));
+        assert_eq!(book.list().len(), 1);
+
+        let transfer = book.get(&id).unwrap().transfer("100", "ETB", "payout-1");
+        assert_eq!(transfer.account_name, "Israel Goytom");
+        assert_eq!(transfer.bank_code, 855);
+        assert!(book.remove(&id).is_some());
+        assert!(book.list().is_empty());
+    }
+}
@@ -0,0 +1,354 @@
+//! # Bulk Module
+//!
+//! Execution settings and consolidated results for submitting large bulk transfers as several
+//! batches in parallel, see [`ChapaClient::bulk_transfer_parallel`](crate::client::ChapaClient::bulk_transfer_parallel).
+//!
+//! ## Example
+//! ```rust,no_run
+//! use chapa_rust::{bulk::BulkExecution, client::ChapaClient, models::transfer::BulkTransferOptions};
+//!
+//! # async fn run(client: ChapaClient, payroll: BulkTransferOptions) -> chapa_rust::error::Result<()> {
+//! let report = client
+//!     .bulk_transfer_parallel(&payroll, BulkExecution::default().concurrency(2))
+//!     .await?;
+//! for failure in &report.failed {
+//!     eprintln!("batch {} failed after {} attempts", failure.index, failure.attempts);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Resumable runs
+//! With a [`BulkProgressStore`] configured, [`ChapaClient::bulk_transfer_run`](crate::client::ChapaClient::bulk_transfer_run)
+//! records every batch before it is sent and once it is accepted. If the process stops halfway,
+//! [`ChapaClient::resume`](crate::client::ChapaClient::resume) submits only the batches that were
+//! not accepted, after checking with Chapa whether the batches in flight at the time were queued.
+//!
+//! ```rust,no_run
+//! use chapa_rust::{bulk::BulkExecution, client::ChapaClient};
+//!
+//! # async fn run(client: ChapaClient) -> chapa_rust::error::Result<()> {
+//! let report = client.resume("payroll-2024-05", BulkExecution::default()).await?;
+//! let statuses = client.verify_bulk_run("payroll-2024-05").await?;
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::{
+    error::{ChapaError, Result},
+    guardrails::parse_amount,
+    models::{
+        normalized::PaymentStatus,
+        response::BulkTransferResponse,
+        transfer::{BulkTransferOptions, TransferOutcome},
+    },
+    registry::BulkProgressStore,
+};
+
+/// Settings controlling how a bulk transfer is split and submitted.
+#[derive(Debug, Clone)]
+pub struct BulkExecution {
+    /// The maximum number of transfers per batch. default to 100, the Chapa limit.
+    pub chunk_size: usize,
+    /// How many batches may be in flight at the same time. default to 1.
+    pub concurrency: usize,
+    /// The minimum interval between two batch submissions. default to 5s, as recommended by Chapa.
+    pub batch_interval: Duration,
+    /// How many times a failed batch is retried. default to 2.
+    pub max_retries: u32,
+    /// The balance check made before the first batch is submitted, if any. default to none.
+    pub balance_check: Option<BalanceCheck>,
+}
+
+impl Default for BulkExecution {
+    fn default() -> Self {
+        Self {
+            chunk_size: 100,
+            concurrency: 1,
+            batch_interval: Duration::from_secs(5),
+            max_retries: 2,
+            balance_check: None,
+        }
+    }
+}
+
+impl BulkExecution {
+    /// Sets the maximum number of transfers per batch.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Sets how many batches may be in flight at the same time.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets the minimum interval between two batch submissions.
+    pub fn batch_interval(mut self, interval: Duration) -> Self {
+        self.batch_interval = interval;
+        self
+    }
+
+    /// Sets how many times a failed batch is retried.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Checks the balance before submitting, see [`BalanceCheck`].
+    pub fn check_balance(mut self, check: BalanceCheck) -> Self {
+        self.balance_check = Some(check);
+        self
+    }
+}
+
+/// Checks that the available balance covers a bulk transfer before any batch is submitted, so
+/// an underfunded payroll fails fast with [`ChapaError::InsufficientLocalBalanceCheck`] instead
+/// of being partially queued and bouncing.
+///
+/// Chapa doesn't quote the charges of a transfer in advance, so they are estimated from a rate
+/// and a flat fee per transfer; both default to zero. The check is made with
+/// [`ChapaClient::check_balance_for`](crate::client::ChapaClient::check_balance_for), or before
+/// every bulk transfer when set on the [`BulkExecution`] or the configuration (see
+/// [`ChapaConfigBuilder::balance_check`](crate::config::ChapaConfigBuilder::balance_check)).
+///
+/// ## Example
+/// ```rust
+/// use chapa_rust::bulk::{BalanceCheck, BulkExecution};
+///
+/// let execution = BulkExecution::default()
+///     .check_balance(BalanceCheck::new().charge_rate(0.01).charge_per_transfer(5.0));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BalanceCheck {
+    /// The estimated charges, as a fraction of each amount (e.g. `0.01` for 1%).
+    pub charge_rate: f64,
+    /// The estimated flat charge of each transfer.
+    pub charge_per_transfer: f64,
+}
+
+impl BalanceCheck {
+    /// Creates a balance check without estimated charges.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the estimated charges, as a fraction of each amount.
+    pub fn charge_rate(mut self, rate: f64) -> Self {
+        self.charge_rate = rate;
+        self
+    }
+
+    /// Sets the estimated flat charge of each transfer.
+    pub fn charge_per_transfer(mut self, charge: f64) -> Self {
+        self.charge_per_transfer = charge;
+        self
+    }
+
+    /// The balance needed by `batches`: their total plus the estimated charges.
+    pub fn required<'a>(&self, batches: impl IntoIterator<Item = &'a BulkTransferOptions>) -> f64 {
+        batches
+            .into_iter()
+            .flat_map(|batch| &batch.bulk_data)
+            .map(|transfer| {
+                let amount = parse_amount(&transfer.amount);
+                amount + amount * self.charge_rate + self.charge_per_transfer
+            })
+            .sum()
+    }
+
+    /// Compares the balance needed by `batches` with the `available` balance in `currency`.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InsufficientLocalBalanceCheck`] with the shortfall if the balance
+    /// doesn't cover the batches.
+    pub fn check<'a>(
+        &self,
+        batches: impl IntoIterator<Item = &'a BulkTransferOptions>,
+        currency: &str,
+        available: f64,
+    ) -> Result<()> {
+        let required = self.required(batches);
+        if required <= available {
+            return Ok(());
+        }
+        Err(ChapaError::InsufficientLocalBalanceCheck {
+            currency: currency.to_string(),
+            required,
+            available,
+            shortfall: required - available,
+        })
+    }
+}
+
+/// The consolidated result of a parallel bulk transfer.
+#[derive(Debug)]
+pub struct BulkTransferReport {
+    /// The batches accepted by Chapa, ordered by batch index.
+    pub succeeded: Vec<BatchSuccess>,
+    /// The batches that still failed after all retries, ordered by batch index.
+    pub failed: Vec<BatchFailure>,
+}
+
+impl BulkTransferReport {
+    /// Returns `true` if every batch was accepted.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// A batch accepted by Chapa.
+#[derive(Debug)]
+pub struct BatchSuccess {
+    /// The position of the batch in the split bulk transfer.
+    pub index: usize,
+    /// The submitted batch.
+    pub batch: BulkTransferOptions,
+    /// How many submissions were needed.
+    pub attempts: u32,
+    /// The response of the accepted submission, `None` if an attempt failed without an answer
+    /// (e.g. it timed out) and the batch was then found queued with Chapa.
+    pub response: Option<BulkTransferResponse>,
+}
+
+/// A batch that failed on every attempt.
+#[derive(Debug)]
+pub struct BatchFailure {
+    /// The position of the batch in the split bulk transfer.
+    pub index: usize,
+    /// The submitted batch.
+    pub batch: BulkTransferOptions,
+    /// How many submissions were made.
+    pub attempts: u32,
+    /// Why the last attempt failed.
+    pub error: BatchError,
+}
+
+/// Why a batch submission failed.
+#[derive(Debug)]
+pub enum BatchError {
+    /// Chapa answered with a non-success status.
+    Rejected(BulkTransferResponse),
+    /// The request itself failed.
+    Request(ChapaError),
+}
+
+/// The settlement status of a single transfer of a bulk transfer batch, see
+/// [`ChapaClient::verify_bulk_transfer`](crate::client::ChapaClient::verify_bulk_transfer).
+///
+/// The status of a found transfer is converted from its [`TransferOutcome`], a cancellation or
+/// reversal counting as [`PaymentStatus::Failed`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkEntryStatus {
+    /// The transfer belongs to the batch, with its status.
+    Found(PaymentStatus),
+    /// No transfer with this reference belongs to the batch.
+    NotFound,
+}
+
+impl BulkEntryStatus {
+    /// Returns `true` once the transfer reached a final state (paid out or failed).
+    pub fn is_final(&self) -> bool {
+        matches!(self, BulkEntryStatus::Found(status) if status.is_terminal())
+    }
+}
+
+impl From<TransferOutcome> for BulkEntryStatus {
+    fn from(outcome: TransferOutcome) -> Self {
+        BulkEntryStatus::Found(match outcome {
+            TransferOutcome::Success { .. } => PaymentStatus::Success,
+            TransferOutcome::Failed { .. } | TransferOutcome::Cancelled => PaymentStatus::Failed,
+            TransferOutcome::Pending => PaymentStatus::Pending,
+            TransferOutcome::Unknown { status } => PaymentStatus::Other(status),
+        })
+    }
+}
+
+/// The recorded progress of a bulk transfer run, see [`BulkProgressStore`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkRun {
+    /// The identifier of the run, chosen by the caller (e.g. the payroll run id).
+    pub run_id: String,
+    /// The full bulk transfer of the run.
+    pub options: BulkTransferOptions,
+    /// The maximum number of transfers per batch the run was split with.
+    pub chunk_size: usize,
+    /// The progress of every batch sent at least once, by batch index.
+    pub batches: BTreeMap<usize, BatchProgress>,
+    /// The final status of every transfer settled so far, by reference.
+    pub settled: BTreeMap<String, BulkEntryStatus>,
+}
+
+impl BulkRun {
+    /// Creates the progress of a run that hasn't sent any batch yet.
+    pub fn new(run_id: impl Into<String>, options: BulkTransferOptions, chunk_size: usize) -> Self {
+        Self {
+            run_id: run_id.into(),
+            options,
+            chunk_size: chunk_size.max(1),
+            batches: BTreeMap::new(),
+            settled: BTreeMap::new(),
+        }
+    }
+
+    /// The batches of the run, as split by [`BulkTransferOptions::chunks`].
+    pub fn chunks(&self) -> Vec<BulkTransferOptions> {
+        self.options.chunks(self.chunk_size)
+    }
+
+    /// Returns `true` once every batch of the run was accepted by Chapa.
+    pub fn is_complete(&self) -> bool {
+        let total = self.options.bulk_data.len().div_ceil(self.chunk_size);
+        (0..total).all(|index| {
+            matches!(
+                self.batches.get(&index),
+                Some(BatchProgress::Accepted { .. })
+            )
+        })
+    }
+}
+
+/// The progress of a single batch of a bulk transfer run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BatchProgress {
+    /// The batch was sent, but no answer was recorded: Chapa may or may not have queued it.
+    Submitting,
+    /// The batch was accepted by Chapa.
+    Accepted {
+        /// The identifier of the queued batch, unknown if acceptance was recovered on resume.
+        batch_id: Option<u64>,
+    },
+}
+
+/// A bulk transfer run being submitted, recording each change to its store.
+pub(crate) struct RunProgress<'a> {
+    store: &'a dyn BulkProgressStore,
+    run: Mutex<BulkRun>,
+}
+
+impl<'a> RunProgress<'a> {
+    pub(crate) fn new(store: &'a dyn BulkProgressStore, run: BulkRun) -> Self {
+        Self {
+            store,
+            run: Mutex::new(run),
+        }
+    }
+
+    /// Records the progress of the batch at `index`, or forgets it when `progress` is `None`.
+    pub(crate) async fn record(&self, index: usize, progress: Option<BatchProgress>) -> Result<()> {
+        let mut run = self.run.lock().await;
+        match progress {
+            Some(progress) => run.batches.insert(index, progress),
+            None => run.batches.remove(&index),
+        };
+        self.store.save(&run).await
+    }
+}
@@ -13,11 +13,18 @@
 //!
 //! ## Features
 //!
-//! - **API Key**: Required for authentication with the Chapa API.
-//! - **Base URL**: The endpoint for the Chapa API, defaulting to `https://api.chapa.co`.
+//! - **API Key**: Required for authentication with the Chapa API. It must start with one of the
+//!   Chapa prefixes (`CHASECK-`, `CHASECK_TEST-`, `CHAPUBK-`, `CHAPUBK_TEST-`), which also determines the
+//!   [`Environment`] (live or test) the configuration targets.
+//! - **Environment**: Optionally pins the configuration to [`Environment::Live`] or [`Environment::Test`],
+//!   selecting the matching base URL and rejecting keys from the other environment.
+//! - **Base URL**: The endpoint for the Chapa API, defaulting to `https://api.chapa.co`. Plain `http://`
+//!   URLs are rejected unless [`ChapaConfigBuilder::allow_insecure`] is set, e.g. for local mock servers.
 //! - **Version**: The version of the API to use, defaulting to `v1`.
 //! - **Default Headers**: Headers included in every API request, such as `Content-Type`.
 //! - **Timeout**: Request timeout duration, defaulting to 30 seconds.
+//! - **Rate Limit**: Optional minimum interval between two requests of a client.
+//! - **TLS**: Minimum accepted TLS protocol version, defaulting to TLS 1.2, and extra trusted root certificates.
 //!
 //! ## Example Usage
 //!
@@ -36,8 +43,9 @@
 //! // Customizing the configuration
 //! let custom_config = ChapaConfig::builder()
 //!     .base_url("http://localhost:8080/dev")
+//!     .allow_insecure() // needed for the plain http:// local URL
 //!     .timeout(Duration::from_secs(10))
-//!     .api_key("my-custom-api-key")
+//!     .api_key("CHASECK_TEST-my-custom-api-key")
 //!     .add_header("X-Custom-Header", "CustomValue")
 //!     .build()
 //!     .expect("Failed to build custom config");
@@ -47,17 +55,66 @@
 //!
 //! ```
 //!
+//! With the `dotenv` feature enabled, [`ChapaConfig::from_env`] loads a `.env` file first, so the
+//! manual `dotenvy::dotenv()` call above isn't needed:
+//!
+//! ```rust,no_run
+//! use chapa_rust::config::ChapaConfig;
+//!
+//! let config = ChapaConfig::from_env().expect("CHAPA_SECRET_KEY must be set");
+//! ```
+//!
 //! ## Notes
 //!
-//! - The API key can be set using the `CHAPA_API_PUBLIC_KEY` environment variable.
+//! - The API key can be set using the `CHAPA_SECRET_KEY` environment variable (the older
+//!   `CHAPA_API_PUBLIC_KEY` name is still read when it isn't set).
+//! - The webhook secret can be set using the `CHAPA_WEBHOOK_SECRET` environment variable.
+//! - [`ChapaConfig::from_env`] also reads `CHAPA_BASE_URL`, `CHAPA_VERSION` and
+//!   `CHAPA_TIMEOUT_SECS`.
 //! - If the API key is not provided, a placeholder value will be used, and an error will be returned
 //!   when attempting to build the configuration.
+//! - Keys that don't start with a known Chapa prefix are rejected with [`ChapaError::InvalidApiKey`].
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
+#[cfg(feature = "async")]
+use crate::audit::AuditTrail;
+#[cfg(feature = "async")]
+use crate::bulk::BalanceCheck;
 use crate::error::{ChapaError, Result};
+use crate::exchange::ExchangeRates;
+use crate::failover::BaseUrlPool;
+use crate::guardrails::Guardrails;
+#[cfg(feature = "har")]
+use crate::har::HarRecorder;
+use crate::models::bank::Country;
+#[cfg(feature = "async")]
+use crate::rate_limit::RateLimiter;
+#[cfg(feature = "async")]
+use crate::registry::{
+    BulkProgressStore, CheckoutStore, ExportCheckpointStore, ReferenceRegistry, TxRefRegistry,
+};
+use crate::rules::AmountRules;
+use crate::serializer::BodySerializer;
+#[cfg(feature = "async")]
+use crate::transport::Transport;
+#[cfg(feature = "async")]
+use crate::webhook::{ReplayGuard, WebhookObserver};
 
 const PLACEHOLDER_API_KEY: &str = "placeholder_api_key";
+const DEFAULT_BASE_URL: &str = "https://api.chapa.co";
+const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_LIST_RESPONSE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Known Chapa key prefixes and the environment they belong to.
+/// The test prefixes must come first since the live ones are prefixes of them.
+const API_KEY_PREFIXES: [(&str, Environment); 4] = [
+    ("CHASECK_TEST-", Environment::Test),
+    ("CHAPUBK_TEST-", Environment::Test),
+    ("CHASECK-", Environment::Live),
+    ("CHAPUBK-", Environment::Live),
+];
 
 /// The `ChapaConfig` struct provides a centralized configuration mechanism for
 /// interacting with an external API. It encapsulates essential settings such as
@@ -69,12 +126,164 @@ pub struct ChapaConfig {
     pub api_key: String,
     /// The base URL for the external API, usually it's `https://api.chapa.co`
     pub base_url: String,
+    /// The base URLs requests are spread over and failed over to, if any. `base_url` is then the
+    /// first URL of the pool.
+    pub base_url_pool: Option<BaseUrlPool>,
     /// The version of the API to be used.
     pub version: String,
     /// Default headers to be included in every API request.
     pub default_headers: HashMap<String, String>,
     /// Request timeout duration. default to 30s.
     pub timeout: Duration,
+    /// The minimum TLS protocol version accepted when connecting to the API. default to TLS 1.2.
+    pub min_tls_version: TlsVersion,
+    /// Extra root certificates trusted in addition to the system ones (e.g. corporate proxies).
+    #[cfg(feature = "async")]
+    pub root_certificates: Vec<reqwest::Certificate>,
+    /// The PEM encoding of `root_certificates`, as added, for the blocking client.
+    #[cfg(feature = "ureq")]
+    pub root_certificate_pems: Vec<Vec<u8>>,
+    /// Whether plain `http://` base URLs are allowed. default to false.
+    pub allow_insecure: bool,
+    /// The environment (live or test) inferred from the API key prefix.
+    pub environment: Environment,
+    /// The country the banks are filtered by, unless another one is given, if any.
+    pub country: Option<Country>,
+    /// Whether responses keep a copy of the original JSON payload. default to false.
+    pub retain_raw_response: bool,
+    /// Whether the customization logo is fetched with a `HEAD` request before a checkout is
+    /// initialized. default to false.
+    pub check_logo: bool,
+    /// Whether responses with an HTTP error status are returned as [`ChapaError::ApiError`]
+    /// instead of being decoded. default to false.
+    pub error_on_status: bool,
+    /// Client-side limiter applied before every request, if any.
+    #[cfg(feature = "async")]
+    pub rate_limiter: Option<RateLimiter>,
+    /// How many times a rate limited (`429`) request is sent again after waiting for its
+    /// `Retry-After` delay. default to 0.
+    pub rate_limit_retries: u32,
+    /// The longest `Retry-After` delay waited for before sending a rate limited request again.
+    /// default to 60s.
+    pub max_retry_after: Duration,
+    /// Hook serializing the JSON request bodies, instead of `serde_json`, if any.
+    pub body_serializer: Option<Arc<dyn BodySerializer>>,
+    /// The maximum size, in bytes, of a list response (transactions, transfers). default to 16 MiB.
+    pub max_list_response_size: usize,
+    /// The minimum amounts checked before charges and transfers are sent. default to Chapa's minimums.
+    pub amount_rules: AmountRules,
+    /// The upper limits on the amounts moved by the client, if any.
+    pub guardrails: Option<Guardrails>,
+    /// The balance check made before every bulk transfer, unless its execution sets its own, if
+    /// any.
+    #[cfg(feature = "async")]
+    pub balance_check: Option<BalanceCheck>,
+    /// The cache of exchange rates observed on swaps.
+    pub exchange_rates: ExchangeRates,
+    /// Registry of submitted transfer references, used to refuse duplicate payouts.
+    #[cfg(feature = "async")]
+    pub transfer_registry: Option<Arc<dyn ReferenceRegistry>>,
+    /// Registry of initialized transaction references, used to refuse duplicate checkouts.
+    #[cfg(feature = "async")]
+    pub tx_ref_registry: Option<Arc<dyn ReferenceRegistry>>,
+    /// Store of the checkout URLs of initialized transactions, used to reuse pending checkouts.
+    #[cfg(feature = "async")]
+    pub checkout_store: Option<Arc<dyn CheckoutStore>>,
+    /// Store of the progress of bulk transfer runs, used to resume interrupted runs.
+    #[cfg(feature = "async")]
+    pub bulk_progress_store: Option<Arc<dyn BulkProgressStore>>,
+    /// Store of the checkpoints of export jobs, used to resume interrupted exports.
+    #[cfg(feature = "async")]
+    pub export_checkpoint_store: Option<Arc<dyn ExportCheckpointStore>>,
+    /// The tamper-evident log of the money-moving requests, if any.
+    #[cfg(feature = "async")]
+    pub audit_trail: Option<AuditTrail>,
+    /// The transport sending the requests instead of the HTTP client, if any.
+    #[cfg(feature = "async")]
+    pub transport: Option<Arc<dyn Transport>>,
+    /// The secret set in the Chapa dashboard to sign webhooks, if webhooks are received.
+    pub webhook_secret: Option<String>,
+    /// Replay protection checked by the webhook extractors, if any.
+    #[cfg(feature = "async")]
+    pub webhook_replay_guard: Option<ReplayGuard>,
+    /// Whether webhooks signed only with the `Chapa-Signature` header, which doesn't cover the
+    /// payload, are accepted. default to `false`.
+    pub accept_secret_signature: bool,
+    /// The observer notified of every stage of processing a webhook by the extractors, if any.
+    #[cfg(feature = "async")]
+    pub webhook_observer: Option<Arc<dyn WebhookObserver>>,
+    /// Recorder capturing the request/response pairs in HAR format, for debugging.
+    #[cfg(feature = "har")]
+    pub har_recorder: Option<HarRecorder>,
+    /// Whether request and response bodies are logged at debug level. default to `false`.
+    #[cfg(feature = "logging")]
+    pub log_bodies: bool,
+}
+
+/// The Chapa environment a configuration targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Environment {
+    /// Production environment, real money is moved. Keys start with `CHASECK-` or `CHAPUBK-`.
+    Live,
+    /// Test (sandbox) environment. Keys start with `CHASECK_TEST-` or `CHAPUBK_TEST-`.
+    Test,
+}
+
+impl Environment {
+    /// The base URL serving this environment.
+    ///
+    /// Chapa currently serves live and test traffic from the same host; the key decides which
+    /// environment a request is executed in.
+    pub fn base_url(&self) -> &'static str {
+        match self {
+            Environment::Live => DEFAULT_BASE_URL,
+            Environment::Test => DEFAULT_BASE_URL,
+        }
+    }
+
+    /// Returns `true` for the live environment, where real money is moved.
+    pub fn is_live(&self) -> bool {
+        *self == Environment::Live
+    }
+
+    /// Infers the environment from the prefix of a Chapa API key.
+    /// # Errors
+    /// Returns [`ChapaError::InvalidApiKey`] if the key doesn't start with a known Chapa prefix.
+    pub fn from_api_key(key: &str) -> Result<Self> {
+        API_KEY_PREFIXES
+            .iter()
+            .find(|(prefix, _)| key.starts_with(prefix) && key.len() > prefix.len())
+            .map(|(_, environment)| *environment)
+            .ok_or_else(|| {
+                ChapaError::InvalidApiKey(
+                    "expected a key starting with CHASECK-, CHASECK_TEST-, CHAPUBK- or CHAPUBK_TEST-"
+                        .to_string(),
+                )
+            })
+    }
+}
+
+/// Minimum TLS protocol versions that can be enforced on connections to the Chapa API.
+///
+/// Cipher suites are not configurable individually; the selected TLS backend only
+/// negotiates its secure default suites for the chosen protocol versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TlsVersion {
+    /// TLS 1.2, the minimum required by PCI DSS.
+    #[default]
+    Tls1_2,
+    /// TLS 1.3. The default `native-tls` backend can not enforce it, enable the `rustls` feature.
+    Tls1_3,
+}
+
+#[cfg(feature = "async")]
+impl From<TlsVersion> for reqwest::tls::Version {
+    fn from(version: TlsVersion) -> Self {
+        match version {
+            TlsVersion::Tls1_2 => reqwest::tls::Version::TLS_1_2,
+            TlsVersion::Tls1_3 => reqwest::tls::Version::TLS_1_3,
+        }
+    }
 }
 
 impl ChapaConfig {
@@ -82,6 +291,89 @@ impl ChapaConfig {
     pub fn builder() -> ChapaConfigBuilder {
         ChapaConfigBuilder::new()
     }
+
+    /// The mode (live or test) of the API key, checked when the configuration was built.
+    ///
+    /// Applications can guard operations moving money against running live by accident:
+    /// ```rust
+    /// use chapa_rust::config::ChapaConfig;
+    ///
+    /// let config = ChapaConfig::builder()
+    ///     .api_key("CHASECK_TEST-xxxxxxxx")
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(!config.mode().is_live(), "refusing to run the payout script live");
+    /// ```
+    pub fn mode(&self) -> Environment {
+        self.environment
+    }
+
+    /// Builds a configuration from environment variables, the other settings keeping their
+    /// defaults:
+    /// - `CHAPA_SECRET_KEY`, the API key, falling back to `CHAPA_API_PUBLIC_KEY`;
+    /// - `CHAPA_BASE_URL`, the base URL of the API;
+    /// - `CHAPA_VERSION`, the API version, e.g. `v1`;
+    /// - `CHAPA_TIMEOUT_SECS`, the request timeout as a whole number of seconds;
+    /// - `CHAPA_WEBHOOK_SECRET`, the webhook secret.
+    ///
+    /// When the `dotenv` feature is enabled, a `.env` file in the current directory (or any parent)
+    /// is loaded first. Variables already set in the environment take precedence over the file.
+    /// # Errors
+    /// Returns [`ChapaError::InvalidEnvVar`] if a variable is set to a malformed value (empty,
+    /// or a timeout that isn't a positive number of seconds), or the errors of
+    /// [`ChapaConfigBuilder::build`], e.g. if the API key is missing or invalid or the base URL
+    /// cannot be parsed.
+    pub fn from_env() -> Result<ChapaConfig> {
+        #[cfg(feature = "dotenv")]
+        dotenvy::dotenv().ok();
+
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Builds a configuration from the variables returned by `var`, see
+    /// [`ChapaConfig::from_env`].
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<ChapaConfig> {
+        let read = |name: &str| -> Result<Option<String>> {
+            match var(name) {
+                Some(value) if value.trim().is_empty() => Err(ChapaError::InvalidEnvVar {
+                    name: name.to_string(),
+                    reason: "the value is empty".to_string(),
+                }),
+                value => Ok(value.map(|value| value.trim().to_string())),
+            }
+        };
+
+        let mut builder = ChapaConfigBuilder::new();
+        // the legacy name is only read when the secret key isn't set.
+        let api_key = match read("CHAPA_SECRET_KEY")? {
+            Some(api_key) => Some(api_key),
+            None => read("CHAPA_API_PUBLIC_KEY")?,
+        };
+        if let Some(api_key) = api_key {
+            builder = builder.api_key(api_key);
+        }
+        if let Some(base_url) = read("CHAPA_BASE_URL")? {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(version) = read("CHAPA_VERSION")? {
+            builder = builder.version(version);
+        }
+        if let Some(timeout) = read("CHAPA_TIMEOUT_SECS")? {
+            let seconds = timeout
+                .parse::<u64>()
+                .ok()
+                .filter(|seconds| *seconds > 0)
+                .ok_or_else(|| ChapaError::InvalidEnvVar {
+                    name: "CHAPA_TIMEOUT_SECS".to_string(),
+                    reason: format!("{:?} is not a positive number of seconds", timeout),
+                })?;
+            builder = builder.timeout(Duration::from_secs(seconds));
+        }
+        if let Some(webhook_secret) = read("CHAPA_WEBHOOK_SECRET")? {
+            builder = builder.webhook_secret(webhook_secret);
+        }
+        builder.build()
+    }
 }
 
 /// The `ChapaConfigBuilder` struct implements the builder pattern for
@@ -94,12 +386,87 @@ pub struct ChapaConfigBuilder {
     api_key: Option<String>,
     /// The base URL for the external API, usually it's `https://api.chapa.co`
     base_url: Option<String>,
+    /// The base URLs requests are spread over and failed over to.
+    base_url_pool: Option<BaseUrlPool>,
     /// The version of the API to be used. default is `v1`.
     version: Option<String>,
     /// Default headers to be included in every API request.
     default_headers: HashMap<String, String>,
     /// Request timeout duration. default to 30s.
     timeout: Option<Duration>,
+    /// The minimum TLS protocol version. default to TLS 1.2.
+    min_tls_version: Option<TlsVersion>,
+    /// PEM encoded root certificates to trust in addition to the system ones.
+    root_certificates: Vec<Vec<u8>>,
+    /// Whether plain `http://` base URLs are allowed. default to false.
+    allow_insecure: bool,
+    /// The environment the key must belong to, if pinned.
+    environment: Option<Environment>,
+    /// The default country of the banks, if any.
+    country: Option<Country>,
+    /// Whether responses keep a copy of the original JSON payload. default to false.
+    retain_raw_response: bool,
+    /// Whether the customization logo is checked with a `HEAD` request. default to false.
+    check_logo: bool,
+    /// Whether responses with an HTTP error status are returned as errors. default to false.
+    error_on_status: bool,
+    /// Client-side limiter applied before every request, if any.
+    #[cfg(feature = "async")]
+    rate_limiter: Option<RateLimiter>,
+    /// How many times a rate limited request is sent again. default to 0.
+    rate_limit_retries: u32,
+    /// The longest `Retry-After` delay waited for. default to 60s.
+    max_retry_after: Duration,
+    /// Hook serializing the JSON request bodies.
+    body_serializer: Option<Arc<dyn BodySerializer>>,
+    /// The maximum size, in bytes, of a list response. default to 16 MiB.
+    max_list_response_size: Option<usize>,
+    /// The minimum amounts checked before charges and transfers are sent.
+    amount_rules: AmountRules,
+    /// The upper limits on the amounts moved by the client.
+    guardrails: Option<Guardrails>,
+    /// The balance check made before bulk transfers.
+    #[cfg(feature = "async")]
+    balance_check: Option<BalanceCheck>,
+    /// The cache of exchange rates observed on swaps.
+    exchange_rates: ExchangeRates,
+    /// Registry of submitted transfer references.
+    #[cfg(feature = "async")]
+    transfer_registry: Option<Arc<dyn ReferenceRegistry>>,
+    /// Registry of initialized transaction references.
+    #[cfg(feature = "async")]
+    tx_ref_registry: Option<Arc<dyn ReferenceRegistry>>,
+    /// Store of the checkout URLs of initialized transactions.
+    #[cfg(feature = "async")]
+    checkout_store: Option<Arc<dyn CheckoutStore>>,
+    /// Store of the progress of bulk transfer runs.
+    #[cfg(feature = "async")]
+    bulk_progress_store: Option<Arc<dyn BulkProgressStore>>,
+    /// Store of the checkpoints of export jobs.
+    #[cfg(feature = "async")]
+    export_checkpoint_store: Option<Arc<dyn ExportCheckpointStore>>,
+    /// The tamper-evident log of the money-moving requests.
+    #[cfg(feature = "async")]
+    audit_trail: Option<AuditTrail>,
+    /// The transport sending the requests instead of the HTTP client.
+    #[cfg(feature = "async")]
+    transport: Option<Arc<dyn Transport>>,
+    /// The secret signing webhooks.
+    webhook_secret: Option<String>,
+    /// Replay protection for webhooks.
+    #[cfg(feature = "async")]
+    webhook_replay_guard: Option<ReplayGuard>,
+    /// Whether webhooks signed only with the secret signature are accepted.
+    accept_secret_signature: bool,
+    /// The observer of webhook outcomes.
+    #[cfg(feature = "async")]
+    webhook_observer: Option<Arc<dyn WebhookObserver>>,
+    /// Recorder capturing the request/response pairs in HAR format.
+    #[cfg(feature = "har")]
+    har_recorder: Option<HarRecorder>,
+    /// Whether request and response bodies are logged.
+    #[cfg(feature = "logging")]
+    log_bodies: bool,
 }
 
 impl ChapaConfigBuilder {
@@ -108,12 +475,27 @@ impl ChapaConfigBuilder {
         Self::default()
     }
 
-    /// Sets a custom base URL for the API.
+    /// Pins the configuration to an environment. Unless a custom base URL is set, the
+    /// environment's base URL is used, and `build()` fails if the API key belongs to another environment.
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Sets a custom base URL for the API. Takes precedence over the environment's base URL.
     pub fn base_url(mut self, url: impl Into<String>) -> Self {
         self.base_url = Some(url.into());
         self
     }
 
+    /// Spreads the requests over several base URLs, with weights and a failover order, see the
+    /// [`failover`](crate::failover) module. Replaces the base URL set with
+    /// [`ChapaConfigBuilder::base_url`].
+    pub fn base_url_pool(mut self, pool: BaseUrlPool) -> Self {
+        self.base_url_pool = Some(pool);
+        self
+    }
+
     /// Sets the API version.
     pub fn version(mut self, version: impl Into<String>) -> Self {
         self.version = Some(version.into());
@@ -126,12 +508,234 @@ impl ChapaConfigBuilder {
         self
     }
 
+    /// Sets the secret used to verify the signature of webhooks, as set in the Chapa dashboard.
+    /// default to the `CHAPA_WEBHOOK_SECRET` environment variable, if set.
+    pub fn webhook_secret(mut self, secret: impl Into<String>) -> Self {
+        self.webhook_secret = Some(secret.into());
+        self
+    }
+
+    /// Sets the replay protection checked on webhooks, after their signature, by
+    /// [`ChapaClient::check_webhook_replay`](crate::client::ChapaClient::check_webhook_replay)
+    /// and the webhook extractors.
+    #[cfg(feature = "async")]
+    pub fn webhook_replay_guard(mut self, guard: ReplayGuard) -> Self {
+        self.webhook_replay_guard = Some(guard);
+        self
+    }
+
+    /// Accepts webhooks carrying only the `Chapa-Signature` header, the HMAC of the webhook
+    /// secret keyed with itself.
+    ///
+    /// That signature is the same for every delivery and does **not** authenticate the
+    /// payload: anyone who saw one delivery can forge others. Only enable this for accounts
+    /// whose deliveries lack the `x-chapa-signature` header, and verify every event with Chapa
+    /// (e.g. [`ChapaClient::cross_check_webhook`](crate::client::ChapaClient::cross_check_webhook))
+    /// before acting on it.
+    pub fn accept_secret_signature(mut self, enabled: bool) -> Self {
+        self.accept_secret_signature = enabled;
+        self
+    }
+
+    /// Sets the observer notified of every [`WebhookOutcome`](crate::webhook::WebhookOutcome)
+    /// of the deliveries processed by the webhook extractors, e.g.
+    /// [`WebhookCounters`](crate::webhook::WebhookCounters).
+    #[cfg(feature = "async")]
+    pub fn webhook_observer(mut self, observer: impl WebhookObserver + 'static) -> Self {
+        self.webhook_observer = Some(Arc::new(observer));
+        self
+    }
+
     /// Sets the request timeout duration.
     pub fn timeout(mut self, duration: Duration) -> Self {
         self.timeout = Some(duration);
         self
     }
 
+    /// Allows plain `http://` base URLs, which send the secret key unencrypted.
+    /// Only intended for local testing against mock servers (e.g. `mockito`).
+    pub fn allow_insecure(mut self) -> Self {
+        self.allow_insecure = true;
+        self
+    }
+
+    /// Keeps the original JSON payload on every response (see [`ChapaResponse::raw`](crate::models::response::ChapaResponse::raw)),
+    /// e.g. to archive exact payloads for audit or compliance.
+    pub fn retain_raw_response(mut self, retain: bool) -> Self {
+        self.retain_raw_response = retain;
+        self
+    }
+
+    /// Fetches the customization logo of every checkout with a `HEAD` request before
+    /// initializing it, refusing logos that are unreachable or not served as an allowed image
+    /// type (see [`Customization::LOGO_TYPES`](crate::models::payment::Customization::LOGO_TYPES)),
+    /// so checkouts don't render with broken branding.
+    pub fn check_logo(mut self, enabled: bool) -> Self {
+        self.check_logo = enabled;
+        self
+    }
+
+    /// Returns the responses with an HTTP error status (`4xx` and `5xx`) as
+    /// [`ChapaError::ApiError`], with the status, message and body of the response, so callers
+    /// can use `?` instead of checking `status == "failed"`. By default, such responses are
+    /// decoded like successful ones.
+    pub fn error_on_status(mut self, enabled: bool) -> Self {
+        self.error_on_status = enabled;
+        self
+    }
+
+    /// Sets the country the banks are filtered by by default, see
+    /// [`ChapaClient::get_banks_in`](crate::client::ChapaClient::get_banks_in).
+    pub fn country(mut self, country: Country) -> Self {
+        self.country = Some(country);
+        self
+    }
+
+    /// Sends a request refused with a `429` again, up to `retries` times, after waiting for the
+    /// delay of its `Retry-After` header. Requests without the header, or asking to wait longer
+    /// than `max_wait`, fail with [`ChapaError::RateLimited`] right away, as do all rate limited
+    /// requests by default.
+    pub fn retry_rate_limited(mut self, retries: u32, max_wait: Duration) -> Self {
+        self.rate_limit_retries = retries;
+        self.max_retry_after = max_wait;
+        self
+    }
+
+    /// Spaces out the requests of the client by at least `min_interval`, so bursts (e.g. parallel
+    /// bulk transfer batches) stay within Chapa's rate limits.
+    #[cfg(feature = "async")]
+    pub fn rate_limit(mut self, min_interval: Duration) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(min_interval));
+        self
+    }
+
+    /// Sets the hook serializing the JSON request bodies, see the
+    /// [`serializer`](crate::serializer) module.
+    pub fn body_serializer(mut self, serializer: impl BodySerializer + 'static) -> Self {
+        self.body_serializer = Some(Arc::new(serializer));
+        self
+    }
+
+    /// Sets the maximum size, in bytes, of a list response (transactions, transfers). List
+    /// responses are decoded while they are downloaded (gathered first with the `simd-json`
+    /// feature) and the download is aborted with [`ChapaError::ResponseTooLarge`] once the limit is exceeded.
+    pub fn max_list_response_size(mut self, bytes: usize) -> Self {
+        self.max_list_response_size = Some(bytes);
+        self
+    }
+
+    /// Sets the minimum amounts checked before charges and transfers are sent, see [`AmountRules`].
+    pub fn amount_rules(mut self, rules: AmountRules) -> Self {
+        self.amount_rules = rules;
+        self
+    }
+
+    /// Sets the upper limits on the amounts moved by the client, see [`Guardrails`].
+    pub fn guardrails(mut self, guardrails: Guardrails) -> Self {
+        self.guardrails = Some(guardrails);
+        self
+    }
+
+    /// Checks the balance before every bulk transfer, see [`BalanceCheck`]. The balance check of
+    /// a [`BulkExecution`](crate::bulk::BulkExecution) takes precedence over this one.
+    #[cfg(feature = "async")]
+    pub fn balance_check(mut self, check: BalanceCheck) -> Self {
+        self.balance_check = Some(check);
+        self
+    }
+
+    /// Sets the cache the exchange rates observed on swaps are recorded in, e.g. to share it
+    /// between clients or to change when rates are considered stale.
+    pub fn exchange_rates(mut self, rates: ExchangeRates) -> Self {
+        self.exchange_rates = rates;
+        self
+    }
+
+    /// Sets the registry remembering submitted transfer references. Transfers reusing a reference
+    /// already in the registry are refused locally with [`ChapaError::DuplicateReference`].
+    #[cfg(feature = "async")]
+    pub fn transfer_registry(mut self, registry: impl ReferenceRegistry + 'static) -> Self {
+        self.transfer_registry = Some(Arc::new(registry));
+        self
+    }
+
+    /// Sets the registry consulted before initializing a transaction. A `tx_ref` already in the
+    /// registry is refused locally with [`ChapaError::DuplicateReference`].
+    #[cfg(feature = "async")]
+    pub fn tx_ref_registry(mut self, registry: impl TxRefRegistry + 'static) -> Self {
+        self.tx_ref_registry = Some(Arc::new(registry));
+        self
+    }
+
+    /// Sets the store remembering the checkout URL of every initialized transaction, used by
+    /// [`ChapaClient::checkout`](crate::client::ChapaClient::checkout) to reuse pending checkouts.
+    #[cfg(feature = "async")]
+    pub fn checkout_store(mut self, store: impl CheckoutStore + 'static) -> Self {
+        self.checkout_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets the store recording the progress of bulk transfer runs, used by
+    /// [`ChapaClient::bulk_transfer_run`](crate::client::ChapaClient::bulk_transfer_run) and
+    /// [`ChapaClient::resume`](crate::client::ChapaClient::resume).
+    #[cfg(feature = "async")]
+    pub fn bulk_progress_store(mut self, store: impl BulkProgressStore + 'static) -> Self {
+        self.bulk_progress_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets the store recording the checkpoints of export jobs, used by
+    /// [`ChapaClient::export`](crate::client::ChapaClient::export).
+    #[cfg(feature = "async")]
+    pub fn export_checkpoint_store(mut self, store: impl ExportCheckpointStore + 'static) -> Self {
+        self.export_checkpoint_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Records the money-moving requests in a tamper-evident `trail`, see the
+    /// [`audit`](crate::audit) module.
+    #[cfg(feature = "async")]
+    pub fn audit_trail(mut self, trail: AuditTrail) -> Self {
+        self.audit_trail = Some(trail);
+        self
+    }
+
+    /// Sends the requests with `transport` instead of the HTTP client, see the
+    /// [`transport`](crate::transport) module.
+    #[cfg(feature = "async")]
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Sets the minimum TLS protocol version accepted when connecting to the API.
+    pub fn min_tls_version(mut self, version: TlsVersion) -> Self {
+        self.min_tls_version = Some(version);
+        self
+    }
+
+    /// Adds an extra PEM encoded root certificate to trust, e.g. for corporate MITM proxies
+    /// or private staging gateways. The certificate is validated when calling `build()`.
+    pub fn add_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Attaches a [`HarRecorder`] that captures every request/response pair (with secrets redacted).
+    #[cfg(feature = "har")]
+    pub fn har_recorder(mut self, recorder: HarRecorder) -> Self {
+        self.har_recorder = Some(recorder);
+        self
+    }
+
+    /// Logs the body of every request and response at debug level, on the `chapa_rust::client`
+    /// target. Bodies hold customer and account details, only enable this for debugging.
+    #[cfg(feature = "logging")]
+    pub fn log_bodies(mut self, enabled: bool) -> Self {
+        self.log_bodies = enabled;
+        self
+    }
+
     /// Adds a specific header key/value pair.
     pub fn add_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.default_headers.insert(key.into(), value.into());
@@ -143,37 +747,185 @@ impl ChapaConfigBuilder {
         if self.api_key.is_none() || self.api_key == Some(PLACEHOLDER_API_KEY.to_string()) {
             return Err(ChapaError::MissingApiKey);
         }
+        let api_key = self.api_key.unwrap();
+        let environment = Environment::from_api_key(&api_key)?;
+        if let Some(expected) = self.environment.filter(|expected| *expected != environment) {
+            return Err(ChapaError::EnvironmentMismatch {
+                expected,
+                actual: environment,
+            });
+        }
+
+        let base_url = match &self.base_url_pool {
+            Some(pool) => {
+                for entry in pool.urls() {
+                    validate_base_url(&entry.url, self.allow_insecure)?;
+                }
+                pool.urls()
+                    .first()
+                    .map(|entry| entry.url.clone())
+                    .ok_or_else(|| {
+                        ChapaError::InvalidBaseUrl("the base URL pool is empty".to_string())
+                    })?
+            }
+            None => self
+                .base_url
+                .unwrap_or_else(|| environment.base_url().to_string()),
+        };
+        validate_base_url(&base_url, self.allow_insecure)?;
+
+        #[cfg(feature = "async")]
+        let root_certificates = self
+            .root_certificates
+            .iter()
+            .map(|pem| {
+                reqwest::Certificate::from_pem(pem)
+                    .map_err(|e| ChapaError::InvalidCertificate(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Without reqwest, check the certificates the way the blocking client will read them.
+        #[cfg(all(feature = "ureq", not(feature = "async")))]
+        for pem in &self.root_certificates {
+            let mut items = ureq::tls::parse_pem(pem);
+            if !items.any(|item| matches!(item, Ok(ureq::tls::PemItem::Certificate(_)))) {
+                return Err(ChapaError::InvalidCertificate(
+                    "no certificate found in the PEM data".to_string(),
+                ));
+            }
+        }
 
         Ok(ChapaConfig {
-            api_key: self.api_key.unwrap(),
-            base_url: self.base_url.unwrap(),
+            api_key,
+            base_url,
+            base_url_pool: self.base_url_pool,
             version: self.version.unwrap(),
             default_headers: self.default_headers,
             timeout: self.timeout.unwrap(),
+            min_tls_version: self.min_tls_version.unwrap(),
+            #[cfg(feature = "async")]
+            root_certificates,
+            #[cfg(feature = "ureq")]
+            root_certificate_pems: self.root_certificates,
+            allow_insecure: self.allow_insecure,
+            environment,
+            retain_raw_response: self.retain_raw_response,
+            country: self.country,
+            check_logo: self.check_logo,
+            error_on_status: self.error_on_status,
+            #[cfg(feature = "async")]
+            rate_limiter: self.rate_limiter,
+            rate_limit_retries: self.rate_limit_retries,
+            max_retry_after: self.max_retry_after,
+            body_serializer: self.body_serializer,
+            max_list_response_size: self.max_list_response_size.unwrap(),
+            amount_rules: self.amount_rules,
+            guardrails: self.guardrails,
+            #[cfg(feature = "async")]
+            balance_check: self.balance_check,
+            exchange_rates: self.exchange_rates,
+            #[cfg(feature = "async")]
+            transfer_registry: self.transfer_registry,
+            #[cfg(feature = "async")]
+            tx_ref_registry: self.tx_ref_registry,
+            #[cfg(feature = "async")]
+            checkout_store: self.checkout_store,
+            #[cfg(feature = "async")]
+            bulk_progress_store: self.bulk_progress_store,
+            #[cfg(feature = "async")]
+            export_checkpoint_store: self.export_checkpoint_store,
+            #[cfg(feature = "async")]
+            audit_trail: self.audit_trail,
+            #[cfg(feature = "async")]
+            transport: self.transport,
+            webhook_secret: self.webhook_secret,
+            #[cfg(feature = "async")]
+            webhook_replay_guard: self.webhook_replay_guard,
+            accept_secret_signature: self.accept_secret_signature,
+            #[cfg(feature = "async")]
+            webhook_observer: self.webhook_observer,
+            #[cfg(feature = "har")]
+            har_recorder: self.har_recorder,
+            #[cfg(feature = "logging")]
+            log_bodies: self.log_bodies,
         })
     }
 }
 
+/// Checks that `base_url` is a valid URL, using HTTPS unless `allow_insecure` is set.
+pub(crate) fn validate_base_url(base_url: &str, allow_insecure: bool) -> Result<()> {
+    let parsed_url = url::Url::parse(base_url)
+        .map_err(|e| ChapaError::InvalidBaseUrl(format!("{}: {}", base_url, e)))?;
+    if parsed_url.scheme() != "https" && !allow_insecure {
+        return Err(ChapaError::InsecureBaseUrl(base_url.to_string()));
+    }
+    Ok(())
+}
+
 impl Default for ChapaConfigBuilder {
     fn default() -> Self {
         let mut headers = HashMap::new();
         headers.insert("Content-Type".to_string(), "application/json".to_string());
 
-        let default_api_key = std::env::var("CHAPA_API_PUBLIC_KEY")
+        let default_api_key = std::env::var("CHAPA_SECRET_KEY")
+            .or_else(|_| std::env::var("CHAPA_API_PUBLIC_KEY"))
             .unwrap_or_else(|_| PLACEHOLDER_API_KEY.to_string());
 
         Self {
             api_key: Some(default_api_key),
-            base_url: Some("https://api.chapa.co".to_string()),
+            base_url: None,
+            base_url_pool: None,
             version: Some("v1".to_string()),
             default_headers: headers,
             timeout: Some(Duration::from_secs(30)),
+            min_tls_version: Some(TlsVersion::default()),
+            root_certificates: Vec::new(),
+            allow_insecure: false,
+            environment: None,
+            retain_raw_response: false,
+            country: None,
+            check_logo: false,
+            error_on_status: false,
+            #[cfg(feature = "async")]
+            rate_limiter: None,
+            rate_limit_retries: 0,
+            max_retry_after: DEFAULT_MAX_RETRY_AFTER,
+            body_serializer: None,
+            max_list_response_size: Some(DEFAULT_MAX_LIST_RESPONSE_SIZE),
+            amount_rules: AmountRules::default(),
+            guardrails: None,
+            #[cfg(feature = "async")]
+            balance_check: None,
+            exchange_rates: ExchangeRates::default(),
+            #[cfg(feature = "async")]
+            transfer_registry: None,
+            #[cfg(feature = "async")]
+            tx_ref_registry: None,
+            #[cfg(feature = "async")]
+            checkout_store: None,
+            #[cfg(feature = "async")]
+            bulk_progress_store: None,
+            #[cfg(feature = "async")]
+            export_checkpoint_store: None,
+            #[cfg(feature = "async")]
+            audit_trail: None,
+            #[cfg(feature = "async")]
+            transport: None,
+            webhook_secret: std::env::var("CHAPA_WEBHOOK_SECRET").ok(),
+            #[cfg(feature = "async")]
+            webhook_replay_guard: None,
+            accept_secret_signature: false,
+            #[cfg(feature = "async")]
+            webhook_observer: None,
+            #[cfg(feature = "har")]
+            har_recorder: None,
+            #[cfg(feature = "logging")]
+            log_bodies: false,
         }
     }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::env;
 
     use super::*;
@@ -184,7 +936,7 @@ mod tests {
         // if the tests are run in parallel, it may cause issues.
         let config: ChapaConfig;
         unsafe {
-            env::set_var("CHAPA_API_PUBLIC_KEY", "test_api_key_123");
+            env::set_var("CHAPA_API_PUBLIC_KEY", "CHASECK_TEST-test_api_key_123");
             config = ChapaConfig::builder()
                 .build()
                 .expect("Failed to build config");
@@ -193,25 +945,191 @@ mod tests {
         assert_eq!(config.base_url, "https://api.chapa.co");
         assert_eq!(config.version, "v1");
         assert_eq!(config.timeout.as_secs(), 30);
+        assert_eq!(config.min_tls_version, TlsVersion::Tls1_2);
+        assert_eq!(config.environment, Environment::Test);
+        assert_eq!(config.max_list_response_size, 16 * 1024 * 1024);
         assert!(config.api_key.contains("placeholder") || !config.api_key.is_empty());
     }
 
+    #[test]
+    fn test_from_vars() {
+        fn vars<'a>(pairs: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+            move |name| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        }
+
+        let config = ChapaConfig::from_vars(vars(&[
+            ("CHAPA_SECRET_KEY", "CHASECK_TEST-from-env"),
+            ("CHAPA_API_PUBLIC_KEY", "CHASECK_TEST-legacy"),
+            ("CHAPA_BASE_URL", "https://sandbox.chapa.example"),
+            ("CHAPA_VERSION", "v2"),
+            ("CHAPA_TIMEOUT_SECS", " 12 "),
+            ("CHAPA_WEBHOOK_SECRET", "whsec"),
+        ]))
+        .unwrap();
+        assert_eq!(config.api_key, "CHASECK_TEST-from-env");
+        assert_eq!(config.base_url, "https://sandbox.chapa.example");
+        assert_eq!(config.version, "v2");
+        assert_eq!(config.timeout, Duration::from_secs(12));
+        assert_eq!(config.webhook_secret.as_deref(), Some("whsec"));
+
+        let config =
+            ChapaConfig::from_vars(vars(&[("CHAPA_API_PUBLIC_KEY", "CHASECK_TEST-legacy")]))
+                .unwrap();
+        assert_eq!(config.api_key, "CHASECK_TEST-legacy");
+        // a blank legacy variable doesn't matter once the secret key is set.
+        let config = ChapaConfig::from_vars(vars(&[
+            ("CHAPA_SECRET_KEY", "CHASECK_TEST-from-env"),
+            ("CHAPA_API_PUBLIC_KEY", " "),
+        ]))
+        .unwrap();
+        assert_eq!(config.api_key, "CHASECK_TEST-from-env");
+
+        for (name, value) in [("CHAPA_TIMEOUT_SECS", "30s"), ("CHAPA_VERSION", " ")] {
+            let pairs = [("CHAPA_SECRET_KEY", "CHASECK_TEST-from-env"), (name, value)];
+            match ChapaConfig::from_vars(vars(&pairs)) {
+                Err(ChapaError::InvalidEnvVar { name: var, .. }) => assert_eq!(var, name),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+        assert!(matches!(
+            ChapaConfig::from_vars(vars(&[
+                ("CHAPA_SECRET_KEY", "CHASECK_TEST-from-env"),
+                ("CHAPA_BASE_URL", "not a url"),
+            ])),
+            Err(ChapaError::InvalidBaseUrl(_))
+        ));
+    }
+
     #[test]
     fn test_builder_pattern() {
         let config = ChapaConfig::builder()
             .base_url("http://localhost:8080/dev")
+            .allow_insecure()
             .version("v2")
             .timeout(Duration::from_secs(5))
-            .api_key("my-secret-key-123")
+            .api_key("CHASECK_TEST-my-secret-key-123")
             .add_header("X-Client-ID", "chapa-cli")
+            .min_tls_version(TlsVersion::Tls1_3)
             .build()
             .expect("Failed to build config");
 
         assert_eq!(config.base_url, "http://localhost:8080/dev");
         assert_eq!(config.timeout.as_secs(), 5);
+        assert_eq!(config.min_tls_version, TlsVersion::Tls1_3);
         assert_eq!(
             config.default_headers.get("X-Client-ID"),
             Some(&"chapa-cli".to_string())
         );
     }
+
+    #[cfg(any(feature = "async", feature = "ureq"))]
+    pub(crate) const TEST_ROOT_CA: &str = "-----BEGIN CERTIFICATE-----
+MIIBkTCCATegAwIBAgIUL+O0zMMwNRvmOqIRsC/TDt/pEpcwCgYIKoZIzj0EAwIw
+HTEbMBkGA1UEAwwSY2hhcGEtcnVzdCB0ZXN0IENBMCAXDTI2MTAxNjExMTM1NVoY
+DzIxMjYwOTIyMTExMzU1WjAdMRswGQYDVQQDDBJjaGFwYS1ydXN0IHRlc3QgQ0Ew
+WTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAQSTVGVNx6XflrdbJ56sa4Sk+weX1l6
+IhuATB0q9K2MYU4eSrM0N3mwv6u/AvLclKmqja9eG7ceSGzRylSgpvbEo1MwUTAd
+BgNVHQ4EFgQUdpzlxoYLzOZ8eSzpGc/7scX/MzQwHwYDVR0jBBgwFoAUdpzlxoYL
+zOZ8eSzpGc/7scX/MzQwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBF
+AiBysx8+Ak5zVRHnx1KkO6NK9Gjch9X/523mGsxcOSWKEwIhAIL7PuoUBE31XWLG
+DLLxCYtO9DXOX5qnfyrywjzufsWd
+-----END CERTIFICATE-----";
+
+    #[test]
+    #[cfg(any(feature = "async", feature = "ureq"))]
+    fn test_root_certificates() {
+        let config = ChapaConfig::builder()
+            .api_key("CHASECK_TEST-my-secret-key-123")
+            .add_root_certificate(TEST_ROOT_CA)
+            .build()
+            .expect("Failed to build config");
+        #[cfg(feature = "async")]
+        assert_eq!(config.root_certificates.len(), 1);
+        #[cfg(feature = "ureq")]
+        assert_eq!(config.root_certificate_pems.len(), 1);
+
+        let invalid = ChapaConfig::builder()
+            .api_key("CHASECK_TEST-my-secret-key-123")
+            .add_root_certificate("not a certificate")
+            .build();
+        assert!(matches!(invalid, Err(ChapaError::InvalidCertificate(_))));
+    }
+
+    #[test]
+    fn test_insecure_base_url() {
+        let rejected = ChapaConfig::builder()
+            .api_key("CHASECK_TEST-my-secret-key-123")
+            .base_url("http://api.chapa.co")
+            .build();
+        assert!(matches!(rejected, Err(ChapaError::InsecureBaseUrl(_))));
+
+        let invalid = ChapaConfig::builder()
+            .api_key("CHASECK_TEST-my-secret-key-123")
+            .base_url("not a url")
+            .build();
+        assert!(matches!(invalid, Err(ChapaError::InvalidBaseUrl(_))));
+
+        let allowed = ChapaConfig::builder()
+            .api_key("CHASECK_TEST-my-secret-key-123")
+            .base_url("http://localhost:1234")
+            .allow_insecure()
+            .build()
+            .expect("Failed to build config");
+        assert!(allowed.allow_insecure);
+    }
+
+    #[test]
+    fn test_api_key_validation() {
+        assert_eq!(
+            Environment::from_api_key("CHASECK-xxxxxxxx").unwrap(),
+            Environment::Live
+        );
+        assert_eq!(
+            Environment::from_api_key("CHASECK_TEST-xxxxxxxx").unwrap(),
+            Environment::Test
+        );
+        assert_eq!(
+            Environment::from_api_key("CHAPUBK_TEST-xxxxxxxx").unwrap(),
+            Environment::Test
+        );
+
+        let live = ChapaConfig::builder()
+            .api_key("CHASECK-xxxxxxxx")
+            .build()
+            .unwrap();
+        assert!(live.mode().is_live());
+
+        for key in ["", "CHASECK-", "sk_live_123", "chaseck_test-123"] {
+            let result = ChapaConfig::builder().api_key(key).build();
+            assert!(matches!(result, Err(ChapaError::InvalidApiKey(_))), "{key}");
+        }
+    }
+
+    #[test]
+    fn test_environment_switching() {
+        let config = ChapaConfig::builder()
+            .environment(Environment::Test)
+            .api_key("CHASECK_TEST-xxxxxxxx")
+            .build()
+            .expect("Failed to build config");
+        assert_eq!(config.environment, Environment::Test);
+        assert_eq!(config.base_url, Environment::Test.base_url());
+
+        let mismatch = ChapaConfig::builder()
+            .environment(Environment::Test)
+            .api_key("CHASECK-xxxxxxxx")
+            .build();
+        assert!(matches!(
+            mismatch,
+            Err(ChapaError::EnvironmentMismatch {
+                expected: Environment::Test,
+                actual: Environment::Live
+            })
+        ));
+    }
 }
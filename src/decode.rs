@@ -0,0 +1,159 @@
+//! Incremental decoding of response bodies.
+//!
+//! List endpoints can return pages of several megabytes. Instead of buffering the whole body
+//! before deserializing it, the chunks are fed to `serde_json` as they arrive, on a blocking
+//! thread, so the body is never held in memory as a whole.
+//!
+//! With the `simd-json` feature, the chunks are gathered instead (within the same limit) and
+//! parsed with simd-json, which needs the whole body but parses large pages faster.
+
+#[cfg(not(feature = "simd-json"))]
+use std::io::Read;
+
+#[cfg(not(feature = "simd-json"))]
+use bytes::Buf;
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+#[cfg(not(feature = "simd-json"))]
+use tokio::sync::mpsc;
+
+use crate::error::{ChapaError, Result};
+
+/// How many chunks may wait for the decoder before the download is paused.
+#[cfg(not(feature = "simd-json"))]
+const CHANNEL_CAPACITY: usize = 8;
+
+/// Deserializes a JSON body of a response to `endpoint` from a stream of chunks, failing with
+/// [`ChapaError::ResponseTooLarge`] as soon as more than `limit` bytes were received.
+///
+/// The start of the body is kept, to be attached to a [`ChapaError::Deserialization`].
+#[cfg(not(feature = "simd-json"))]
+pub(crate) async fn decode_stream<T, S, E>(mut chunks: S, limit: usize, endpoint: &str) -> Result<T>
+where
+    T: DeserializeOwned + Send + 'static,
+    S: Stream<Item = std::result::Result<Bytes, E>> + Unpin,
+    ChapaError: From<E>,
+{
+    let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+    let decoder = tokio::task::spawn_blocking(move || {
+        serde_json::from_reader::<_, T>(ChunkReader {
+            receiver,
+            current: Bytes::new(),
+        })
+    });
+
+    let mut received = 0usize;
+    let mut excerpt = Vec::new();
+    while let Some(chunk) = chunks.next().await {
+        // returning early drops the sender, which ends the decoder with an EOF error.
+        let chunk = chunk?;
+        received += chunk.len();
+        if received > limit {
+            return Err(ChapaError::ResponseTooLarge(limit));
+        }
+        let room = ChapaError::BODY_EXCERPT_LIMIT.saturating_sub(excerpt.len());
+        excerpt.extend_from_slice(&chunk[..chunk.len().min(room)]);
+        if sender.send(chunk).await.is_err() {
+            // the decoder stopped early, its error is reported below.
+            break;
+        }
+    }
+    drop(sender);
+
+    decoder
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+        .map_err(|e| ChapaError::deserialization(e, &excerpt, endpoint))
+}
+
+/// Deserializes a JSON body of a response to `endpoint` from a stream of chunks with simd-json,
+/// failing with [`ChapaError::ResponseTooLarge`] as soon as more than `limit` bytes were
+/// received.
+#[cfg(feature = "simd-json")]
+pub(crate) async fn decode_stream<T, S, E>(mut chunks: S, limit: usize, endpoint: &str) -> Result<T>
+where
+    T: DeserializeOwned + Send + 'static,
+    S: Stream<Item = std::result::Result<Bytes, E>> + Unpin,
+    ChapaError: From<E>,
+{
+    let mut body = Vec::new();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > limit {
+            return Err(ChapaError::ResponseTooLarge(limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    // simd-json parses in place, the start of the body is copied to be attached to the error.
+    let excerpt = body[..body.len().min(ChapaError::BODY_EXCERPT_LIMIT)].to_vec();
+    simd_json::serde::from_slice(&mut body)
+        .map_err(|e| ChapaError::deserialization(serde::de::Error::custom(e), &excerpt, endpoint))
+}
+
+/// A blocking reader over the chunks sent by [`decode_stream`].
+#[cfg(not(feature = "simd-json"))]
+struct ChunkReader {
+    receiver: mpsc::Receiver<Bytes>,
+    current: Bytes,
+}
+
+#[cfg(not(feature = "simd-json"))]
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while !self.current.has_remaining() {
+            match self.receiver.blocking_recv() {
+                Some(chunk) => self.current = chunk,
+                None => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.current.remaining());
+        self.current.copy_to_slice(&mut buf[..len]);
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunked(body: &'static str, size: usize) -> Vec<std::result::Result<Bytes, ChapaError>> {
+        body.as_bytes()
+            .chunks(size)
+            .map(|chunk| Ok(Bytes::from_static(chunk)))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_decode_stream() {
+        let body = r#"{"status":"success","data":[1,2,3,4,5,6,7,8,9,10]}"#;
+        let value: serde_json::Value = decode_stream(
+            futures_util::stream::iter(chunked(body, 3)),
+            1024,
+            "/v1/banks",
+        )
+        .await
+        .unwrap();
+        assert_eq!(value["data"].as_array().unwrap().len(), 10);
+
+        let too_large = decode_stream::<serde_json::Value, _, _>(
+            futures_util::stream::iter(chunked(body, 3)),
+            16,
+            "/v1/banks",
+        )
+        .await;
+        assert!(matches!(too_large, Err(ChapaError::ResponseTooLarge(16))));
+
+        let truncated = decode_stream::<serde_json::Value, _, _>(
+            futures_util::stream::iter(chunked(&body[..20], 3)),
+            1024,
+            "/v1/banks",
+        )
+        .await;
+        assert!(matches!(
+            truncated,
+            Err(ChapaError::Deserialization { body: received, endpoint, .. })
+                if received == body[..20] && endpoint == "/v1/banks"
+        ));
+    }
+}
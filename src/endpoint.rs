@@ -0,0 +1,206 @@
+//! # Endpoint Module
+//!
+//! Every Chapa API endpoint called by the client, with its HTTP method, path template and
+//! idempotency, so paths are written in a single place and policies (retries, caching) can be
+//! decided per endpoint instead of per string.
+//!
+//! Paths are relative to the versioned base URL, e.g. `{base_url}/{version}/banks`.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::endpoint::Endpoint;
+//!
+//! let verify = Endpoint::VerifyTransaction { tx_ref: "chewatatest-6669" };
+//! assert_eq!(verify.path(), "transaction/verify/chewatatest-6669");
+//! assert_eq!(verify.template(), "transaction/verify/{tx_ref}");
+//! assert!(verify.is_idempotent());
+//! ```
+use http::Method;
+
+use crate::models::channel::PaymentChannel;
+
+/// A Chapa API endpoint, with the parameters of its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint<'a> {
+    /// `GET banks`, the banks supported for transfers.
+    Banks,
+    /// `GET balances`, the merchant's balance in every currency.
+    Balances,
+    /// `GET payment-channels`, the payment channels enabled for the merchant account.
+    PaymentChannels,
+    /// `POST transaction/initialize`, starts a hosted checkout.
+    InitializeTransaction,
+    /// `GET transaction/verify/{tx_ref}`, the state of a payment.
+    VerifyTransaction {
+        /// The merchant's transaction reference.
+        tx_ref: &'a str,
+    },
+    /// `GET transaction/events/{tx_ref}`, the logs of a payment.
+    TransactionLogs {
+        /// The merchant's transaction reference.
+        tx_ref: &'a str,
+    },
+    /// `GET transactions`, a page of the merchant's transactions.
+    Transactions {
+        /// An additional query string.
+        filter: Option<&'a str>,
+        /// The page to fetch, the first one if `None`.
+        page: Option<u32>,
+    },
+    /// `GET transfers`, a page of the merchant's transfers.
+    Transfers {
+        /// An additional query string, e.g. `batch_id=42`.
+        filter: Option<&'a str>,
+        /// The page to fetch, the first one if `None`.
+        page: Option<u32>,
+    },
+    /// `POST transfers`, initiates a transfer.
+    Transfer,
+    /// `GET transfers/verify/{reference}`, the state of a transfer.
+    VerifyTransfer {
+        /// The merchant's transfer reference.
+        reference: &'a str,
+    },
+    /// `POST bulk-transfers`, queues a batch of transfers.
+    BulkTransfer,
+    /// `POST swap`, converts between currencies.
+    Swap,
+    /// `POST subaccount`, creates a subaccount.
+    CreateSubaccount,
+    /// `GET subaccount`, the merchant's subaccounts.
+    Subaccounts,
+    /// `POST charges?type={channel}`, initiates a direct charge.
+    DirectCharge {
+        /// The channel to charge.
+        channel: PaymentChannel,
+    },
+    /// `POST validate?type={channel}`, authorizes a direct charge.
+    AuthorizeDirectCharge {
+        /// The channel of the charge.
+        channel: PaymentChannel,
+    },
+}
+
+impl Endpoint<'_> {
+    /// The HTTP method of the endpoint.
+    pub fn method(&self) -> Method {
+        match self {
+            Endpoint::Banks
+            | Endpoint::Balances
+            | Endpoint::PaymentChannels
+            | Endpoint::VerifyTransaction { .. }
+            | Endpoint::TransactionLogs { .. }
+            | Endpoint::Transactions { .. }
+            | Endpoint::Transfers { .. }
+            | Endpoint::VerifyTransfer { .. }
+            | Endpoint::Subaccounts => Method::GET,
+            Endpoint::InitializeTransaction
+            | Endpoint::Transfer
+            | Endpoint::BulkTransfer
+            | Endpoint::Swap
+            | Endpoint::CreateSubaccount
+            | Endpoint::DirectCharge { .. }
+            | Endpoint::AuthorizeDirectCharge { .. } => Method::POST,
+        }
+    }
+
+    /// The path template of the endpoint, without the parameters filled in.
+    pub fn template(&self) -> &'static str {
+        match self {
+            Endpoint::Banks => "banks",
+            Endpoint::Balances => "balances",
+            Endpoint::PaymentChannels => "payment-channels",
+            Endpoint::InitializeTransaction => "transaction/initialize",
+            Endpoint::VerifyTransaction { .. } => "transaction/verify/{tx_ref}",
+            Endpoint::TransactionLogs { .. } => "transaction/events/{tx_ref}",
+            Endpoint::Transactions { .. } => "transactions",
+            Endpoint::Transfers { .. } | Endpoint::Transfer => "transfers",
+            Endpoint::VerifyTransfer { .. } => "transfers/verify/{reference}",
+            Endpoint::BulkTransfer => "bulk-transfers",
+            Endpoint::Swap => "swap",
+            Endpoint::CreateSubaccount | Endpoint::Subaccounts => "subaccount",
+            Endpoint::DirectCharge { .. } => "charges?type={channel}",
+            Endpoint::AuthorizeDirectCharge { .. } => "validate?type={channel}",
+        }
+    }
+
+    /// The path of the endpoint, query string included.
+    pub fn path(&self) -> String {
+        match self {
+            Endpoint::VerifyTransaction { tx_ref } => format!("transaction/verify/{}", tx_ref),
+            Endpoint::TransactionLogs { tx_ref } => format!("transaction/events/{}", tx_ref),
+            Endpoint::Transactions { filter, page } | Endpoint::Transfers { filter, page } => {
+                let query: Vec<String> = filter
+                    .map(str::to_string)
+                    .into_iter()
+                    .chain(page.map(|page| format!("page={}", page)))
+                    .collect();
+                if query.is_empty() {
+                    self.template().to_string()
+                } else {
+                    format!("{}?{}", self.template(), query.join("&"))
+                }
+            }
+            Endpoint::VerifyTransfer { reference } => format!("transfers/verify/{}", reference),
+            Endpoint::DirectCharge { channel } => format!("charges?type={}", channel.as_str()),
+            Endpoint::AuthorizeDirectCharge { channel } => {
+                format!("validate?type={}", channel.as_str())
+            }
+            other => other.template().to_string(),
+        }
+    }
+
+    /// Returns `true` if sending the request twice has the same effect as sending it once, so
+    /// it can safely be retried or cached.
+    pub fn is_idempotent(&self) -> bool {
+        self.method() == Method::GET
+    }
+
+    /// Returns `true` if the request moves money (checkouts, transfers, swaps and direct
+    /// charges), which is what the [audit trail](crate::audit) records.
+    pub fn moves_money(&self) -> bool {
+        matches!(
+            self,
+            Endpoint::InitializeTransaction
+                | Endpoint::Transfer
+                | Endpoint::BulkTransfer
+                | Endpoint::Swap
+                | Endpoint::DirectCharge { .. }
+                | Endpoint::AuthorizeDirectCharge { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_paths() {
+        assert_eq!(Endpoint::Banks.path(), "banks");
+        assert_eq!(Endpoint::PaymentChannels.path(), "payment-channels");
+        assert!(Endpoint::PaymentChannels.is_idempotent());
+        assert_eq!(
+            Endpoint::Transactions {
+                filter: None,
+                page: None
+            }
+            .path(),
+            "transactions"
+        );
+        assert_eq!(
+            Endpoint::Transfers {
+                filter: Some("batch_id=42"),
+                page: Some(2)
+            }
+            .path(),
+            "transfers?batch_id=42&page=2"
+        );
+        let charge = Endpoint::DirectCharge {
+            channel: PaymentChannel::Telebirr,
+        };
+        assert_eq!(charge.path(), "charges?type=telebirr");
+        assert_eq!(charge.method(), Method::POST);
+        assert!(!charge.is_idempotent());
+    }
+}
@@ -1,6 +1,34 @@
 //! Error handling module for Chapa API interactions.
+//!
+//! [`ChapaError`] serializes into a `{"code": ..., "message": ...}` object, so services built on
+//! the SDK can forward errors to their own clients as structured JSON. The codes returned by
+//! [`ChapaError::code`] are stable; the messages are meant for humans and may change.
+//!
+//! Errors returned by the Chapa API carry the [`ResponseHeaders`] worth quoting to Chapa
+//! support (request id, rate limit counters, server date), see [`ChapaError::response_headers`].
+//!
+//! [`ChapaError::class`] sorts errors into a few [`ErrorClass`]es (the caller's fault, a
+//! misconfiguration, a transient failure, a Chapa outage or a bug), so a service can decide
+//! whether to answer its user with a 4xx, retry, or page someone.
+//!
+//! [`ChapaError::http_status`] maps errors to the HTTP status a web handler should answer with;
+//! with the `axum` feature, [`ChapaError`] implements `IntoResponse`, so handlers can return
+//! `Result<_, ChapaError>` directly.
+//!
+//! With the `miette` feature, [`ChapaError`] also implements `miette::Diagnostic`, with the
+//! error code and an actionable help text, for readable reports in CLIs and scripts.
+use std::{fmt, time::Duration};
+
+use http::HeaderMap;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+use serde_json::Value;
 use thiserror::Error;
 
+use crate::{
+    config::Environment,
+    models::response::{Message, ValidationErrors},
+};
+
 /// A specialized `Result` type for Chapa operations.
 pub type Result<T> = std::result::Result<T, ChapaError>;
 
@@ -9,22 +37,1154 @@ pub type Result<T> = std::result::Result<T, ChapaError>;
 pub enum ChapaError {
     /// Indicates that the API key is missing in the configuration.
     #[error(
-        "API Key is required but not set. Please set it using the CHAPA_API_PUBLIC_KEY environment variable or via the builder's api_key() method."
+        "API Key is required but not set. Please set it using the CHAPA_SECRET_KEY environment variable or via the builder's api_key() method."
     )]
     MissingApiKey,
-    /// Indicates that a network error occurred.
+    /// Indicates that the configured API key doesn't look like a Chapa key.
+    #[error("Invalid API Key: {0}")]
+    InvalidApiKey(String),
+    /// Indicates that a network error occurred that doesn't fit any of the more specific variants.
+    #[cfg(feature = "async")]
     #[error("Network error occurred")]
-    NetworkError(#[from] reqwest::Error),
+    NetworkError(#[source] reqwest::Error),
+    /// Indicates that the connection to the API could not be established (DNS, TCP or TLS failure).
+    #[cfg(feature = "async")]
+    #[error("Failed to connect to the Chapa API")]
+    ConnectError(#[source] reqwest::Error),
+    /// Indicates that the request didn't complete within the configured timeout. The request
+    /// may still have been processed by Chapa.
+    #[error(
+        "Request to the Chapa API timed out{}",
+        timeout_details(*.elapsed, *.configured)
+    )]
+    Timeout {
+        /// How long the request ran before timing out, if known.
+        elapsed: Option<Duration>,
+        /// The timeout configured on the client, if known.
+        configured: Option<Duration>,
+        /// The underlying `reqwest` or `ureq` error.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// Indicates that the response body could not be read.
+    #[cfg(feature = "async")]
+    #[error("Failed to read the response body")]
+    BodyError(#[source] reqwest::Error),
+    /// Indicates that the response body could not be decoded into the expected type.
+    #[cfg(feature = "async")]
+    #[error("Failed to decode the response body")]
+    DecodeError(#[source] reqwest::Error),
+    /// Indicates that following a redirect failed (e.g. too many redirects).
+    #[cfg(feature = "async")]
+    #[error("Redirect error occurred")]
+    RedirectError(#[source] reqwest::Error),
+    /// Indicates that Chapa refused the request with a `429` status: too many requests were sent.
+    #[error("Rate limited by the Chapa API{}", .retry_after.map(|delay| format!(", retry after {}s", delay.as_secs())).unwrap_or_default())]
+    RateLimited {
+        /// How long to wait before sending the request again, from the `Retry-After` header.
+        retry_after: Option<Duration>,
+    },
+    /// Indicates that the blocking transport failed to get a response (see the `blocking` module).
+    #[error("Transport error: {0}")]
+    TransportError(String),
     /// Invalid HTTP method
     #[error("Invalid HTTP method: {0}")]
     InvalidHttpMethod(String),
     /// Represents an error returned by the Chapa API.
     #[error("API error occurred: {0}")]
-    ApiError(String),
+    ApiError(Box<ApiErrorDetails>),
     /// Indicates that a header value is invalid.
     #[error("Invalid header value: {0}")]
     InvalidHeaderValue(String),
     /// Indicates that a header name is invalid.
     #[error("Invalid header name: {0}")]
     InvalidHeaderName(String),
+    /// Indicates that the API key belongs to a different environment than the one pinned on the builder.
+    #[error(
+        "API Key is for the {actual:?} environment but the {expected:?} environment was requested"
+    )]
+    EnvironmentMismatch {
+        /// The environment pinned on the builder.
+        expected: Environment,
+        /// The environment inferred from the API key.
+        actual: Environment,
+    },
+    /// Indicates that an environment variable read by
+    /// [`ChapaConfig::from_env`](crate::config::ChapaConfig::from_env) has a malformed value.
+    #[error("Invalid value for the {name} environment variable: {reason}")]
+    InvalidEnvVar {
+        /// The name of the variable.
+        name: String,
+        /// Why the value was rejected.
+        reason: String,
+    },
+    /// Indicates that the configured base URL could not be parsed.
+    #[error("Invalid base URL: {0}")]
+    InvalidBaseUrl(String),
+    /// Indicates that the configured base URL doesn't use HTTPS and insecure URLs were not allowed.
+    #[error(
+        "Insecure base URL: {0}. Use https:// or call the builder's allow_insecure() method for local testing."
+    )]
+    InsecureBaseUrl(String),
+    /// Indicates that a configured root certificate could not be parsed.
+    #[error("Invalid root certificate: {0}")]
+    InvalidCertificate(String),
+    /// Indicates that a reference was already submitted by this client and was refused locally.
+    #[error("Reference has already been submitted: {0}")]
+    DuplicateReference(String),
+    /// Indicates that a transfer was about to be submitted without a reference.
+    #[error("Missing reference: {0}")]
+    MissingReference(String),
+    /// Indicates that the checkout customization (title, description) was rejected locally.
+    #[error("Invalid customization: {0}")]
+    InvalidCustomization(String),
+    /// Indicates that an amount was rejected locally by the amount rules.
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    /// Indicates that a payout beneficiary was rejected locally.
+    #[error("Invalid beneficiary: {0}")]
+    InvalidBeneficiary(String),
+    /// Indicates that an amount breaks a limit set by the merchant's guardrails.
+    #[error("Guardrail exceeded: {0}")]
+    GuardrailExceeded(String),
+    /// Indicates that the balance fetched before a bulk transfer doesn't cover the batch total
+    /// and its estimated charges.
+    #[error(
+        "Insufficient {currency} balance: {required} required, {available} available (short by {shortfall})"
+    )]
+    InsufficientLocalBalanceCheck {
+        /// The currency of the bulk transfer.
+        currency: String,
+        /// The batch total plus the estimated charges.
+        required: f64,
+        /// The available balance in the currency.
+        available: f64,
+        /// How much is missing, `required - available`.
+        shortfall: f64,
+    },
+    /// Indicates that a swap amount is below the minimum accepted by Chapa.
+    #[error("Swap amount {amount} is below the minimum of {minimum}")]
+    SwapAmountTooLow {
+        /// The amount of the swap.
+        amount: f64,
+        /// The smallest amount that can be swapped.
+        minimum: f64,
+    },
+    /// Indicates that a swap amount is above the maximum accepted by Chapa.
+    #[error("Swap amount {amount} is above the maximum of {maximum}")]
+    SwapAmountTooHigh {
+        /// The amount of the swap.
+        amount: f64,
+        /// The largest amount that can be swapped.
+        maximum: f64,
+    },
+    /// Indicates that Chapa doesn't swap between the two currencies.
+    #[error("Swapping {from} to {to} is not supported")]
+    UnsupportedSwapPair {
+        /// The currency to swap from.
+        from: String,
+        /// The currency to swap to.
+        to: String,
+    },
+    /// Indicates that the split settlement of a payment differs from the requested split.
+    #[error("Split mismatch: {0}")]
+    SplitMismatch(String),
+    /// Indicates that a webhook signature is missing or doesn't match the payload.
+    #[error("Invalid webhook signature: {0}")]
+    InvalidSignature(String),
+    /// Indicates that a webhook delivery is older (or newer) than the replay tolerance allows.
+    #[error("Stale webhook: {0}")]
+    StaleWebhook(String),
+    /// Indicates that a webhook delivery was already received.
+    #[error("Duplicate webhook: {0}")]
+    DuplicateWebhook(String),
+    /// Indicates that no progress was recorded for a bulk transfer run.
+    #[error("Unknown bulk transfer run: {0}")]
+    UnknownBulkRun(String),
+    /// Indicates that a lookup walked as many pages as it is allowed to without finding its item,
+    /// while more pages follow.
+    #[error("Lookup limit reached: {0}")]
+    LookupLimitReached(String),
+    /// Indicates that the checkpoint recorded under an export job id belongs to another export.
+    #[error("Export job mismatch: {0}")]
+    ExportJobMismatch(String),
+    /// Indicates that an entry of the audit trail doesn't match its hash or the previous entry.
+    #[error("Audit trail broken at entry {0}")]
+    AuditChainBroken(u64),
+    /// Indicates that no exchange rate was observed for a currency pair.
+    #[error("No exchange rate observed for {0}")]
+    UnknownExchangeRate(String),
+    /// Indicates that a response body exceeded the configured size limit, in bytes.
+    #[error("Response body exceeds the limit of {0} bytes")]
+    ResponseTooLarge(usize),
+    /// Indicates that a response body doesn't match the expected model, e.g. after a change of
+    /// the API.
+    #[error("Failed to deserialize the response of {endpoint}: {source}")]
+    Deserialization {
+        /// The error of the deserializer.
+        #[source]
+        source: serde_json::Error,
+        /// The body of the response (lossily decoded as UTF-8), truncated to
+        /// [`ChapaError::BODY_EXCERPT_LIMIT`] bytes. It may hold customer details.
+        body: String,
+        /// The path of the request, e.g. `/v1/transaction/verify/order-1`.
+        endpoint: String,
+    },
+    /// Indicates that a JSON payload could not be serialized or deserialized.
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    /// Indicates that a local file operation failed.
+    #[error("I/O error: {0}")]
+    IoError(String),
+}
+
+/// The selected headers of an error response, worth quoting when escalating to Chapa support.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResponseHeaders {
+    /// The HTTP status of the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// The identifier of the request, from `x-request-id` (or Cloudflare's `cf-ray`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// The number of requests allowed in the rate limit window, from `x-ratelimit-limit`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_limit: Option<String>,
+    /// The number of requests left in the rate limit window, from `x-ratelimit-remaining`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_remaining: Option<String>,
+    /// How long to wait before retrying, from `retry-after`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<String>,
+    /// The date of the response according to the server, from `date`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+impl ResponseHeaders {
+    /// Picks the selected headers out of `headers`.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            status: None,
+            request_id: header("x-request-id").or_else(|| header("cf-ray")),
+            rate_limit_limit: header("x-ratelimit-limit"),
+            rate_limit_remaining: header("x-ratelimit-remaining"),
+            retry_after: header("retry-after"),
+            date: header("date"),
+        }
+    }
+
+    /// The delay of the `retry-after` header, given either in seconds or as an HTTP date (a date
+    /// in the past gives a zero delay).
+    pub fn retry_after_delay(&self) -> Option<Duration> {
+        let value = self.retry_after.as_deref()?.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        Some(
+            (at.to_utc() - chrono::Utc::now())
+                .to_std()
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Returns `true` if none of the selected headers was present.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The well-known kinds of errors returned by the Chapa API, recognized from their message (see
+/// [`ChapaApiErrorKind::from_message`]), so callers can match on them instead of on strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChapaApiErrorKind {
+    /// The API key was refused, e.g. `"Invalid API Key"`.
+    InvalidApiKey,
+    /// The balance doesn't cover a transfer, e.g. `"Insufficient Balance"`.
+    InsufficientBalance,
+    /// The `tx_ref` or transfer reference was used before, e.g.
+    /// `"Transaction reference has been used before"`.
+    DuplicateReference,
+    /// No transaction matches the reference, e.g. `"Invalid transaction or Transaction not found"`.
+    TransactionNotFound,
+    /// The bank code isn't one of the supported banks, e.g. `"Invalid Bank"`.
+    InvalidBank,
+    /// The account number was refused by the bank, e.g. `"Invalid account number"`.
+    InvalidAccount,
+    /// The currency isn't supported, e.g. `"Invalid currency"`.
+    InvalidCurrency,
+    /// Too many requests were sent, e.g. `"Too Many Attempts."`.
+    RateLimited,
+    /// Any other message.
+    Other,
+}
+
+impl ChapaApiErrorKind {
+    /// Recognizes the kind of an error from its message, case insensitively.
+    pub fn from_message(message: &str) -> Self {
+        let message = message.to_ascii_lowercase();
+        let has = |needle: &str| message.contains(needle);
+        if has("api key") || has("secret key") || has("unauthorized") || has("unauthenticated") {
+            ChapaApiErrorKind::InvalidApiKey
+        } else if has("insufficient balance") {
+            ChapaApiErrorKind::InsufficientBalance
+        } else if has("reference")
+            && ["used before", "already used", "already exist", "duplicate"]
+                .iter()
+                .any(|hint| has(hint))
+        {
+            ChapaApiErrorKind::DuplicateReference
+        } else if has("transaction not found") || has("invalid transaction") {
+            ChapaApiErrorKind::TransactionNotFound
+        } else if has("invalid bank") {
+            ChapaApiErrorKind::InvalidBank
+        } else if has("invalid account") {
+            ChapaApiErrorKind::InvalidAccount
+        } else if has("currency") && (has("invalid") || has("not supported")) {
+            ChapaApiErrorKind::InvalidCurrency
+        } else if has("too many") {
+            ChapaApiErrorKind::RateLimited
+        } else {
+            ChapaApiErrorKind::Other
+        }
+    }
+}
+
+/// The details of an error returned by the Chapa API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiErrorDetails {
+    /// The message of the error, usually the `message` of Chapa's response.
+    pub message: String,
+    /// The kind of the error, recognized from its message (or a `429` status).
+    pub kind: ChapaApiErrorKind,
+    /// The selected headers of the response, empty if the error wasn't built from a response.
+    pub headers: ResponseHeaders,
+    /// The body of the response, for errors built from a response with an HTTP error status
+    /// (see [`ChapaConfigBuilder::error_on_status`](crate::config::ChapaConfigBuilder::error_on_status)).
+    /// Bodies that aren't JSON are kept as a JSON string.
+    pub body: Option<Value>,
+    /// The validation errors per field, when the request was refused because of them.
+    pub validation_errors: Option<ValidationErrors>,
+}
+
+impl ApiErrorDetails {
+    /// The HTTP status of the response, if the error was built from a response with an HTTP
+    /// error status.
+    pub fn status(&self) -> Option<u16> {
+        self.headers.status
+    }
+
+    /// The kind of an error with `message`, falling back on the status of the response.
+    fn kind_of(message: &str, headers: &ResponseHeaders) -> ChapaApiErrorKind {
+        match ChapaApiErrorKind::from_message(message) {
+            ChapaApiErrorKind::Other if headers.status == Some(429) => {
+                ChapaApiErrorKind::RateLimited
+            }
+            kind => kind,
+        }
+    }
+}
+
+impl fmt::Display for ApiErrorDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if let Some(request_id) = &self.headers.request_id {
+            write!(f, " (request id: {})", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl ChapaError {
+    /// The maximum size, in bytes, of the body kept by [`ChapaError::Deserialization`].
+    pub const BODY_EXCERPT_LIMIT: usize = 4096;
+
+    /// Builds a [`ChapaError::Deserialization`] from the error of the deserializer, the `body`
+    /// of the response (truncated) and the path of the request.
+    pub fn deserialization(
+        source: serde_json::Error,
+        body: &[u8],
+        endpoint: impl Into<String>,
+    ) -> Self {
+        let excerpt = &body[..body.len().min(Self::BODY_EXCERPT_LIMIT)];
+        ChapaError::Deserialization {
+            source,
+            body: String::from_utf8_lossy(excerpt).into_owned(),
+            endpoint: endpoint.into(),
+        }
+    }
+
+    /// Builds a [`ChapaError::ApiError`] from `message`, without response headers.
+    pub fn api_error(message: impl Into<String>) -> Self {
+        Self::api_error_with_headers(message, ResponseHeaders::default())
+    }
+
+    /// Builds a [`ChapaError::ApiError`] from `message` and the headers of the response.
+    pub fn api_error_with_headers(message: impl Into<String>, headers: ResponseHeaders) -> Self {
+        let message = message.into();
+        ChapaError::ApiError(Box::new(ApiErrorDetails {
+            kind: ApiErrorDetails::kind_of(&message, &headers),
+            message,
+            headers,
+            body: None,
+            validation_errors: None,
+        }))
+    }
+
+    /// Builds a [`ChapaError::ApiError`] from a response with an HTTP error status, given its
+    /// selected `headers` (including the status) and its `body`. The message is the `message`
+    /// of the body, or the status when the body has none.
+    pub fn api_error_from_response(headers: ResponseHeaders, body: &[u8]) -> Self {
+        let body = serde_json::from_slice::<Value>(body).ok().or_else(|| {
+            let text = String::from_utf8_lossy(body);
+            (!text.trim().is_empty()).then(|| Value::String(text.into_owned()))
+        });
+        let parsed = body
+            .as_ref()
+            .and_then(|body| body.get("message"))
+            .and_then(|message| serde_json::from_value::<Message>(message.clone()).ok())
+            .filter(|message| !message.is_empty());
+        let message = parsed
+            .as_ref()
+            .map(Message::to_display_string)
+            .unwrap_or_else(|| match headers.status {
+                Some(status) => format!("HTTP status {}", status),
+                None => "HTTP error status".to_string(),
+            });
+        ChapaError::ApiError(Box::new(ApiErrorDetails {
+            kind: ApiErrorDetails::kind_of(&message, &headers),
+            message,
+            headers,
+            body,
+            validation_errors: parsed.and_then(|message| message.field_errors().cloned()),
+        }))
+    }
+
+    /// Attaches `errors` to a [`ChapaError::ApiError`], leaving other errors untouched.
+    pub fn with_validation_errors(mut self, errors: Option<ValidationErrors>) -> Self {
+        if let ChapaError::ApiError(details) = &mut self {
+            details.validation_errors = errors;
+        }
+        self
+    }
+
+    /// The validation errors per field, for API errors refusing a request because of them.
+    pub fn validation_errors(&self) -> Option<&ValidationErrors> {
+        match self {
+            ChapaError::ApiError(details) => details.validation_errors.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// The kind of the error, for errors returned by the Chapa API.
+    ///
+    /// # Example
+    /// ```rust
+    /// use chapa_rust::error::{ChapaApiErrorKind, ChapaError};
+    ///
+    /// let error = ChapaError::api_error("Insufficient Balance");
+    /// assert_eq!(error.api_error_kind(), Some(ChapaApiErrorKind::InsufficientBalance));
+    /// ```
+    pub fn api_error_kind(&self) -> Option<ChapaApiErrorKind> {
+        match self {
+            ChapaError::ApiError(details) => Some(details.kind),
+            _ => None,
+        }
+    }
+
+    /// The selected headers of the response, for errors returned by the Chapa API.
+    pub fn response_headers(&self) -> Option<&ResponseHeaders> {
+        match self {
+            ChapaError::ApiError(details) => Some(&details.headers),
+            _ => None,
+        }
+    }
+
+    /// A stable, machine-readable code identifying the kind of error, e.g. `"timeout"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ChapaError::MissingApiKey => "missing_api_key",
+            ChapaError::InvalidApiKey(_) => "invalid_api_key",
+            #[cfg(feature = "async")]
+            ChapaError::NetworkError(_) => "network_error",
+            #[cfg(feature = "async")]
+            ChapaError::ConnectError(_) => "connect_error",
+            ChapaError::Timeout { .. } => "timeout",
+            #[cfg(feature = "async")]
+            ChapaError::BodyError(_) => "body_error",
+            ChapaError::TransportError(_) => "transport_error",
+            ChapaError::RateLimited { .. } => "rate_limited",
+            #[cfg(feature = "async")]
+            ChapaError::DecodeError(_) => "decode_error",
+            #[cfg(feature = "async")]
+            ChapaError::RedirectError(_) => "redirect_error",
+            ChapaError::InvalidHttpMethod(_) => "invalid_http_method",
+            ChapaError::ApiError(_) => "api_error",
+            ChapaError::InvalidHeaderValue(_) => "invalid_header_value",
+            ChapaError::InvalidHeaderName(_) => "invalid_header_name",
+            ChapaError::EnvironmentMismatch { .. } => "environment_mismatch",
+            ChapaError::InvalidEnvVar { .. } => "invalid_env_var",
+            ChapaError::InvalidBaseUrl(_) => "invalid_base_url",
+            ChapaError::InsecureBaseUrl(_) => "insecure_base_url",
+            ChapaError::InvalidCertificate(_) => "invalid_certificate",
+            ChapaError::DuplicateReference(_) => "duplicate_reference",
+            ChapaError::MissingReference(_) => "missing_reference",
+            ChapaError::InvalidCustomization(_) => "invalid_customization",
+            ChapaError::InvalidAmount(_) => "invalid_amount",
+            ChapaError::InvalidBeneficiary(_) => "invalid_beneficiary",
+            ChapaError::GuardrailExceeded(_) => "guardrail_exceeded",
+            ChapaError::InsufficientLocalBalanceCheck { .. } => "insufficient_balance",
+            ChapaError::SwapAmountTooLow { .. } => "swap_amount_too_low",
+            ChapaError::SwapAmountTooHigh { .. } => "swap_amount_too_high",
+            ChapaError::UnsupportedSwapPair { .. } => "unsupported_swap_pair",
+            ChapaError::SplitMismatch(_) => "split_mismatch",
+            ChapaError::InvalidSignature(_) => "invalid_signature",
+            ChapaError::StaleWebhook(_) => "stale_webhook",
+            ChapaError::DuplicateWebhook(_) => "duplicate_webhook",
+            ChapaError::UnknownBulkRun(_) => "unknown_bulk_run",
+            ChapaError::ExportJobMismatch(_) => "export_job_mismatch",
+            ChapaError::LookupLimitReached(_) => "lookup_limit_reached",
+            ChapaError::AuditChainBroken(_) => "audit_chain_broken",
+            ChapaError::UnknownExchangeRate(_) => "unknown_exchange_rate",
+            ChapaError::ResponseTooLarge(_) => "response_too_large",
+            ChapaError::Deserialization { .. } => "deserialization_error",
+            ChapaError::JsonError(_) => "json_error",
+            ChapaError::IoError(_) => "io_error",
+        }
+    }
+}
+
+/// The severity class of a [`ChapaError`], see [`ChapaError::class`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// The request was refused because of its content (amount, beneficiary, reference, ...):
+    /// report it to the user, e.g. with a 4xx status. Retrying the same request won't help.
+    UserError,
+    /// The SDK is misconfigured (API key, base URL, certificates, stores): fix the deployment.
+    ConfigError,
+    /// The request failed on the way (connection, timeout, rate limit): retry later.
+    Transient,
+    /// Chapa answered with a server error or an unreadable response: retry later, and alert if
+    /// it lasts.
+    ChapaOutage,
+    /// An invariant was broken, in the SDK or in Chapa's data: page someone.
+    Bug,
+}
+
+impl ErrorClass {
+    /// Returns `true` if sending the same request again later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ErrorClass::Transient | ErrorClass::ChapaOutage)
+    }
+}
+
+impl ChapaError {
+    /// The severity class of the error.
+    ///
+    /// Errors returned by the Chapa API are classified by their HTTP status and kind: a refused
+    /// API key (`401`, `403` or [`ChapaApiErrorKind::InvalidApiKey`]) is
+    /// [`ErrorClass::ConfigError`], `429` is [`ErrorClass::Transient`], `5xx` is
+    /// [`ErrorClass::ChapaOutage`], and other statuses, as well as API errors raised locally
+    /// from a response body, are [`ErrorClass::UserError`].
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            ChapaError::DuplicateReference(_)
+            | ChapaError::MissingReference(_)
+            | ChapaError::InvalidCustomization(_)
+            | ChapaError::InvalidAmount(_)
+            | ChapaError::InvalidBeneficiary(_)
+            | ChapaError::GuardrailExceeded(_)
+            | ChapaError::InsufficientLocalBalanceCheck { .. }
+            | ChapaError::SwapAmountTooLow { .. }
+            | ChapaError::SwapAmountTooHigh { .. }
+            | ChapaError::UnsupportedSwapPair { .. }
+            | ChapaError::InvalidSignature(_)
+            | ChapaError::StaleWebhook(_)
+            | ChapaError::DuplicateWebhook(_)
+            | ChapaError::UnknownExchangeRate(_) => ErrorClass::UserError,
+            ChapaError::MissingApiKey
+            | ChapaError::InvalidApiKey(_)
+            | ChapaError::InvalidHeaderValue(_)
+            | ChapaError::InvalidHeaderName(_)
+            | ChapaError::EnvironmentMismatch { .. }
+            | ChapaError::InvalidEnvVar { .. }
+            | ChapaError::InvalidBaseUrl(_)
+            | ChapaError::InsecureBaseUrl(_)
+            | ChapaError::InvalidCertificate(_)
+            | ChapaError::UnknownBulkRun(_)
+            | ChapaError::ExportJobMismatch(_)
+            | ChapaError::ResponseTooLarge(_)
+            | ChapaError::IoError(_) => ErrorClass::ConfigError,
+            #[cfg(feature = "async")]
+            ChapaError::NetworkError(_)
+            | ChapaError::ConnectError(_)
+            | ChapaError::BodyError(_) => ErrorClass::Transient,
+            ChapaError::Timeout { .. }
+            | ChapaError::TransportError(_)
+            | ChapaError::RateLimited { .. } => ErrorClass::Transient,
+            #[cfg(feature = "async")]
+            ChapaError::DecodeError(_) | ChapaError::RedirectError(_) => ErrorClass::ChapaOutage,
+            ChapaError::LookupLimitReached(_) | ChapaError::Deserialization { .. } => {
+                ErrorClass::ChapaOutage
+            }
+            ChapaError::InvalidHttpMethod(_)
+            | ChapaError::SplitMismatch(_)
+            | ChapaError::AuditChainBroken(_)
+            | ChapaError::JsonError(_) => ErrorClass::Bug,
+            ChapaError::ApiError(details) => match details.headers.status {
+                _ if details.kind == ChapaApiErrorKind::InvalidApiKey => ErrorClass::ConfigError,
+                Some(401 | 403) => ErrorClass::ConfigError,
+                Some(429) => ErrorClass::Transient,
+                Some(status) if status >= 500 => ErrorClass::ChapaOutage,
+                _ => ErrorClass::UserError,
+            },
+        }
+    }
+
+    /// Returns `true` if sending the same request again later may succeed: network failures and
+    /// timeouts, and API errors that are rate limited (`429`) or returned by an unavailable
+    /// gateway (`502`, `503`, `504`).
+    ///
+    /// Stricter than [`ErrorClass::is_retryable`]: other `5xx` statuses and unreadable responses
+    /// are not retryable, as Chapa may have processed the request, and an API error carrying an
+    /// invalid key or validation errors is permanent whatever its status.
+    ///
+    /// # Example
+    /// ```rust
+    /// use chapa_rust::error::ChapaError;
+    ///
+    /// assert!(ChapaError::TransportError("connection reset".to_string()).is_retryable());
+    /// assert!(!ChapaError::InvalidApiKey("not a secret key".to_string()).is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ChapaError::ApiError(details) => {
+                let permanent = details.kind == ChapaApiErrorKind::InvalidApiKey
+                    || details
+                        .validation_errors
+                        .as_ref()
+                        .is_some_and(|errors| !errors.is_empty());
+                !permanent
+                    && (details.kind == ChapaApiErrorKind::RateLimited
+                        || matches!(details.headers.status, Some(429 | 502 | 503 | 504)))
+            }
+            other => other.class() == ErrorClass::Transient,
+        }
+    }
+}
+
+impl ChapaError {
+    /// The HTTP status a web handler should answer its own client with for the error.
+    ///
+    /// Errors caused by the request are `4xx` (`422` for refused amounts and details, `409` for
+    /// a reused reference, `401` for a bad webhook signature), failures reaching Chapa are `502`
+    /// (`504` for timeouts, `503` when rate limited), and misconfigurations and bugs are `500`.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ChapaError::DuplicateReference(_) => 409,
+            ChapaError::InvalidSignature(_) => 401,
+            ChapaError::StaleWebhook(_) | ChapaError::DuplicateWebhook(_) => 400,
+            ChapaError::Timeout { .. } => 504,
+            ChapaError::RateLimited { .. } => 503,
+            ChapaError::ApiError(_) => match self.class() {
+                ErrorClass::UserError => 400,
+                ErrorClass::Transient => 503,
+                ErrorClass::ChapaOutage => 502,
+                ErrorClass::ConfigError | ErrorClass::Bug => 500,
+            },
+            error => match error.class() {
+                ErrorClass::UserError => 422,
+                ErrorClass::Transient | ErrorClass::ChapaOutage => 502,
+                ErrorClass::ConfigError | ErrorClass::Bug => 500,
+            },
+        }
+    }
+}
+
+impl Serialize for ChapaError {
+    /// Serializes the error as `{"code": ..., "message": ...}`, with the `headers` of the
+    /// response for API errors that have any.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let headers = self
+            .response_headers()
+            .filter(|headers| !headers.is_empty());
+        let mut state =
+            serializer.serialize_struct("ChapaError", 2 + headers.is_some() as usize)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let Some(headers) = headers {
+            state.serialize_field("headers", headers)?;
+        }
+        state.end()
+    }
+}
+
+#[cfg(feature = "miette")]
+impl ChapaError {
+    /// An actionable hint for the error, shown by `miette` reports.
+    fn help_text(&self) -> Option<&'static str> {
+        let help = match self {
+            ChapaError::MissingApiKey => {
+                "check CHAPA_SECRET_KEY: set it to your secret key, or call the builder's api_key()"
+            }
+            ChapaError::InvalidApiKey(_) => {
+                "check the key in your Chapa dashboard, secret keys start with CHASECK_TEST- or CHASECK-"
+            }
+            ChapaError::EnvironmentMismatch { .. } => {
+                "use a test key (CHASECK_TEST-) for the test environment and a live key (CHASECK-) for live"
+            }
+            #[cfg(feature = "async")]
+            ChapaError::ConnectError(_) | ChapaError::NetworkError(_) => {
+                "check your network connection and that the base URL is reachable"
+            }
+            ChapaError::TransportError(_) => {
+                "check your network connection and that the base URL is reachable"
+            }
+            ChapaError::Timeout { .. } => {
+                "retry the request, or raise the timeout with the builder's timeout()"
+            }
+            ChapaError::RateLimited { .. } => {
+                "wait for retry_after before retrying, or space out requests with the builder's rate_limit()"
+            }
+            ChapaError::InvalidEnvVar { .. } => {
+                "fix or unset the variable, see ChapaConfig::from_env for the expected formats"
+            }
+            ChapaError::InvalidBaseUrl(_) => {
+                "the base URL must be absolute, e.g. https://api.chapa.co"
+            }
+            ChapaError::InsecureBaseUrl(_) => {
+                "use an https:// URL, allow_insecure() is only meant for local mock servers"
+            }
+            ChapaError::InvalidCertificate(_) => "root certificates must be PEM encoded",
+            ChapaError::DuplicateReference(_) => {
+                "generate a new reference, or verify the earlier request before retrying"
+            }
+            ChapaError::MissingReference(_) => {
+                "set a unique reference, e.g. with TransferOptions::reference_from_seed"
+            }
+            ChapaError::InvalidCustomization(_) => {
+                "shorten the checkout title (16 characters) or description (50 characters)"
+            }
+            ChapaError::InvalidAmount(_) => {
+                "check the amount against the minimums of the currency (see the rules module)"
+            }
+            ChapaError::InvalidBeneficiary(_) => {
+                "check the bank code and account number against the banks listed by get_banks()"
+            }
+            ChapaError::GuardrailExceeded(_) => {
+                "check the amount, and send intended large amounts with without_guardrails()"
+            }
+            ChapaError::InsufficientLocalBalanceCheck { .. } => {
+                "top up the balance by at least the shortfall, or split the bulk transfer"
+            }
+            ChapaError::SwapAmountTooLow { .. } | ChapaError::SwapAmountTooHigh { .. } => {
+                "swap an amount between the minimum and maximum attached to the error, or split it"
+            }
+            ChapaError::UnsupportedSwapPair { .. } => {
+                "Chapa swaps foreign currency balances to ETB, e.g. from USD to ETB"
+            }
+            ChapaError::SplitMismatch(_) => {
+                "compare the subaccounts of the payment with the split settings of the subaccounts"
+            }
+            ChapaError::InvalidSignature(_) => {
+                "check that the webhook secret matches the one set in the Chapa dashboard"
+            }
+            ChapaError::StaleWebhook(_) => {
+                "check the server clock, or raise the tolerance of the ReplayGuard"
+            }
+            ChapaError::UnknownBulkRun(_) => {
+                "configure a bulk progress store and start the run with bulk_transfer_run()"
+            }
+            ChapaError::ExportJobMismatch(_) => "give every export job its own job id",
+            ChapaError::AuditChainBroken(_) => {
+                "the audit log was modified after it was written, restore it from a trusted copy"
+            }
+            ChapaError::UnknownExchangeRate(_) => {
+                "swap or record a rate for the currency pair before converting"
+            }
+            ChapaError::ResponseTooLarge(_) => {
+                "raise the builder's max_list_response_size() or request smaller pages"
+            }
+            ChapaError::Deserialization { .. } => {
+                "the response doesn't match the SDK models, report the body attached to the error"
+            }
+            ChapaError::ApiError(_) => {
+                "check the message returned by Chapa, e.g. the bank code must be one listed by get_banks()"
+            }
+            _ => return None,
+        };
+        Some(help)
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for ChapaError {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        Some(Box::new(format!("chapa::{}", ChapaError::code(self))))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.help_text()
+            .map(|help| Box::new(help) as Box<dyn std::fmt::Display>)
+    }
+}
+
+/// Answers with [`ChapaError::http_status`] and the error serialized as JSON, with a
+/// `Retry-After` header when rate limited. The message of `500` errors is not exposed, only
+/// their code.
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for ChapaError {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::{HeaderValue, StatusCode, header};
+
+        let status =
+            StatusCode::from_u16(self.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let body = if status == StatusCode::INTERNAL_SERVER_ERROR {
+            serde_json::json!({"code": self.code(), "message": "Internal server error"})
+        } else {
+            serde_json::to_value(&self).unwrap_or_default()
+        };
+        let mut response = (
+            status,
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            )],
+            body.to_string(),
+        )
+            .into_response();
+        let retry_after = match &self {
+            ChapaError::RateLimited { retry_after } => *retry_after,
+            other => other
+                .response_headers()
+                .and_then(ResponseHeaders::retry_after_delay),
+        };
+        if let Some(delay) = retry_after.filter(|_| status == StatusCode::SERVICE_UNAVAILABLE) {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(delay.as_secs()));
+        }
+        response
+    }
+}
+
+/// Helper function to describe the durations of a [`ChapaError::Timeout`], as far as they are
+/// known.
+fn timeout_details(elapsed: Option<Duration>, configured: Option<Duration>) -> String {
+    match (elapsed, configured) {
+        (Some(elapsed), Some(configured)) => format!(
+            " after {}ms (timeout of {}ms)",
+            elapsed.as_millis(),
+            configured.as_millis()
+        ),
+        (Some(elapsed), None) => format!(" after {}ms", elapsed.as_millis()),
+        (None, Some(configured)) => format!(" (timeout of {}ms)", configured.as_millis()),
+        (None, None) => String::new(),
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<reqwest::Error> for ChapaError {
+    /// Classifies a [`reqwest::Error`] into the most specific network variant.
+    ///
+    /// The durations of a [`ChapaError::Timeout`] aren't known from the error alone and are left
+    /// unset; the client fills them in.
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            ChapaError::Timeout {
+                elapsed: None,
+                configured: None,
+                source: Box::new(error),
+            }
+        } else if error.is_connect() {
+            ChapaError::ConnectError(error)
+        } else if error.is_redirect() {
+            ChapaError::RedirectError(error)
+        } else if error.is_decode() {
+            ChapaError::DecodeError(error)
+        } else if error.is_body() {
+            ChapaError::BodyError(error)
+        } else {
+            ChapaError::NetworkError(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_error() {
+        let error = ChapaError::DuplicateReference("payout-1".to_string());
+        assert_eq!(error.code(), "duplicate_reference");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "duplicate_reference",
+                "message": "Reference has already been submitted: payout-1"
+            })
+        );
+    }
+
+    #[test]
+    fn test_api_error_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", "req-42".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        let error = ChapaError::api_error_with_headers(
+            "Too many requests",
+            ResponseHeaders::from_headers(&headers),
+        );
+        assert_eq!(
+            error.to_string(),
+            "API error occurred: Too many requests (request id: req-42)"
+        );
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["headers"],
+            serde_json::json!({"request_id": "req-42", "rate_limit_remaining": "0"})
+        );
+        assert!(
+            serde_json::to_value(ChapaError::api_error("Invalid Bank"))
+                .unwrap()
+                .get("headers")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_api_error_from_response() {
+        let headers = ResponseHeaders {
+            status: Some(401),
+            ..ResponseHeaders::default()
+        };
+        let error = ChapaError::api_error_from_response(
+            headers.clone(),
+            br#"{"message":"Invalid API Key","status":"failed","data":null}"#,
+        );
+        let ChapaError::ApiError(details) = &error else {
+            panic!("expected an API error, got {:?}", error);
+        };
+        assert_eq!(details.message, "Invalid API Key");
+        assert_eq!(details.status(), Some(401));
+        assert_eq!(details.body.as_ref().unwrap()["status"], "failed");
+
+        let error = ChapaError::api_error_from_response(headers, b"<html>Bad Gateway</html>");
+        let ChapaError::ApiError(details) = &error else {
+            panic!("expected an API error, got {:?}", error);
+        };
+        assert_eq!(details.message, "HTTP status 401");
+        assert_eq!(
+            details.body,
+            Some(Value::String("<html>Bad Gateway</html>".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_api_error_kind() {
+        for (message, kind) in [
+            ("Invalid API Key", ChapaApiErrorKind::InvalidApiKey),
+            (
+                "Insufficient Balance",
+                ChapaApiErrorKind::InsufficientBalance,
+            ),
+            (
+                "Transaction reference has been used before",
+                ChapaApiErrorKind::DuplicateReference,
+            ),
+            (
+                "Invalid transaction or Transaction not found",
+                ChapaApiErrorKind::TransactionNotFound,
+            ),
+            (
+                "Transfer reference already exists",
+                ChapaApiErrorKind::DuplicateReference,
+            ),
+            ("Reference does not exist", ChapaApiErrorKind::Other),
+            ("Invalid Bank", ChapaApiErrorKind::InvalidBank),
+            ("Too Many Attempts.", ChapaApiErrorKind::RateLimited),
+            ("Something went wrong", ChapaApiErrorKind::Other),
+        ] {
+            assert_eq!(
+                ChapaApiErrorKind::from_message(message),
+                kind,
+                "{}",
+                message
+            );
+        }
+        let rate_limited = ChapaError::api_error_with_headers(
+            "Slow down",
+            ResponseHeaders {
+                status: Some(429),
+                ..ResponseHeaders::default()
+            },
+        );
+        assert_eq!(
+            rate_limited.api_error_kind(),
+            Some(ChapaApiErrorKind::RateLimited)
+        );
+        assert_eq!(ChapaError::MissingApiKey.api_error_kind(), None);
+    }
+
+    #[test]
+    fn test_error_class() {
+        let api_error = |status: Option<u16>| {
+            ChapaError::api_error_with_headers(
+                "error",
+                ResponseHeaders {
+                    status,
+                    ..ResponseHeaders::default()
+                },
+            )
+        };
+        assert_eq!(api_error(Some(400)).class(), ErrorClass::UserError);
+        assert_eq!(api_error(None).class(), ErrorClass::UserError);
+        assert_eq!(api_error(Some(429)).class(), ErrorClass::Transient);
+        assert_eq!(api_error(Some(503)).class(), ErrorClass::ChapaOutage);
+        assert_eq!(api_error(Some(403)).class(), ErrorClass::ConfigError);
+        assert_eq!(
+            ChapaError::api_error("Invalid API Key").class(),
+            ErrorClass::ConfigError
+        );
+        assert!(api_error(Some(503)).class().is_retryable());
+        assert_eq!(ChapaError::MissingApiKey.class(), ErrorClass::ConfigError);
+        assert_eq!(
+            ChapaError::InvalidAmount("1 ETB".to_string()).class(),
+            ErrorClass::UserError
+        );
+        assert!(!ErrorClass::UserError.is_retryable());
+    }
+
+    #[test]
+    fn test_retry_after_delay() {
+        let headers = |retry_after: &str| ResponseHeaders {
+            retry_after: Some(retry_after.to_string()),
+            ..ResponseHeaders::default()
+        };
+        assert_eq!(
+            headers("30").retry_after_delay(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            headers("Wed, 21 Oct 2015 07:28:00 GMT").retry_after_delay(),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(headers("soon").retry_after_delay(), None);
+        assert_eq!(
+            ChapaError::RateLimited {
+                retry_after: Some(Duration::from_secs(5))
+            }
+            .to_string(),
+            "Rate limited by the Chapa API, retry after 5s"
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let api_error = |message: &str, status: u16| {
+            ChapaError::api_error_with_headers(
+                message,
+                ResponseHeaders {
+                    status: Some(status),
+                    ..ResponseHeaders::default()
+                },
+            )
+        };
+        assert!(api_error("Too many requests", 429).is_retryable());
+        assert!(api_error("Bad gateway", 502).is_retryable());
+        assert!(api_error("Service unavailable", 503).is_retryable());
+        assert!(!api_error("Internal server error", 500).is_retryable());
+        assert!(!api_error("Invalid API Key", 503).is_retryable());
+        assert!(!api_error("Invalid currency", 400).is_retryable());
+        assert!(ChapaError::TransportError("connection reset".to_string()).is_retryable());
+        assert!(!ChapaError::MissingApiKey.is_retryable());
+        assert!(!ChapaError::InvalidAmount("1 ETB".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_http_status() {
+        assert_eq!(
+            ChapaError::InvalidAmount("1 ETB".to_string()).http_status(),
+            422
+        );
+        assert_eq!(
+            ChapaError::DuplicateReference("payout-1".to_string()).http_status(),
+            409
+        );
+        assert_eq!(ChapaError::MissingApiKey.http_status(), 500);
+        assert_eq!(ChapaError::api_error("Invalid currency").http_status(), 400);
+        assert_eq!(ChapaError::api_error("Invalid API Key").http_status(), 500);
+        assert_eq!(
+            ChapaError::api_error_with_headers(
+                "Service Unavailable",
+                ResponseHeaders {
+                    status: Some(503),
+                    ..ResponseHeaders::default()
+                }
+            )
+            .http_status(),
+            502
+        );
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_into_response() {
+        use axum::{http::StatusCode, response::IntoResponse};
+
+        let response =
+            ChapaError::InvalidAmount("1 ETB is below the minimum".to_string()).into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "invalid_amount");
+
+        let response = ChapaError::RateLimited {
+            retry_after: Some(Duration::from_secs(30)),
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()["retry-after"], "30");
+
+        let response = ChapaError::InvalidApiKey("CHASECK_TEST-leaked".to_string()).into_response();
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&body).contains("leaked"));
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_diagnostic_help() {
+        use miette::Diagnostic;
+
+        let error = ChapaError::MissingApiKey;
+        assert_eq!(
+            Diagnostic::code(&error).unwrap().to_string(),
+            "chapa::missing_api_key"
+        );
+        assert!(
+            error
+                .help()
+                .unwrap()
+                .to_string()
+                .contains("CHAPA_SECRET_KEY")
+        );
+        assert!(
+            ChapaError::IoError("disk full".to_string())
+                .help()
+                .is_none()
+        );
+    }
 }
@@ -0,0 +1,173 @@
+//! # Exchange Module
+//!
+//! A local cache of the exchange rates observed on swaps, so amounts can be converted for
+//! display without extra API calls. Rates older than the configured maximum age are still
+//! used, but the [`Conversion`] is flagged as stale.
+//!
+//! The client records the rate of every successful [`ChapaClient::swap`](crate::client::ChapaClient::swap)
+//! in the cache of its configuration.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::exchange::ExchangeRates;
+//!
+//! let rates = ExchangeRates::default();
+//! rates.record("USD", "ETB", 57.1);
+//!
+//! let conversion = rates.convert(10.0, "USD", "ETB").unwrap();
+//! assert!((conversion.amount - 571.0).abs() < 1e-9);
+//! assert!(!conversion.stale);
+//! // the inverse rate is derived
+//! assert!(rates.convert(571.0, "ETB", "USD").is_ok());
+//! ```
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::error::{ChapaError, Result};
+
+/// An exchange rate observed at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObservedRate {
+    /// The rate, in target currency per source currency.
+    pub rate: f64,
+    /// When the rate was observed.
+    pub observed_at: DateTime<Utc>,
+}
+
+/// The result of converting an amount with a cached rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conversion {
+    /// The converted amount.
+    pub amount: f64,
+    /// The rate applied.
+    pub rate: f64,
+    /// When the rate was observed.
+    pub observed_at: DateTime<Utc>,
+    /// Whether the rate is older than the maximum age of the cache.
+    pub stale: bool,
+}
+
+/// A cache of observed exchange rates, keyed by currency pair.
+///
+/// Clones share the same rates.
+#[derive(Debug, Clone)]
+pub struct ExchangeRates {
+    max_age: Duration,
+    rates: Arc<RwLock<HashMap<(String, String), ObservedRate>>>,
+}
+
+impl Default for ExchangeRates {
+    /// A cache considering rates stale after one hour.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(60 * 60))
+    }
+}
+
+impl ExchangeRates {
+    /// Creates an empty cache considering rates stale after `max_age`.
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            rates: Arc::default(),
+        }
+    }
+
+    /// Records the rate from `from` to `to`, observed now.
+    pub fn record(&self, from: &str, to: &str, rate: f64) {
+        self.record_at(from, to, rate, Utc::now());
+    }
+
+    /// Records the rate from `from` to `to`, observed at `observed_at`. Older observations
+    /// than the cached one are ignored.
+    pub fn record_at(&self, from: &str, to: &str, rate: f64, observed_at: DateTime<Utc>) {
+        if !(rate.is_finite() && rate > 0.0) {
+            return;
+        }
+        let mut rates = self.rates.write().unwrap_or_else(|e| e.into_inner());
+        let observed = rates
+            .entry(Self::key(from, to))
+            .or_insert(ObservedRate { rate, observed_at });
+        if observed.observed_at <= observed_at {
+            *observed = ObservedRate { rate, observed_at };
+        }
+    }
+
+    /// Returns the latest rate from `from` to `to`, derived from the inverse pair if needed.
+    pub fn rate(&self, from: &str, to: &str) -> Option<ObservedRate> {
+        let rates = self.rates.read().unwrap_or_else(|e| e.into_inner());
+        let direct = rates.get(&Self::key(from, to)).copied();
+        let inverse = rates
+            .get(&Self::key(to, from))
+            .map(|observed| ObservedRate {
+                rate: 1.0 / observed.rate,
+                observed_at: observed.observed_at,
+            });
+        match (direct, inverse) {
+            (Some(direct), Some(inverse)) if inverse.observed_at > direct.observed_at => {
+                Some(inverse)
+            }
+            (direct, inverse) => direct.or(inverse),
+        }
+    }
+
+    /// Converts `amount` from `from` to `to` with the latest cached rate.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::UnknownExchangeRate`] if no rate between the currencies was observed.
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Result<Conversion> {
+        let now = Utc::now();
+        if from.eq_ignore_ascii_case(to) {
+            return Ok(Conversion {
+                amount,
+                rate: 1.0,
+                observed_at: now,
+                stale: false,
+            });
+        }
+        let observed = self
+            .rate(from, to)
+            .ok_or_else(|| ChapaError::UnknownExchangeRate(format!("{} -> {}", from, to)))?;
+        let age = (now - observed.observed_at).to_std().unwrap_or_default();
+        Ok(Conversion {
+            amount: amount * observed.rate,
+            rate: observed.rate,
+            observed_at: observed.observed_at,
+            stale: age > self.max_age,
+        })
+    }
+
+    fn key(from: &str, to: &str) -> (String, String) {
+        (from.to_ascii_uppercase(), to.to_ascii_uppercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_flags_stale_rates() {
+        let rates = ExchangeRates::new(Duration::from_secs(60));
+        assert!(matches!(
+            rates.convert(1.0, "USD", "ETB"),
+            Err(ChapaError::UnknownExchangeRate(_))
+        ));
+
+        let two_hours_ago = Utc::now() - chrono::Duration::hours(2);
+        rates.record_at("usd", "etb", 50.0, two_hours_ago);
+        let conversion = rates.convert(2.0, "USD", "ETB").unwrap();
+        assert_eq!(conversion.amount, 100.0);
+        assert!(conversion.stale);
+
+        // a newer observation of the inverse pair wins.
+        rates.record("ETB", "USD", 0.0125);
+        let conversion = rates.convert(2.0, "USD", "ETB").unwrap();
+        assert_eq!(conversion.rate, 80.0);
+        assert!(!conversion.stale);
+    }
+}
@@ -0,0 +1,145 @@
+//! # Failover Module
+//!
+//! A [`BaseUrlPool`] spreading requests over several base URLs (e.g. regional endpoints, or a
+//! local caching proxy in front of the real API), attached to a client with
+//! [`ChapaConfigBuilder::base_url_pool`](crate::config::ChapaConfigBuilder::base_url_pool).
+//!
+//! Each request is first sent to a URL picked at random in proportion to its weight. If that URL
+//! is unreachable (the connection cannot be established), the request is sent again to the other
+//! URLs, in the order they were added, until one answers. URLs with a weight of `0` only serve as
+//! fallbacks. Requests whose body cannot be replayed (multipart uploads) are not failed over.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::{config::ChapaConfig, failover::BaseUrlPool};
+//!
+//! let pool = BaseUrlPool::new()
+//!     .add("https://chapa-proxy.internal.example.com", 3)
+//!     .add("https://api.chapa.co", 1);
+//! let config = ChapaConfig::builder()
+//!     .api_key("CHASECK_TEST-xxxxxxxxxxxx")
+//!     .base_url_pool(pool)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(config.base_url, "https://chapa-proxy.internal.example.com");
+//! ```
+use std::hash::{BuildHasher, Hasher, RandomState};
+
+#[cfg(feature = "async")]
+use url::Url;
+
+/// A base URL of a [`BaseUrlPool`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedUrl {
+    /// The base URL, e.g. `https://api.chapa.co`.
+    pub url: String,
+    /// The share of requests first sent to this URL, relative to the other weights.
+    pub weight: u32,
+}
+
+/// Base URLs with weights and a failover order, see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BaseUrlPool {
+    urls: Vec<WeightedUrl>,
+}
+
+impl BaseUrlPool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a base URL, after the existing ones in the failover order.
+    pub fn add(mut self, url: impl Into<String>, weight: u32) -> Self {
+        self.urls.push(WeightedUrl {
+            url: url.into(),
+            weight,
+        });
+        self
+    }
+
+    /// The base URLs, in failover order.
+    pub fn urls(&self) -> &[WeightedUrl] {
+        &self.urls
+    }
+
+    /// Picks the base URL to send a request to first, at random in proportion to the weights.
+    /// Falls back to the first URL if every weight is `0`.
+    pub fn pick(&self) -> Option<&str> {
+        let total: u64 = self.urls.iter().map(|entry| u64::from(entry.weight)).sum();
+        if total == 0 {
+            return self.urls.first().map(|entry| entry.url.as_str());
+        }
+        let mut roll = RandomState::new().build_hasher().finish() % total;
+        self.urls
+            .iter()
+            .find(|entry| {
+                let weight = u64::from(entry.weight);
+                if roll < weight {
+                    return true;
+                }
+                roll -= weight;
+                false
+            })
+            .map(|entry| entry.url.as_str())
+    }
+
+    /// The URLs to retry a request sent to `url` with, on the other base URLs in failover order.
+    /// Empty if `url` doesn't start with a base URL of the pool.
+    #[cfg(feature = "async")]
+    pub(crate) fn fallbacks(&self, url: &Url) -> Vec<Url> {
+        let url = url.as_str();
+        let Some((used, path)) = self.urls.iter().find_map(|entry| {
+            let base = entry.url.trim_end_matches('/');
+            url.strip_prefix(base)
+                .filter(|path| path.is_empty() || path.starts_with('/'))
+                .map(|path| (base, path))
+        }) else {
+            return Vec::new();
+        };
+        self.urls
+            .iter()
+            .map(|entry| entry.url.trim_end_matches('/'))
+            .filter(|base| *base != used)
+            .filter_map(|base| Url::parse(&format!("{}{}", base, path)).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_pick_and_fallbacks() {
+        let pool = BaseUrlPool::new()
+            .add("https://proxy.example.com/chapa/", 0)
+            .add("https://eu.example.com", 1)
+            .add("https://api.chapa.co", 0);
+
+        for _ in 0..20 {
+            assert_eq!(pool.pick(), Some("https://eu.example.com"));
+        }
+        assert_eq!(
+            BaseUrlPool::new().add("https://a.example.com", 0).pick(),
+            Some("https://a.example.com")
+        );
+        assert_eq!(BaseUrlPool::new().pick(), None);
+
+        #[cfg(feature = "async")]
+        {
+            let url = Url::parse("https://eu.example.com/v1/banks?page=2").unwrap();
+            let fallbacks: Vec<String> =
+                pool.fallbacks(&url).into_iter().map(String::from).collect();
+            assert_eq!(
+                fallbacks,
+                vec![
+                    "https://proxy.example.com/chapa/v1/banks?page=2",
+                    "https://api.chapa.co/v1/banks?page=2"
+                ]
+            );
+            let elsewhere = Url::parse("https://other.example.com/v1/banks").unwrap();
+            assert!(pool.fallbacks(&elsewhere).is_empty());
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! # Fixtures Module
+//!
+//! A corpus of real Chapa API response payloads, available behind the `fixtures` feature.
+//! Each payload is embedded as a JSON string constant and paired with a helper that
+//! deserializes it into the matching model, so both this crate and downstream users can
+//! regression-test model changes against real-world payloads.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::fixtures;
+//!
+//! let banks = fixtures::get_banks().unwrap();
+//! assert!(banks.data.is_some());
+//!
+//! // or deserialize every fixture at once
+//! fixtures::check_all().unwrap();
+//! ```
+use crate::{
+    error::Result,
+    models::{
+        response::{GetBanksResponse, InitializeResponse, VerifyResponse},
+        transaction::GetTransactionsResponse,
+    },
+};
+
+/// `GET /banks` with a bank and a mobile money wallet.
+pub const GET_BANKS: &str = include_str!("../fixtures/get_banks.json");
+/// `GET /banks` called with an invalid API key.
+pub const GET_BANKS_INVALID_KEY: &str = include_str!("../fixtures/get_banks_invalid_key.json");
+/// `POST /transaction/initialize` returning a checkout URL.
+pub const INITIALIZE_SUCCESS: &str = include_str!("../fixtures/initialize_success.json");
+/// `POST /transaction/initialize` called without authorization.
+pub const INITIALIZE_UNAUTHORIZED: &str = include_str!("../fixtures/initialize_unauthorized.json");
+/// `GET /transaction/verify/{tx_ref}` for a successful payment.
+pub const VERIFY_SUCCESS: &str = include_str!("../fixtures/verify_success.json");
+/// `GET /transaction/verify/{tx_ref}` for an unknown reference.
+pub const VERIFY_NOT_FOUND: &str = include_str!("../fixtures/verify_not_found.json");
+/// `GET /transactions` with a single page of transactions.
+pub const GET_TRANSACTIONS: &str = include_str!("../fixtures/get_transactions.json");
+/// `GET /transactions` with transactions of customers who left every detail empty.
+pub const GET_TRANSACTIONS_NULL_CUSTOMER: &str =
+    include_str!("../fixtures/get_transactions_null_customer.json");
+
+/// Deserializes [`GET_BANKS`].
+pub fn get_banks() -> Result<GetBanksResponse> {
+    Ok(serde_json::from_str(GET_BANKS)?)
+}
+
+/// Deserializes [`GET_BANKS_INVALID_KEY`].
+pub fn get_banks_invalid_key() -> Result<GetBanksResponse> {
+    Ok(serde_json::from_str(GET_BANKS_INVALID_KEY)?)
+}
+
+/// Deserializes [`INITIALIZE_SUCCESS`].
+pub fn initialize_success() -> Result<InitializeResponse> {
+    Ok(serde_json::from_str(INITIALIZE_SUCCESS)?)
+}
+
+/// Deserializes [`INITIALIZE_UNAUTHORIZED`].
+pub fn initialize_unauthorized() -> Result<InitializeResponse> {
+    Ok(serde_json::from_str(INITIALIZE_UNAUTHORIZED)?)
+}
+
+/// Deserializes [`VERIFY_SUCCESS`].
+pub fn verify_success() -> Result<VerifyResponse> {
+    Ok(serde_json::from_str(VERIFY_SUCCESS)?)
+}
+
+/// Deserializes [`VERIFY_NOT_FOUND`].
+pub fn verify_not_found() -> Result<VerifyResponse> {
+    Ok(serde_json::from_str(VERIFY_NOT_FOUND)?)
+}
+
+/// Deserializes [`GET_TRANSACTIONS`].
+pub fn get_transactions() -> Result<GetTransactionsResponse> {
+    Ok(serde_json::from_str(GET_TRANSACTIONS)?)
+}
+
+/// Deserializes [`GET_TRANSACTIONS_NULL_CUSTOMER`].
+pub fn get_transactions_null_customer() -> Result<GetTransactionsResponse> {
+    Ok(serde_json::from_str(GET_TRANSACTIONS_NULL_CUSTOMER)?)
+}
+
+/// Deserializes every fixture into its model, failing on the first payload that no longer parses.
+pub fn check_all() -> Result<()> {
+    get_banks()?;
+    get_banks_invalid_key()?;
+    initialize_success()?;
+    initialize_unauthorized()?;
+    verify_success()?;
+    verify_not_found()?;
+    get_transactions()?;
+    get_transactions_null_customer()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_deserialize() {
+        check_all().unwrap();
+
+        assert_eq!(get_banks().unwrap().data.unwrap().len(), 2);
+        assert!(get_banks_invalid_key().unwrap().data.is_none());
+        assert_eq!(initialize_success().unwrap().status, "success");
+        assert_eq!(initialize_unauthorized().unwrap().status, "failed");
+        assert_eq!(
+            verify_success().unwrap().data.unwrap().tx_ref(),
+            Some("chewatatest-6669")
+        );
+        assert!(verify_not_found().unwrap().data.is_none());
+        assert_eq!(get_transactions().unwrap().data.transactions.len(), 1);
+
+        let null_customer = get_transactions_null_customer().unwrap().data.transactions;
+        assert!(null_customer[0].trans_id.is_none());
+        assert!(null_customer[0].customer.email.is_none());
+        let payment =
+            crate::models::normalized::Payment::from(null_customer.into_iter().nth(1).unwrap());
+        assert_eq!(payment.customer.mobile.as_deref(), Some("0911223344"));
+        assert!(payment.customer.first_name.is_none());
+    }
+}
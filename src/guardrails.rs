@@ -0,0 +1,218 @@
+//! # Guardrails Module
+//!
+//! Upper limits on the amounts moved by the client, checked before anything is sent, to reduce
+//! the blast radius of bugs (a misplaced decimal point, a loop initializing checkouts forever)
+//! that would otherwise move real money. Attach [`Guardrails`] with
+//! [`ChapaConfigBuilder::guardrails`](crate::config::ChapaConfigBuilder::guardrails).
+//!
+//! Three limits can be set per currency:
+//! - the amount of a single transfer;
+//! - the total of a bulk transfer batch;
+//! - the total amount of the checkouts initialized during the current UTC day, tracked in
+//!   process (each client process keeps its own tally, which restarts with the process).
+//!
+//! A request breaking a limit fails with [`ChapaError::GuardrailExceeded`]. When a large amount
+//! is intended, send it through
+//! [`ChapaClient::without_guardrails`](crate::client::ChapaClient::without_guardrails), an
+//! explicit override that is easy to spot in code review.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::{config::ChapaConfig, guardrails::Guardrails};
+//!
+//! let guardrails = Guardrails::new()
+//!     .max_transfer("ETB", 50_000.0)
+//!     .max_bulk_total("ETB", 250_000.0)
+//!     .max_daily_initialized("ETB", 1_000_000.0);
+//! let config = ChapaConfig::builder()
+//!     .api_key("CHASECK_TEST-xxxxxxxxxxxx")
+//!     .guardrails(guardrails)
+//!     .build()
+//!     .unwrap();
+//! assert!(config.guardrails.unwrap().check_transfer(75_000.0, "ETB").is_err());
+//! ```
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{NaiveDate, Utc};
+
+use crate::error::{ChapaError, Result};
+
+/// Upper limits per currency on the amounts moved by a client, see the
+/// [module documentation](self).
+///
+/// Currencies are matched case-insensitively; currencies without a limit are not limited. Clones
+/// share the same daily tally.
+#[derive(Debug, Clone, Default)]
+pub struct Guardrails {
+    max_transfer: HashMap<String, f64>,
+    max_bulk_total: HashMap<String, f64>,
+    max_daily_initialized: HashMap<String, f64>,
+    initialized: Arc<Mutex<DailyTally>>,
+}
+
+/// The amounts initialized per currency during `day`.
+#[derive(Debug, Default)]
+struct DailyTally {
+    day: Option<NaiveDate>,
+    totals: HashMap<String, f64>,
+}
+
+impl Guardrails {
+    /// Creates guardrails without any limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the amount of a single transfer in `currency` to `max`.
+    pub fn max_transfer(mut self, currency: &str, max: f64) -> Self {
+        self.max_transfer.insert(currency.to_ascii_uppercase(), max);
+        self
+    }
+
+    /// Limits the total of a bulk transfer batch in `currency` to `max`.
+    pub fn max_bulk_total(mut self, currency: &str, max: f64) -> Self {
+        self.max_bulk_total
+            .insert(currency.to_ascii_uppercase(), max);
+        self
+    }
+
+    /// Limits the total amount of the checkouts initialized in `currency` during a UTC day to
+    /// `max`.
+    pub fn max_daily_initialized(mut self, currency: &str, max: f64) -> Self {
+        self.max_daily_initialized
+            .insert(currency.to_ascii_uppercase(), max);
+        self
+    }
+
+    /// Checks the amount of a single transfer.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::GuardrailExceeded`] if the amount is above the limit.
+    pub fn check_transfer(&self, amount: f64, currency: &str) -> Result<()> {
+        Self::check(&self.max_transfer, "single transfer", amount, currency)
+    }
+
+    /// Checks the total of a bulk transfer batch.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::GuardrailExceeded`] if the total is above the limit.
+    pub fn check_bulk_total(&self, total: f64, currency: &str) -> Result<()> {
+        Self::check(&self.max_bulk_total, "bulk transfer batch", total, currency)
+    }
+
+    /// The total amount initialized in `currency` during the current UTC day.
+    pub fn initialized_today(&self, currency: &str) -> f64 {
+        let mut tally = self.lock();
+        tally.roll_over();
+        tally
+            .totals
+            .get(&currency.to_ascii_uppercase())
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Adds `amount` to the daily tally of `currency`, unless it would exceed the daily limit.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::GuardrailExceeded`] if the tally would exceed the limit; the tally
+    /// is then left untouched.
+    pub fn reserve_initialized(&self, amount: f64, currency: &str) -> Result<()> {
+        let currency = currency.to_ascii_uppercase();
+        let mut tally = self.lock();
+        tally.roll_over();
+        let total = tally.totals.entry(currency.clone()).or_insert(0.0);
+        match self.max_daily_initialized.get(&currency) {
+            Some(max) if *total + amount > *max => {
+                return Err(ChapaError::GuardrailExceeded(format!(
+                    "{} {} would bring the checkouts initialized today to {} {}, above the daily limit of {} {}",
+                    amount,
+                    currency,
+                    *total + amount,
+                    currency,
+                    max,
+                    currency
+                )));
+            }
+            _ => {}
+        }
+        *total += amount;
+        Ok(())
+    }
+
+    /// Removes `amount` from the daily tally of `currency`, for a checkout that wasn't
+    /// initialized after all.
+    pub fn release_initialized(&self, amount: f64, currency: &str) {
+        let mut tally = self.lock();
+        tally.roll_over();
+        if let Some(total) = tally.totals.get_mut(&currency.to_ascii_uppercase()) {
+            *total = (*total - amount).max(0.0);
+        }
+    }
+
+    fn check(table: &HashMap<String, f64>, what: &str, amount: f64, currency: &str) -> Result<()> {
+        match table.get(&currency.to_ascii_uppercase()) {
+            Some(max) if amount > *max => Err(ChapaError::GuardrailExceeded(format!(
+                "{} {} is above the {} limit of {} {}",
+                amount, currency, what, max, currency
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DailyTally> {
+        self.initialized.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Parses an amount sent as a string; amounts were already checked by the amount rules, so an
+/// unparsable one counts as zero.
+#[cfg(any(feature = "async", feature = "ureq"))]
+pub(crate) fn parse_amount(amount: &str) -> f64 {
+    amount.trim().parse().unwrap_or(0.0)
+}
+
+impl DailyTally {
+    /// Starts a new tally when the UTC day changed.
+    fn roll_over(&mut self) {
+        let today = Utc::now().date_naive();
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.totals.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guardrails() {
+        let guardrails = Guardrails::new()
+            .max_transfer("etb", 1000.0)
+            .max_bulk_total("ETB", 5000.0)
+            .max_daily_initialized("ETB", 300.0);
+
+        assert!(guardrails.check_transfer(1000.0, "ETB").is_ok());
+        assert!(matches!(
+            guardrails.check_transfer(1000.5, "ETB"),
+            Err(ChapaError::GuardrailExceeded(_))
+        ));
+        assert!(guardrails.check_transfer(1_000_000.0, "USD").is_ok());
+        assert!(guardrails.check_bulk_total(6000.0, "ETB").is_err());
+
+        guardrails.reserve_initialized(200.0, "ETB").unwrap();
+        guardrails
+            .clone()
+            .reserve_initialized(100.0, "ETB")
+            .unwrap();
+        assert!(guardrails.reserve_initialized(1.0, "ETB").is_err());
+        assert_eq!(guardrails.initialized_today("ETB"), 300.0);
+        guardrails.release_initialized(100.0, "ETB");
+        guardrails.reserve_initialized(50.0, "ETB").unwrap();
+        assert_eq!(guardrails.initialized_today("etb"), 250.0);
+    }
+}
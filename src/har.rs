@@ -0,0 +1,398 @@
+//! # HAR Module
+//!
+//! Debugging helper, available behind the `har` feature, that records the HTTP traffic of a
+//! [`ChapaClient`](crate::client::ChapaClient) in the [HAR 1.2](http://www.softwareishard.com/blog/har-12-spec/)
+//! format. The exported file can be opened in browser dev tools or attached to Chapa support tickets.
+//!
+//! Sensitive headers (`Authorization`, API keys, signatures) are redacted before an entry is stored,
+//! so the bearer secret key never ends up in the export. So are the customer and account details
+//! of JSON bodies (names, emails, phone numbers and account numbers), at any depth.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use chapa_rust::{client::ChapaClient, config::ChapaConfig, har::HarRecorder};
+//!
+//! # async fn run() -> chapa_rust::error::Result<()> {
+//! let recorder = HarRecorder::new();
+//! let config = ChapaConfig::builder()
+//!     .api_key("CHASECK_TEST-xxxxxxxxxxxx")
+//!     .har_recorder(recorder.clone())
+//!     .build()?;
+//! let client = ChapaClient::from_config(config)?;
+//! client.get_banks().await?;
+//!
+//! recorder.export_to_file("chapa.har")?;
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+
+use crate::error::{ChapaError, Result};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// A cheaply cloneable handle collecting HAR entries for every request made by the client it is attached to.
+#[derive(Debug, Clone, Default)]
+pub struct HarRecorder {
+    entries: Arc<Mutex<Vec<HarEntry>>>,
+}
+
+impl HarRecorder {
+    /// Creates a new, empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of recorded request/response pairs.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if nothing has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes all recorded entries.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Builds the HAR document from the entries recorded so far.
+    pub fn to_har(&self) -> Har {
+        Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries: self.lock().clone(),
+            },
+        }
+    }
+
+    /// Serializes the recorded traffic as a pretty-printed HAR JSON string.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.to_har())?)
+    }
+
+    /// Writes the recorded traffic as a HAR file to `path`.
+    /// # Errors
+    /// Returns an error if the file can not be written.
+    pub fn export_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path.as_ref(), self.to_json()?)
+            .map_err(|e| ChapaError::IoError(format!("{}: {}", path.as_ref().display(), e)))
+    }
+
+    pub(crate) fn record(&self, entry: HarEntry) {
+        self.lock().push(entry);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<HarEntry>> {
+        // a poisoned recorder only means another thread panicked while pushing, the data is still usable.
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The root of a HAR document.
+#[derive(Debug, Clone, Serialize)]
+pub struct Har {
+    /// The log containing every recorded entry.
+    pub log: HarLog,
+}
+
+/// The `log` object of a HAR document.
+#[derive(Debug, Clone, Serialize)]
+pub struct HarLog {
+    /// The HAR format version, always `1.2`.
+    pub version: String,
+    /// The application that produced the log.
+    pub creator: HarCreator,
+    /// The recorded request/response pairs.
+    pub entries: Vec<HarEntry>,
+}
+
+/// The application that created the HAR document.
+#[derive(Debug, Clone, Serialize)]
+pub struct HarCreator {
+    /// Name of the application.
+    pub name: String,
+    /// Version of the application.
+    pub version: String,
+}
+
+/// A single recorded request/response pair.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    /// When the request was sent.
+    pub started_date_time: DateTime<Utc>,
+    /// Total elapsed time of the request in milliseconds.
+    pub time: f64,
+    /// The recorded request.
+    pub request: HarRequest,
+    /// The recorded response.
+    pub response: HarResponse,
+    /// Cache information, always empty.
+    pub cache: serde_json::Value,
+    /// Timing breakdown of the request.
+    pub timings: HarTimings,
+}
+
+/// The request part of a [`HarEntry`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    /// The HTTP method.
+    pub method: String,
+    /// The absolute request URL.
+    pub url: String,
+    /// The HTTP version.
+    pub http_version: String,
+    /// The request headers, with secrets redacted.
+    pub headers: Vec<HarHeader>,
+    /// The query string parameters.
+    pub query_string: Vec<HarHeader>,
+    /// Cookies sent with the request, always empty.
+    pub cookies: Vec<HarHeader>,
+    /// The size of the headers, `-1` as it is unknown.
+    pub headers_size: i64,
+    /// The size of the request body in bytes.
+    pub body_size: i64,
+    /// The request body, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+}
+
+/// The response part of a [`HarEntry`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The HTTP status text.
+    pub status_text: String,
+    /// The HTTP version.
+    pub http_version: String,
+    /// The response headers, with secrets redacted.
+    pub headers: Vec<HarHeader>,
+    /// Cookies set by the response, always empty.
+    pub cookies: Vec<HarHeader>,
+    /// The response body.
+    pub content: HarContent,
+    /// The redirect target, empty unless redirected.
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    /// The size of the headers, `-1` as it is unknown.
+    pub headers_size: i64,
+    /// The size of the response body in bytes.
+    pub body_size: i64,
+}
+
+/// A name/value pair used for headers and query parameters.
+#[derive(Debug, Clone, Serialize)]
+pub struct HarHeader {
+    /// The header or parameter name.
+    pub name: String,
+    /// The header or parameter value.
+    pub value: String,
+}
+
+/// The body of a recorded request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    /// The content type of the body.
+    pub mime_type: String,
+    /// The body as text.
+    pub text: String,
+}
+
+/// The body of a recorded response.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    /// The size of the body in bytes.
+    pub size: i64,
+    /// The content type of the body.
+    pub mime_type: String,
+    /// The body as text.
+    pub text: String,
+}
+
+/// Timing breakdown of a recorded entry in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct HarTimings {
+    /// Time spent sending the request.
+    pub send: f64,
+    /// Time spent waiting for the response.
+    pub wait: f64,
+    /// Time spent reading the response body.
+    pub receive: f64,
+}
+
+/// The request half of an entry, captured before the request is sent.
+pub(crate) struct PendingEntry {
+    started: DateTime<Utc>,
+    instant: std::time::Instant,
+    request: HarRequest,
+}
+
+impl PendingEntry {
+    pub(crate) fn from_request(request: &reqwest::Request) -> Self {
+        let body = request.body().and_then(|b| b.as_bytes());
+        let body_size = body.map_or(0, |b| b.len() as i64);
+        let body = body.map(redacted_body);
+        let mime_type = header_value(request.headers(), "content-type");
+
+        Self {
+            started: Utc::now(),
+            instant: std::time::Instant::now(),
+            request: HarRequest {
+                method: request.method().to_string(),
+                url: request.url().to_string(),
+                http_version: format!("{:?}", request.version()),
+                headers: redacted_headers(request.headers()),
+                query_string: request
+                    .url()
+                    .query_pairs()
+                    .map(|(name, value)| HarHeader {
+                        name: name.into_owned(),
+                        value: value.into_owned(),
+                    })
+                    .collect(),
+                cookies: Vec::new(),
+                headers_size: -1,
+                body_size,
+                post_data: body.map(|text| HarPostData { mime_type, text }),
+            },
+        }
+    }
+
+    pub(crate) fn finish(
+        self,
+        status: reqwest::StatusCode,
+        version: reqwest::Version,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> HarEntry {
+        let elapsed = self.instant.elapsed().as_secs_f64() * 1000.0;
+        HarEntry {
+            started_date_time: self.started,
+            time: elapsed,
+            request: self.request,
+            response: HarResponse {
+                status: status.as_u16(),
+                status_text: status.canonical_reason().unwrap_or_default().to_string(),
+                http_version: format!("{:?}", version),
+                headers: redacted_headers(headers),
+                cookies: Vec::new(),
+                content: HarContent {
+                    size: body.len() as i64,
+                    mime_type: header_value(headers, "content-type"),
+                    text: redacted_body(body),
+                },
+                redirect_url: String::new(),
+                headers_size: -1,
+                body_size: body.len() as i64,
+            },
+            cache: serde_json::json!({}),
+            timings: HarTimings {
+                send: 0.0,
+                wait: elapsed,
+                receive: 0.0,
+            },
+        }
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> String {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name == "authorization"
+        || name == "cookie"
+        || name == "set-cookie"
+        || name.contains("key")
+        || name.contains("secret")
+        || name.contains("signature")
+        || name.contains("token")
+}
+
+/// The text of a body, with the sensitive fields redacted if it is JSON.
+fn redacted_body(body: &[u8]) -> String {
+    match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(mut json) => {
+            crate::audit::redact(&mut json);
+            json.to_string()
+        }
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+fn redacted_headers(headers: &HeaderMap) -> Vec<HarHeader> {
+    headers
+        .iter()
+        .map(|(name, value)| HarHeader {
+            name: name.to_string(),
+            value: if is_sensitive(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                value.to_str().unwrap_or_default().to_string()
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensitive_headers_are_redacted() {
+        let request = reqwest::Client::new()
+            .post("https://api.chapa.co/v1/transaction/initialize?lang=en")
+            .bearer_auth("CHASECK_TEST-secret")
+            .header("x-chapa-signature", "abc")
+            .header("x-request-id", "42")
+            .body(r#"{"amount":"100","email":"abebe@example.com"}"#)
+            .build()
+            .unwrap();
+        let entry = PendingEntry::from_request(&request).finish(
+            reqwest::StatusCode::OK,
+            reqwest::Version::HTTP_11,
+            &HeaderMap::new(),
+            br#"{"status":"success","data":[{"account_number":"1000200030001","mobile":"0911223344"}]}"#,
+        );
+
+        let recorder = HarRecorder::new();
+        recorder.record(entry);
+        let json = recorder.to_json().unwrap();
+
+        assert!(!json.contains("CHASECK_TEST-secret"));
+        assert!(json.contains(REDACTED));
+        assert!(json.contains("x-request-id"));
+        assert!(json.contains(r#"\"amount\":\"100\""#));
+        for detail in ["abebe@example.com", "1000200030001", "0911223344"] {
+            assert!(!json.contains(detail), "{} isn't redacted", detail);
+        }
+        assert_eq!(
+            recorder.to_har().log.entries[0].request.query_string.len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! # Legacy Module
+//!
+//! Deprecated free functions of the previous API, kept so existing code can migrate to
+//! [`ChapaClient`] gradually. Every call builds a client from the legacy environment variables
+//! and delegates to it:
+//! - `CHAPA_API_PRIVATE_KEY`, the secret key (required);
+//! - `CHAPA_BASE_URL`, the base URL of the API (optional).
+//!
+//! ## Migration
+//! ```rust,no_run
+//! use chapa_rust::client::ChapaClient;
+//!
+//! # async fn run() -> chapa_rust::error::Result<()> {
+//! // before: let banks = chapa_rust::legacy::get_banks().await?;
+//! let client = ChapaClient::new(std::env::var("CHAPA_API_PRIVATE_KEY").unwrap())?;
+//! let banks = client.get_banks().await?;
+//! # Ok(())
+//! # }
+//! ```
+#![allow(deprecated)]
+
+use crate::{
+    client::ChapaClient,
+    config::ChapaConfigBuilder,
+    error::{ChapaError, Result},
+    models::{
+        payment::InitializeOptions,
+        response::{GetBanksResponse, InitializeResponse, VerifyResponse},
+    },
+};
+
+const PRIVATE_KEY_VAR: &str = "CHAPA_API_PRIVATE_KEY";
+const BASE_URL_VAR: &str = "CHAPA_BASE_URL";
+
+/// Builds a client from the legacy `CHAPA_API_PRIVATE_KEY` and `CHAPA_BASE_URL` environment
+/// variables.
+///
+/// # Errors
+/// Returns [`ChapaError::MissingApiKey`] if `CHAPA_API_PRIVATE_KEY` is not set, or the error of
+/// [`ChapaConfigBuilder::build`] if a variable is invalid.
+#[deprecated(note = "build a `ChapaClient` with `ChapaConfig::builder()` instead")]
+pub fn legacy_client() -> Result<ChapaClient> {
+    let key = std::env::var(PRIVATE_KEY_VAR).map_err(|_| ChapaError::MissingApiKey)?;
+    let mut builder = ChapaConfigBuilder::new().api_key(key);
+    if let Ok(url) = std::env::var(BASE_URL_VAR) {
+        builder = builder.base_url(url);
+    }
+    ChapaClient::from_config(builder.build()?)
+}
+
+/// Retrieves the list of all banks supported by Chapa, see [`ChapaClient::get_banks`].
+#[deprecated(note = "use `ChapaClient::get_banks` instead")]
+pub async fn get_banks() -> Result<GetBanksResponse> {
+    legacy_client()?.get_banks().await
+}
+
+/// Verifies the status of a transaction, see [`ChapaClient::verify_transaction`].
+#[deprecated(note = "use `ChapaClient::verify_transaction` instead")]
+pub async fn verify_transaction(tx_ref: &str) -> Result<VerifyResponse> {
+    legacy_client()?.verify_transaction(tx_ref).await
+}
+
+/// Builds and initializes a transaction the way the previous API did.
+///
+/// ```rust,no_run
+/// # #![allow(deprecated)]
+/// use chapa_rust::legacy::TransactionBuilder;
+///
+/// # async fn run() -> chapa_rust::error::Result<()> {
+/// let response = TransactionBuilder::new()
+///     .amount("100")
+///     .currency("ETB")
+///     .email("abebe@bikila.com")
+///     .tx_ref("order-1")
+///     .initialize()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[deprecated(
+    note = "build an `InitializeOptions` and call `ChapaClient::initialize_transaction` instead"
+)]
+#[derive(Debug, Default)]
+pub struct TransactionBuilder {
+    options: InitializeOptions,
+}
+
+impl TransactionBuilder {
+    /// Starts an empty transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the amount, as a decimal string.
+    pub fn amount(mut self, amount: impl Into<String>) -> Self {
+        self.options.amount = amount.into();
+        self
+    }
+
+    /// Sets the currency (e.g., "ETB", "USD").
+    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+        self.options.currency = currency.into();
+        self
+    }
+
+    /// Sets the customer's email.
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.options.email = Some(email.into());
+        self
+    }
+
+    /// Sets the customer's first name.
+    pub fn first_name(mut self, first_name: impl Into<String>) -> Self {
+        self.options.first_name = Some(first_name.into());
+        self
+    }
+
+    /// Sets the customer's last name.
+    pub fn last_name(mut self, last_name: impl Into<String>) -> Self {
+        self.options.last_name = Some(last_name.into());
+        self
+    }
+
+    /// Sets the customer's phone number.
+    pub fn phone_number(mut self, phone_number: impl Into<String>) -> Self {
+        self.options.phone_number = Some(phone_number.into());
+        self
+    }
+
+    /// Sets the merchant's transaction reference.
+    pub fn tx_ref(mut self, tx_ref: impl Into<String>) -> Self {
+        self.options.tx_ref = tx_ref.into();
+        self
+    }
+
+    /// Sets the URL Chapa calls once the payment completes.
+    pub fn callback_url(mut self, url: impl Into<String>) -> Self {
+        self.options.callback_url = Some(url.into());
+        self
+    }
+
+    /// Sets the URL the customer is redirected to after the payment.
+    pub fn return_url(mut self, url: impl Into<String>) -> Self {
+        self.options.return_url = Some(url.into());
+        self
+    }
+
+    /// Returns the options of the transaction, to pass to
+    /// [`ChapaClient::initialize_transaction`].
+    pub fn build(self) -> InitializeOptions {
+        self.options
+    }
+
+    /// Initializes the transaction with a client built from the legacy environment variables,
+    /// see [`legacy_client`].
+    pub async fn initialize(self) -> Result<InitializeResponse> {
+        legacy_client()?.initialize_transaction(self.options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_builder() {
+        let options = TransactionBuilder::new()
+            .amount("100")
+            .currency("ETB")
+            .email("abebe@bikila.com")
+            .tx_ref("order-1")
+            .return_url("https://example.com/done")
+            .build();
+
+        assert_eq!(options.amount, "100");
+        assert_eq!(options.currency, "ETB");
+        assert_eq!(options.email.as_deref(), Some("abebe@bikila.com"));
+        assert_eq!(options.tx_ref, "order-1");
+        assert_eq!(
+            options.return_url.as_deref(),
+            Some("https://example.com/done")
+        );
+        assert_eq!(options.callback_url, None);
+    }
+}
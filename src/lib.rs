@@ -28,7 +28,7 @@
 //!
 //! #[tokio::main]
 //! async fn main() {
-//!     let mut client = ChapaClient::new("YOUR_SECRET_KEY").unwrap();
+//!     let client = ChapaClient::new("CHASECK_TEST-YOUR_SECRET_KEY").unwrap();
 //!
 //!     let req = InitializeOptions {
 //!         amount: "100".to_string(),
@@ -53,7 +53,7 @@
 //! | Transactions | `initialize`, `verify`, `all_transactions`, `transaction_logs` |
 //! | Split Payments | `split_payment` |
 //! | Banks | `list_banks` |
-//! | Subaccounts | `create_subaccount` |
+//! | Subaccounts | `create_subaccount`, `get_subaccounts` |
 //! | Transfers | `transfer`, `bulk_transfer`, `verify_transfer`, `all_transfers` |
 //! | Direct Charges | `direct_charge`, `authorize_direct_charge` |
 //! | Utilities | `generate_tx_ref()` |
@@ -70,9 +70,22 @@
 //!
 //! ## Feature Flags [TODO!]
 //!
-//! - `blocking` — Enables blocking (non-async) client support  
+//! - `async` — The async client and every module built on it, with `reqwest` and `tokio` (enabled by default)  
+//! - `ureq` — Enables a blocking (non-async) client sending its requests with `ureq` (see the `blocking` module); combine with `default-features = false` to drop `reqwest` and `tokio`  
 //! - `serde` — Enables serialization and deserialization (enabled by default)  
-//! - `logging` — Enables request/response logging (via `tracing` or `log`)  
+//! - `logging` — Runs every request in a `tracing` span (endpoint, method, status, duration), with optional debug-level body logging (see `ChapaConfigBuilder::log_bodies`)  
+//! - `har` — Records redacted SDK traffic in HAR format for debugging (see the `har` module)  
+//! - `dotenv` — Loads a `.env` file automatically in `ChapaConfig::from_env()`  
+//! - `fixtures` — Exposes real Chapa response payloads for regression tests (see the `fixtures` module)  
+//! - `rustls` — Uses `rustls` instead of the platform TLS library  
+//! - `miette` — Implements `miette::Diagnostic` on `ChapaError` with help text  
+//! - `simd-json` — Parses the transaction and transfer lists with simd-json  
+//! - `test-utils` — Exposes Chapa's test-mode phones, cards and banks as constants (see the `test_utils` module), a `MockChapaClient` implementing the `ChapaApi` trait (see the `mock` module), and a `ScriptedTransport` answering the client's requests with scripted responses (see the `testing` module)  
+//! - `axum` — Makes `ChapaState` and `ChapaWebhook` Axum extractors (see the `state` and `webhook` modules), and `ChapaError` an Axum response  
+//! - `actix` — Makes `ChapaState` and `ChapaWebhook` Actix-web extractors (see the `state` and `webhook` modules)  
+//! - `rocket` — Makes `ChapaState` a Rocket request guard with a fairing, and `ChapaWebhook` a data guard (see the `state` and `webhook` modules)  
+//! - `tracing` — Emits webhook processing outcomes as `tracing` events (see the `webhook` module)  
+//! - `reload` — Watches a configuration file and applies rotated keys and URLs to running clients (see the `reload` module)  
 //!
 //! ```toml
 //! [dependencies]
@@ -115,7 +128,63 @@
 //! It aims to provide an ergonomic and type-safe developer experience for
 //! Rust developers building payment systems in Ethiopia and beyond.
 #![deny(missing_docs)]
+#[cfg(feature = "async")]
+pub mod api;
+#[cfg(feature = "async")]
+pub mod audit;
+pub mod beneficiary;
+#[cfg(feature = "ureq")]
+pub mod blocking;
+#[cfg(feature = "async")]
+pub mod bulk;
+#[cfg(feature = "async")]
 pub mod client;
 pub mod config;
+#[cfg(feature = "async")]
+mod decode;
+pub mod endpoint;
 pub mod error;
+pub mod exchange;
+#[cfg(feature = "async")]
+pub mod export;
+pub mod failover;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub mod guardrails;
+#[cfg(feature = "har")]
+pub mod har;
+#[cfg(feature = "async")]
+pub mod legacy;
+#[cfg(feature = "async")]
+pub mod list;
+#[cfg(feature = "logging")]
+mod logging;
+#[cfg(feature = "test-utils")]
+pub mod mock;
 pub mod models;
+pub mod poll;
+pub mod preflight;
+#[cfg(feature = "async")]
+pub mod quick;
+#[cfg(feature = "async")]
+pub mod rate_limit;
+#[cfg(feature = "async")]
+pub mod registry;
+#[cfg(feature = "reload")]
+pub mod reload;
+pub mod rules;
+pub mod serializer;
+#[cfg(feature = "async")]
+pub mod sink;
+#[cfg(feature = "async")]
+pub mod state;
+pub mod stats;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "test-utils")]
+pub mod testing;
+pub mod timeline;
+#[cfg(feature = "async")]
+pub mod transport;
+#[cfg(feature = "async")]
+pub mod webhook;
@@ -0,0 +1,152 @@
+//! # List Module
+//!
+//! Uniform pagination over Chapa's list endpoints: [`ChapaClient::list`](crate::client::ChapaClient::list)
+//! walks every page of any response type implementing [`ListPage`] and yields the items one by
+//! one as a [`ChapaList`], a [`Stream`] that fetches the next page only when it is needed.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use chapa_rust::models::transfer::GetTransfersResponse;
+//!
+//! # async fn run(client: chapa_rust::client::ChapaClient) -> chapa_rust::error::Result<()> {
+//! let mut transfers = client.list::<GetTransfersResponse>(Some("batch_id=42"));
+//! while let Some(transfer) = transfers.next_item().await {
+//!     println!("{:?}", transfer?.reference);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    endpoint::Endpoint,
+    error::{ChapaError, Result},
+    models::{
+        response::RawResponse,
+        transaction::{GetTransactionsResponse, Transaction},
+        transfer::{GetTransfersResponse, Transfer},
+    },
+};
+
+/// A page of a paginated Chapa list endpoint.
+pub trait ListPage: DeserializeOwned + RawResponse + Send + 'static {
+    /// The type of the listed items.
+    type Item: Send + 'static;
+
+    /// The endpoint returning the page `page` (starting at 1) of the items matching `filter`,
+    /// an additional query string such as `batch_id=42`.
+    fn endpoint(filter: Option<&str>, page: u32) -> Endpoint<'_>;
+
+    /// Splits the page into its items and whether a next page exists.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::ApiError`] if the page doesn't have a success status.
+    fn into_items(self) -> Result<(Vec<Self::Item>, bool)>;
+}
+
+impl ListPage for GetTransactionsResponse {
+    type Item = Transaction;
+
+    fn endpoint(filter: Option<&str>, page: u32) -> Endpoint<'_> {
+        Endpoint::Transactions {
+            filter,
+            page: Some(page),
+        }
+    }
+
+    fn into_items(self) -> Result<(Vec<Transaction>, bool)> {
+        if self.status != "success" {
+            return Err(ChapaError::api_error(self.message));
+        }
+        let has_next = self.data.pagination.next_page_url.is_some();
+        Ok((self.data.transactions, has_next))
+    }
+}
+
+impl ListPage for GetTransfersResponse {
+    type Item = Transfer;
+
+    fn endpoint(filter: Option<&str>, page: u32) -> Endpoint<'_> {
+        Endpoint::Transfers {
+            filter,
+            page: Some(page),
+        }
+    }
+
+    fn into_items(self) -> Result<(Vec<Transfer>, bool)> {
+        if self.status != "success" {
+            return Err(ChapaError::api_error(self.message));
+        }
+        let has_next = self
+            .meta
+            .as_ref()
+            .is_some_and(|meta| meta.next_page_url.is_some());
+        Ok((self.data, has_next))
+    }
+}
+
+/// The items of a paginated list, fetched page by page while they are consumed.
+///
+/// The list ends after the first error.
+pub struct ChapaList<'a, T> {
+    items: Pin<Box<dyn Stream<Item = Result<T>> + Send + 'a>>,
+    failed: bool,
+}
+
+impl<'a, T: Send + 'a> ChapaList<'a, T> {
+    /// Wraps a stream of pages into a stream of items.
+    pub(crate) fn from_pages<S>(pages: S) -> Self
+    where
+        S: Stream<Item = Result<Vec<T>>> + Send + 'a,
+    {
+        let items = pages
+            .map_ok(|page| futures_util::stream::iter(page.into_iter().map(Ok)))
+            .try_flatten();
+        Self {
+            items: Box::pin(items),
+            failed: false,
+        }
+    }
+
+    /// Returns the next item, fetching the next page if needed, or `None` at the end of the list.
+    pub async fn next_item(&mut self) -> Option<Result<T>> {
+        self.next().await
+    }
+
+    /// Collects the remaining items.
+    ///
+    /// # Errors
+    /// Returns the first error met while fetching the pages.
+    pub async fn collect_all(self) -> Result<Vec<T>> {
+        self.try_collect().await
+    }
+}
+
+impl<T> Stream for ChapaList<'_, T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.failed {
+            return Poll::Ready(None);
+        }
+        let item = self.items.as_mut().poll_next(cx);
+        if let Poll::Ready(Some(Err(_))) = &item {
+            self.failed = true;
+        }
+        item
+    }
+}
+
+impl<T> std::fmt::Debug for ChapaList<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChapaList")
+            .field("failed", &self.failed)
+            .finish_non_exhaustive()
+    }
+}
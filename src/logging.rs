@@ -0,0 +1,72 @@
+//! Request logging with `tracing`, behind the `logging` feature.
+//!
+//! Every request sent by the client runs in a `chapa.request` span on the `chapa_rust::client`
+//! target, carrying the endpoint, the HTTP method, the response status and the duration until the
+//! response headers were received. Bodies are logged at debug level only when enabled with
+//! [`ChapaConfigBuilder::log_bodies`](crate::config::ChapaConfigBuilder::log_bodies), since they
+//! hold customer and account details.
+use std::time::Instant;
+
+use tracing::{Span, field};
+
+use crate::error::Result;
+
+const TARGET: &str = "chapa_rust::client";
+
+/// The span of a request in flight.
+pub(crate) struct RequestLog {
+    span: Span,
+    started: Instant,
+}
+
+impl RequestLog {
+    /// Opens the span of `request`, logging its body if `log_bodies` is set.
+    pub(crate) fn start(request: &reqwest::Request, endpoint: &str, log_bodies: bool) -> Self {
+        let span = tracing::debug_span!(
+            target: TARGET,
+            "chapa.request",
+            method = %request.method(),
+            endpoint,
+            status = field::Empty,
+            duration_ms = field::Empty,
+        );
+        let body = request.body().and_then(reqwest::Body::as_bytes);
+        if let Some(body) = body.filter(|_| log_bodies) {
+            tracing::debug!(target: TARGET, parent: &span, body = %String::from_utf8_lossy(body), "request body");
+        }
+        Self {
+            span,
+            started: Instant::now(),
+        }
+    }
+
+    /// The span of the request, to instrument the request future with.
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Records the status and duration of the request.
+    pub(crate) fn finish(&self, result: &Result<reqwest::Response>) {
+        let duration_ms = self.started.elapsed().as_millis() as u64;
+        self.span.record("duration_ms", duration_ms);
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                self.span.record("status", status.as_u16());
+                if status.is_client_error() || status.is_server_error() {
+                    tracing::warn!(target: TARGET, parent: &self.span, status = status.as_u16(), duration_ms, "request failed");
+                } else {
+                    tracing::debug!(target: TARGET, parent: &self.span, status = status.as_u16(), duration_ms, "request completed");
+                }
+            }
+            Err(error) => {
+                tracing::warn!(target: TARGET, parent: &self.span, error = %error, duration_ms, "request failed");
+            }
+        }
+    }
+}
+
+/// Logs the body of a response received from `url`.
+pub(crate) fn response_body(url: &reqwest::Url, body: &[u8]) {
+    tracing::debug!(target: TARGET, url = %url.path(), body = %String::from_utf8_lossy(body), "response body");
+}
@@ -0,0 +1,300 @@
+//! # Mock Module
+//!
+//! A [`MockChapaClient`], available behind the `test-utils` feature, implementing [`ChapaApi`]
+//! with canned responses, so payment flows written against the trait can be unit-tested without
+//! an HTTP server.
+//!
+//! Responses are registered per endpoint, by the path template of the [`Endpoint`] (e.g.
+//! `"transaction/verify/{tx_ref}"`), as the JSON body Chapa would answer with. Every call is
+//! recorded, see [`MockChapaClient::calls`]. Calling an endpoint without a response fails with
+//! [`ChapaError::ApiError`].
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::{api::ChapaApi, mock::MockChapaClient};
+//! use serde_json::json;
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let chapa = MockChapaClient::new().respond(
+//!     "transfers/verify/{reference}",
+//!     json!({"message": "Transfer details", "status": "success", "data": null}),
+//! );
+//!
+//! let response = chapa.verify_transfer("payout-1").await.unwrap();
+//! assert_eq!(response.status, "success");
+//! assert_eq!(chapa.calls()[0].path, "transfers/verify/payout-1");
+//! # });
+//! ```
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    api::ChapaApi,
+    endpoint::Endpoint,
+    error::{ChapaError, Result},
+    list::ListPage,
+    models::{
+        channel::PaymentChannel,
+        direct_charge::{
+            AuthorizeDirectChargeOptions, AuthorizeDirectChargeResponse, DirectChargeOptions,
+        },
+        payment::InitializeOptions,
+        response::{
+            BulkTransferResponse, CreateSubaccountResponse, DirectChargeResponse,
+            GetBalancesResponse, GetBanksResponse, GetPaymentChannelsResponse,
+            GetSubaccountsResponse, InitializeResponse, SwapResponse, TransactionLogsResponse,
+            VerifyResponse, VerifyTransferResponse,
+        },
+        subaccount::CreateSubaccountOptions,
+        swap::SwapOptions,
+        transaction::GetTransactionsResponse,
+        transfer::{
+            BulkTransferOptions, GetTransfersResponse, Transfer, TransferOptions, TransferResponse,
+        },
+    },
+};
+
+type Responder = Arc<dyn Fn(&MockCall) -> Result<Value> + Send + Sync>;
+
+/// A call received by a [`MockChapaClient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+    /// The HTTP method of the endpoint.
+    pub method: String,
+    /// The path template of the endpoint, e.g. `"transaction/verify/{tx_ref}"`.
+    pub template: &'static str,
+    /// The path of the endpoint with its parameters, e.g. `"transaction/verify/order-1"`.
+    pub path: String,
+    /// The request body, as JSON.
+    pub body: Option<Value>,
+}
+
+/// A [`ChapaApi`] answering with canned responses, for unit tests.
+///
+/// Clones share the same responses and recorded calls.
+#[derive(Clone, Default)]
+pub struct MockChapaClient {
+    responders: Arc<Mutex<HashMap<&'static str, Responder>>>,
+    calls: Arc<Mutex<Vec<MockCall>>>,
+}
+
+impl fmt::Debug for MockChapaClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockChapaClient")
+            .field("calls", &self.calls())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MockChapaClient {
+    /// Creates a mock without any response.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers every call to the endpoint with the path template `template` with `body`.
+    pub fn respond(self, template: &'static str, body: Value) -> Self {
+        self.on(template, move |_| Ok(body.clone()))
+    }
+
+    /// Answers every call to the endpoint with the path template `template` with the result
+    /// of `responder`, e.g. to answer depending on the request or to simulate an error.
+    pub fn on(
+        self,
+        template: &'static str,
+        responder: impl Fn(&MockCall) -> Result<Value> + Send + Sync + 'static,
+    ) -> Self {
+        self.lock_responders().insert(template, Arc::new(responder));
+        self
+    }
+
+    /// The calls received so far, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn lock_responders(&self) -> std::sync::MutexGuard<'_, HashMap<&'static str, Responder>> {
+        self.responders.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records a call to `endpoint` and deserializes the registered response.
+    fn call<T, K>(&self, endpoint: Endpoint<'_>, body: Option<&K>) -> Result<T>
+    where
+        T: DeserializeOwned,
+        K: Serialize,
+    {
+        let call = MockCall {
+            method: endpoint.method().to_string(),
+            template: endpoint.template(),
+            path: endpoint.path(),
+            body: body.map(serde_json::to_value).transpose()?,
+        };
+        let responder = self.lock_responders().get(call.template).cloned();
+        self.calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(call.clone());
+        let responder = responder.ok_or_else(|| {
+            ChapaError::api_error(format!("no mock response for {}", call.template))
+        })?;
+        Ok(serde_json::from_value(responder(&call)?)?)
+    }
+}
+
+#[async_trait]
+impl ChapaApi for MockChapaClient {
+    async fn get_banks(&self) -> Result<GetBanksResponse> {
+        self.call::<_, ()>(Endpoint::Banks, None)
+    }
+
+    async fn get_balances(&self) -> Result<GetBalancesResponse> {
+        self.call::<_, ()>(Endpoint::Balances, None)
+    }
+
+    async fn get_payment_channels(&self) -> Result<Vec<PaymentChannel>> {
+        let response: GetPaymentChannelsResponse =
+            self.call::<_, ()>(Endpoint::PaymentChannels, None)?;
+        Ok(PaymentChannel::parse_all(
+            &response.data.unwrap_or_default(),
+        ))
+    }
+
+    async fn initialize_transaction(
+        &self,
+        transaction: InitializeOptions,
+    ) -> Result<InitializeResponse> {
+        self.call(Endpoint::InitializeTransaction, Some(&transaction))
+    }
+
+    async fn verify_transaction(&self, tx_ref: &str) -> Result<VerifyResponse> {
+        self.call::<_, ()>(Endpoint::VerifyTransaction { tx_ref }, None)
+    }
+
+    async fn get_transaction_logs(&self, tx_ref: &str) -> Result<TransactionLogsResponse> {
+        self.call::<_, ()>(Endpoint::TransactionLogs { tx_ref }, None)
+    }
+
+    async fn get_transactions(&self) -> Result<GetTransactionsResponse> {
+        let endpoint = Endpoint::Transactions {
+            filter: None,
+            page: None,
+        };
+        self.call::<_, ()>(endpoint, None)
+    }
+
+    async fn get_transfers(&self) -> Result<GetTransfersResponse> {
+        let endpoint = Endpoint::Transfers {
+            filter: None,
+            page: None,
+        };
+        self.call::<_, ()>(endpoint, None)
+    }
+
+    /// Answers with the transfers of the `"transfers"` response, as a single page.
+    async fn get_bulk_transfers(&self, batch_id: u64) -> Result<Vec<Transfer>> {
+        let filter = format!("batch_id={}", batch_id);
+        let endpoint = Endpoint::Transfers {
+            filter: Some(&filter),
+            page: Some(1),
+        };
+        let page: GetTransfersResponse = self.call::<_, ()>(endpoint, None)?;
+        Ok(page.into_items()?.0)
+    }
+
+    async fn transfer(&self, options: TransferOptions) -> Result<TransferResponse> {
+        self.call(Endpoint::Transfer, Some(&options))
+    }
+
+    async fn verify_transfer(&self, reference: &str) -> Result<VerifyTransferResponse> {
+        self.call::<_, ()>(Endpoint::VerifyTransfer { reference }, None)
+    }
+
+    async fn bulk_transfer(&self, options: BulkTransferOptions) -> Result<BulkTransferResponse> {
+        self.call(Endpoint::BulkTransfer, Some(&options))
+    }
+
+    async fn swap(&self, options: SwapOptions) -> Result<SwapResponse> {
+        self.call(Endpoint::Swap, Some(&options))
+    }
+
+    async fn create_subaccount(
+        &self,
+        options: CreateSubaccountOptions,
+    ) -> Result<CreateSubaccountResponse> {
+        self.call(Endpoint::CreateSubaccount, Some(&options))
+    }
+
+    async fn get_subaccounts(&self) -> Result<GetSubaccountsResponse> {
+        self.call::<_, ()>(Endpoint::Subaccounts, None)
+    }
+
+    async fn direct_charge(
+        &self,
+        channel: PaymentChannel,
+        options: DirectChargeOptions,
+    ) -> Result<DirectChargeResponse> {
+        self.call(Endpoint::DirectCharge { channel }, Some(&options))
+    }
+
+    async fn authorize_direct_charge(
+        &self,
+        channel: PaymentChannel,
+        options: AuthorizeDirectChargeOptions,
+    ) -> Result<AuthorizeDirectChargeResponse> {
+        self.call(Endpoint::AuthorizeDirectCharge { channel }, Some(&options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_responses() {
+        let chapa = MockChapaClient::new()
+            .on("transaction/initialize", |call| {
+                let tx_ref = call.body.as_ref().unwrap()["tx_ref"].as_str().unwrap();
+                Ok(serde_json::json!({
+                    "message": "Hosted Link",
+                    "status": "success",
+                    "data": {"checkout_url": format!("https://checkout.chapa.co/checkout/payment/{}", tx_ref)}
+                }))
+            })
+            .on("transfers", |_| Err(ChapaError::api_error("Unauthorized")));
+
+        let response = chapa
+            .initialize_transaction(InitializeOptions {
+                amount: "100".to_string(),
+                currency: "ETB".to_string(),
+                tx_ref: "order-1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(
+            response
+                .data
+                .unwrap()
+                .checkout_url
+                .ends_with("/payment/order-1")
+        );
+        assert!(matches!(
+            chapa.get_bulk_transfers(42).await,
+            Err(ChapaError::ApiError(_))
+        ));
+        assert!(chapa.get_banks().await.is_err());
+
+        let calls = chapa.calls();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].method, "POST");
+        assert_eq!(calls[1].path, "transfers?batch_id=42&page=1");
+        assert_eq!(calls[2].template, "banks");
+    }
+}
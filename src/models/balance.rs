@@ -0,0 +1,13 @@
+//! Models related to the merchant's balances.
+
+crate::chapa_model! {
+    /// The balance of the merchant in one currency.
+    pub struct Balance {
+        /// The currency of the balance, e.g. "ETB".
+        pub currency: String,
+        /// The amount that can be paid out right now.
+        pub available_balance: f64,
+        /// The amount including payments not settled yet.
+        pub ledger_balance: f64,
+    }
+}
@@ -1,5 +1,7 @@
 //! Models related to banks and bank listings.
 
+use std::fmt;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -14,8 +16,9 @@ pub struct Bank {
     pub name: String,
     /// The account number length for the bank.
     pub acct_length: u32,
-    /// The country identifier for the bank.
-    pub country_id: u32,
+    /// The country of the bank, from its `country_id`.
+    #[serde(rename = "country_id")]
+    pub country: Country,
     /// The creation timestamp of the bank entry.
     pub created_at: DateTime<Utc>,
     /// The last updated timestamp of the bank entry.
@@ -36,3 +39,50 @@ pub enum Currency {
     /// United States Dollar
     USD,
 }
+
+/// The country of a bank, identified by its Chapa `country_id`.
+///
+/// Chapa only operates in Ethiopia for now; countries added as it expands are kept as
+/// [`Country::Other`] with their identifier until they get their own variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "u32", into = "u32")]
+pub enum Country {
+    /// Ethiopia, `country_id` 1.
+    Ethiopia,
+    /// Another country, by its Chapa `country_id`.
+    Other(u32),
+}
+
+impl Country {
+    /// The Chapa `country_id` of the country.
+    pub fn id(&self) -> u32 {
+        match self {
+            Country::Ethiopia => 1,
+            Country::Other(id) => *id,
+        }
+    }
+}
+
+impl From<u32> for Country {
+    fn from(id: u32) -> Self {
+        match id {
+            1 => Country::Ethiopia,
+            id => Country::Other(id),
+        }
+    }
+}
+
+impl From<Country> for u32 {
+    fn from(country: Country) -> Self {
+        country.id()
+    }
+}
+
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Country::Ethiopia => write!(f, "Ethiopia"),
+            Country::Other(id) => write!(f, "country {}", id),
+        }
+    }
+}
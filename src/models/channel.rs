@@ -0,0 +1,87 @@
+//! Models related to payment channels (methods a customer can pay with).
+//!
+//! [`PaymentChannel::ALL`] lists the channels documented by Chapa, and
+//! [`ChapaClient::get_payment_channels`](crate::client::ChapaClient::get_payment_channels) the ones
+//! enabled for the merchant account. The identifiers match the `type` values used by the direct
+//! charge endpoints and the `method` reported on verified payments.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A payment channel supported by Chapa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentChannel {
+    /// Visa/Mastercard card payments.
+    Card,
+    /// Ethio Telecom's telebirr wallet.
+    Telebirr,
+    /// Safaricom M-Pesa wallet.
+    Mpesa,
+    /// Commercial Bank of Ethiopia's CBE Birr wallet.
+    Cbebirr,
+    /// Coop Bank's e-Birr wallet.
+    Ebirr,
+    /// Enat Bank.
+    EnatBank,
+    /// Dashen Bank's Amole wallet.
+    Amole,
+    /// Awash Bank's Awash Birr wallet.
+    Awashbirr,
+}
+
+impl PaymentChannel {
+    /// Every channel documented by Chapa.
+    pub const ALL: [PaymentChannel; 8] = [
+        PaymentChannel::Card,
+        PaymentChannel::Telebirr,
+        PaymentChannel::Mpesa,
+        PaymentChannel::Cbebirr,
+        PaymentChannel::Ebirr,
+        PaymentChannel::EnatBank,
+        PaymentChannel::Amole,
+        PaymentChannel::Awashbirr,
+    ];
+
+    /// The identifier Chapa uses for the channel, e.g. `"telebirr"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentChannel::Card => "card",
+            PaymentChannel::Telebirr => "telebirr",
+            PaymentChannel::Mpesa => "mpesa",
+            PaymentChannel::Cbebirr => "cbebirr",
+            PaymentChannel::Ebirr => "ebirr",
+            PaymentChannel::EnatBank => "enat_bank",
+            PaymentChannel::Amole => "amole",
+            PaymentChannel::Awashbirr => "awashbirr",
+        }
+    }
+
+    /// Parses a Chapa channel identifier case-insensitively, returning `None` for unknown channels.
+    pub fn parse(channel: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.as_str().eq_ignore_ascii_case(channel))
+    }
+
+    /// Parses the channel identifiers listed by Chapa, skipping the ones this crate doesn't know.
+    #[cfg(feature = "async")]
+    pub(crate) fn parse_all<S: AsRef<str>>(channels: &[S]) -> Vec<Self> {
+        channels
+            .iter()
+            .filter_map(|channel| Self::parse(channel.as_ref()))
+            .collect()
+    }
+
+    /// Returns `true` for mobile money wallets, which require the customer's phone number.
+    pub fn is_mobile_money(&self) -> bool {
+        !matches!(self, PaymentChannel::Card | PaymentChannel::EnatBank)
+    }
+}
+
+impl fmt::Display for PaymentChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
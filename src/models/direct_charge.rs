@@ -0,0 +1,240 @@
+//! Models related to direct charges, where the customer pays from their wallet without
+//! being redirected to the hosted checkout.
+
+#[cfg(feature = "async")]
+use reqwest::multipart::Form;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+#[cfg(feature = "async")]
+use crate::client::FormBody;
+use crate::models::{normalized::PaymentStatus, payment::VerifyData, response::RawResponse};
+
+/// The Request structure for initiating a direct charge.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DirectChargeOptions {
+    /// The amount to be charged.
+    pub amount: String,
+    /// The currency of the charge, usually "ETB".
+    pub currency: String,
+    /// A unique reference for the transaction.
+    pub tx_ref: String,
+    /// The phone number of the wallet to charge, required for mobile money channels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mobile: Option<String>,
+    /// The first name of the customer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_name: Option<String>,
+    /// The last name of the customer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_name: Option<String>,
+    /// The email address of the customer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+#[cfg(feature = "async")]
+impl FormBody for DirectChargeOptions {
+    fn into_form(self) -> Form {
+        let optional = [
+            ("mobile", self.mobile),
+            ("first_name", self.first_name),
+            ("last_name", self.last_name),
+            ("email", self.email),
+        ];
+        optional.into_iter().fold(
+            Form::new()
+                .text("amount", self.amount)
+                .text("currency", self.currency)
+                .text("tx_ref", self.tx_ref),
+            |form, (name, value)| match value {
+                Some(value) => form.text(name, value),
+                None => form,
+            },
+        )
+    }
+}
+
+/// Represents the data received after initiating a direct charge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectChargeData {
+    /// How the customer authorizes the charge (e.g., "ussd", "otp").
+    pub auth_type: Option<String>,
+    /// Provider details of the initiated charge.
+    pub meta: Option<DirectChargeMeta>,
+}
+
+/// Provider details of an initiated direct charge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectChargeMeta {
+    /// A message from the provider.
+    pub message: Option<String>,
+    /// The status of the initiation request.
+    pub status: Option<String>,
+    /// The Chapa reference of the charge, used to authorize it.
+    pub ref_id: Option<String>,
+    /// The status of the payment itself.
+    #[serde(
+        default,
+        deserialize_with = "optional_payment_status",
+        serialize_with = "serialize_payment_status"
+    )]
+    pub payment_status: Option<PaymentStatus>,
+}
+
+/// Parses the status of a direct charge payment, reported in upper case (e.g. `"PENDING"`).
+fn optional_payment_status<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<PaymentStatus>, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.map(|status| PaymentStatus::parse(&status)))
+}
+
+/// Writes the status of a direct charge payment back as a string.
+fn serialize_payment_status<S: Serializer>(
+    status: &Option<PaymentStatus>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    status
+        .as_ref()
+        .map(PaymentStatus::as_str)
+        .serialize(serializer)
+}
+
+/// The Request structure for authorizing (validating) a direct charge.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AuthorizeDirectChargeOptions {
+    /// The Chapa reference of the charge, as returned in [`DirectChargeMeta::ref_id`].
+    pub reference: String,
+    /// The encrypted authorization payload (e.g., the OTP), see Chapa's 3DES instructions.
+    pub client: String,
+}
+
+#[cfg(feature = "async")]
+impl FormBody for AuthorizeDirectChargeOptions {
+    fn into_form(self) -> Form {
+        Form::new()
+            .text("reference", self.reference)
+            .text("client", self.client)
+    }
+}
+
+/// Represents the response received after authorizing a direct charge.
+///
+/// The shape of this response varies by provider, each known shape has its own variant so no
+/// field is lost; bodies matching none of them are kept as [`AuthorizeDirectChargeResponse::Other`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AuthorizeDirectChargeResponse {
+    /// The response of Amole, which reports the transaction and processor references.
+    Amole(AmoleAuthorization),
+    /// The standard Chapa envelope, returned by the other mobile money providers.
+    Standard(StandardAuthorization),
+    /// A response of an unknown shape.
+    Other(Value),
+}
+
+impl AuthorizeDirectChargeResponse {
+    /// The message describing the outcome, if any.
+    pub fn message(&self) -> Option<&Value> {
+        match self {
+            AuthorizeDirectChargeResponse::Amole(amole) => Some(&amole.message),
+            AuthorizeDirectChargeResponse::Standard(standard) => Some(&standard.message),
+            AuthorizeDirectChargeResponse::Other(value) => value.get("message"),
+        }
+    }
+}
+
+/// The response of Amole after authorizing a direct charge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmoleAuthorization {
+    /// A message describing the outcome.
+    pub message: Value,
+    /// The reference of the authorized transaction.
+    pub trx_ref: String,
+    /// Amole's processor identifier of the payment.
+    pub processor_id: String,
+}
+
+/// The standard response after authorizing a direct charge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandardAuthorization {
+    /// A message describing the outcome.
+    pub message: Value,
+    /// The status of the authorization (e.g., "success").
+    pub status: String,
+    /// The provider's details of the payment, when reported.
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+impl RawResponse for AuthorizeDirectChargeResponse {}
+
+/// The final state of a direct charge, as returned by
+/// [`ChapaClient::direct_charge_and_wait`](crate::client::ChapaClient::direct_charge_and_wait).
+#[derive(Debug)]
+pub enum DirectChargeOutcome {
+    /// The customer paid, with the verified transaction.
+    Success(Box<VerifyData>),
+    /// The payment failed or was cancelled, with the verified transaction.
+    Failed(Box<VerifyData>),
+    /// The payment was still pending when polling stopped.
+    TimedOut {
+        /// The last status reported for the transaction, if it was found at all.
+        last_status: Option<String>,
+    },
+}
+
+impl DirectChargeOutcome {
+    /// Returns `true` if the customer paid.
+    pub fn is_success(&self) -> bool {
+        matches!(self, DirectChargeOutcome::Success(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize_response_per_provider() {
+        let amole: AuthorizeDirectChargeResponse = serde_json::from_str(
+            r#"{"message":"Payment is completed","trx_ref":"CHcuKjgnN0Dk0","processor_id":"1234567"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            amole,
+            AuthorizeDirectChargeResponse::Amole(AmoleAuthorization { ref processor_id, .. }) if processor_id == "1234567"
+        ));
+
+        let standard: AuthorizeDirectChargeResponse = serde_json::from_str(
+            r#"{"message":"Payment is completed","status":"success","data":{"ref_id":"CHcuKjgnN0Dk0"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            standard,
+            AuthorizeDirectChargeResponse::Standard(_)
+        ));
+
+        let other: AuthorizeDirectChargeResponse =
+            serde_json::from_str(r#"{"message":"Accepted","reference":"r-1"}"#).unwrap();
+        assert!(matches!(other, AuthorizeDirectChargeResponse::Other(_)));
+        assert_eq!(other.message().unwrap(), "Accepted");
+    }
+
+    #[test]
+    fn test_payment_status() {
+        let meta: DirectChargeMeta =
+            serde_json::from_str(r#"{"ref_id":"CHcuKjgnN0Dk0","payment_status":"pending"}"#)
+                .unwrap();
+        let status = meta.payment_status.unwrap();
+        assert!(status.is_pending() && !status.is_terminal());
+        assert!(PaymentStatus::parse("FAILED").is_terminal());
+
+        let meta: DirectChargeMeta =
+            serde_json::from_str(r#"{"payment_status":"REVERSED"}"#).unwrap();
+        assert_eq!(
+            serde_json::to_value(&meta).unwrap()["payment_status"],
+            "REVERSED"
+        );
+    }
+}
@@ -21,9 +21,87 @@
 //! ```
 //!
 //! All response models can be directly deserialized from Chapa API JSON responses.
+//!
+//! ## Field naming
+//! The JSON keys of the API are snake case, the naming rule [`chapa_model!`](crate::chapa_model)
+//! applies to the models it declares, along with the derives every model needs.
+//! - Struct fields are named like the JSON keys; a key that doesn't make a good field name is
+//!   renamed on its own field (e.g. `country_id` on [`Bank`](bank::Bank)).
+//! - Enums follow the values of their endpoint: [`PaymentChannel`](channel::PaymentChannel)
+//!   uses `rename_all = "snake_case"`, [`SplitType`](payment::SplitType) renames each variant,
+//!   [`Currency`](bank::Currency) keeps the upper-case codes as variant names, and
+//!   [`TransferType`](transfer::TransferType) converts from and into a string so unknown values
+//!   are kept.
+//! - Names Chapa uses interchangeably across endpoints are accepted with `alias`, the field
+//!   keeping the name of the endpoint the model was written for: `tx_ref` also accepts
+//!   `trx_ref`, the `reference` of a verified payment also accepts `ref_id` and
+//!   `chapa_reference`, and the `ref_id` of a listed [`Transaction`](transaction::Transaction)
+//!   also accepts `reference` and `chapa_reference`.
+//! - Unknown fields are ignored, so new fields in responses don't break deserialization.
+//!
+//! Calls to endpoints the SDK doesn't cover, sent with
+//! [`ChapaClient::call`](crate::client::ChapaClient::call), can reuse
+//! [`ChapaResponse`](response::ChapaResponse) around a model of their own declared with
+//! [`chapa_model!`](crate::chapa_model), following the JSON of that endpoint in the same way.
 
+pub mod balance;
 pub mod bank;
+pub mod channel;
+pub mod direct_charge;
+pub mod normalized;
 pub mod payment;
+pub mod receipt;
 pub mod response;
+pub mod subaccount;
+pub mod swap;
 pub mod transaction;
 pub mod transfer;
+pub mod webhook;
+
+/// Declares a model with the conventions of the crate's models: it derives `Debug`, `Clone`,
+/// `Serialize` and `Deserialize`, and maps its fields or variants to snake case keys.
+///
+/// Attributes, including further derives and `#[serde(...)]` ones such as field aliases, are
+/// kept; `rename_all` is the one container attribute the macro already sets. The expansion refers to `::serde`, so crates using the macro depend on `serde`.
+///
+/// # Example
+/// ```rust
+/// chapa_rust::chapa_model! {
+///     /// A payment link.
+///     #[derive(PartialEq)]
+///     pub struct PaymentLink {
+///         pub checkout_url: String,
+///         #[serde(alias = "trx_ref")]
+///         pub tx_ref: String,
+///     }
+/// }
+///
+/// chapa_rust::chapa_model! {
+///     /// The state of a payment link.
+///     pub enum LinkState {
+///         Active,
+///         PartiallyPaid,
+///     }
+/// }
+///
+/// let link: PaymentLink =
+///     serde_json::from_str(r#"{"checkout_url": "https://checkout.chapa.co/x", "trx_ref": "tx-1"}"#).unwrap();
+/// assert_eq!(link.tx_ref, "tx-1");
+/// let state: LinkState = serde_json::from_str(r#""partially_paid""#).unwrap();
+/// assert!(matches!(state, LinkState::PartiallyPaid));
+/// ```
+#[macro_export]
+macro_rules! chapa_model {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident { $($body:tt)* }) => {
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        $(#[$meta])*
+        $vis struct $name { $($body)* }
+    };
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($body:tt)* }) => {
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        $(#[$meta])*
+        $vis enum $name { $($body)* }
+    };
+}
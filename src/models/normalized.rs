@@ -0,0 +1,207 @@
+//! Normalized payment model shared by every source of payment data.
+//!
+//! Chapa describes the same payment with different shapes depending on the endpoint
+//! (transaction verification, transaction listing, ...). The [`Payment`] struct gives downstream
+//! systems a single shape to store and reconcile, regardless of where the data came from.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{payment::VerifyData, transaction::Transaction};
+
+/// A payment normalized from any Chapa payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payment {
+    /// The Chapa reference of the payment.
+    pub id: Option<String>,
+    /// The merchant's transaction reference, when the source includes it.
+    pub tx_ref: Option<String>,
+    /// The amount of the payment, kept as a decimal string to avoid precision loss.
+    pub amount: String,
+    /// The currency of the payment (e.g., "ETB", "USD").
+    pub currency: Option<String>,
+    /// The normalized status of the payment.
+    pub status: PaymentStatus,
+    /// The payment method used (e.g., "telebirr", "card").
+    pub method: Option<String>,
+    /// The customer who made the payment.
+    pub customer: PaymentCustomer,
+    /// When the payment was created.
+    pub created_at: Option<DateTime<Utc>>,
+    /// The payload the payment was normalized from.
+    pub source: PaymentSource,
+}
+
+/// The customer details of a normalized [`Payment`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaymentCustomer {
+    /// The first name of the customer.
+    pub first_name: Option<String>,
+    /// The last name of the customer.
+    pub last_name: Option<String>,
+    /// The email address of the customer.
+    pub email: Option<String>,
+    /// The mobile number of the customer.
+    pub mobile: Option<String>,
+}
+
+/// The normalized status of a [`Payment`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentStatus {
+    /// The payment completed successfully.
+    Success,
+    /// The payment has not reached a final state yet.
+    Pending,
+    /// The payment failed or was cancelled.
+    Failed,
+    /// A status not known to the SDK, kept verbatim.
+    Other(String),
+}
+
+impl PaymentStatus {
+    /// Parses a Chapa status string case-insensitively.
+    pub fn parse(status: &str) -> Self {
+        match status.to_ascii_lowercase().as_str() {
+            "success" | "successful" | "completed" => PaymentStatus::Success,
+            "pending" | "processing" => PaymentStatus::Pending,
+            "failed" | "failure" | "cancelled" | "canceled" => PaymentStatus::Failed,
+            _ => PaymentStatus::Other(status.to_string()),
+        }
+    }
+
+    /// The status as a lower case string, e.g. `"pending"`, or the unknown status verbatim.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PaymentStatus::Success => "success",
+            PaymentStatus::Pending => "pending",
+            PaymentStatus::Failed => "failed",
+            PaymentStatus::Other(other) => other,
+        }
+    }
+
+    /// Returns `true` if the payment will not change status anymore.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, PaymentStatus::Success | PaymentStatus::Failed)
+    }
+
+    /// Returns `true` if the payment has not reached a final state yet.
+    pub fn is_pending(&self) -> bool {
+        matches!(self, PaymentStatus::Pending)
+    }
+}
+
+/// The payload a normalized [`Payment`] was built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentSource {
+    /// The transaction verification endpoint.
+    Verification,
+    /// The transaction listing endpoint.
+    TransactionList,
+    /// A `charge.*` webhook event.
+    Webhook,
+}
+
+impl From<VerifyData> for Payment {
+    fn from(data: VerifyData) -> Self {
+        Payment {
+            id: data.reference,
+            tx_ref: data.tx_ref,
+            amount: data.amount.to_string(),
+            currency: data.currency,
+            status: data
+                .status
+                .as_deref()
+                .map_or(PaymentStatus::Pending, PaymentStatus::parse),
+            method: data.method,
+            customer: PaymentCustomer {
+                first_name: data.first_name,
+                last_name: data.last_name,
+                email: data.email,
+                mobile: None,
+            },
+            created_at: Some(data.created_at),
+            source: PaymentSource::Verification,
+        }
+    }
+}
+
+impl From<Transaction> for Payment {
+    fn from(transaction: Transaction) -> Self {
+        Payment {
+            id: Some(transaction.ref_id.into()),
+            tx_ref: None,
+            amount: transaction.amount.into(),
+            currency: Some(transaction.currency.into()),
+            status: PaymentStatus::parse(&transaction.status),
+            method: Some(transaction.payment_method.into()),
+            customer: PaymentCustomer {
+                first_name: transaction.customer.first_name.map(Into::into),
+                last_name: transaction.customer.last_name.map(Into::into),
+                email: transaction.customer.email.map(Into::into),
+                mobile: transaction.customer.mobile.map(Into::into),
+            },
+            created_at: Some(transaction.created_at),
+            source: PaymentSource::TransactionList,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_sources() {
+        let verify: VerifyData = serde_json::from_value(serde_json::json!({
+            "first_name": "Bilen",
+            "last_name": "Gizachew",
+            "email": "abebech_bekele@gmail.com",
+            "currency": "ETB",
+            "amount": 100,
+            "charge": 3.5,
+            "mode": "test",
+            "method": "test",
+            "type": "API",
+            "status": "success",
+            "ref_id": "6jnheVKQEmy",
+            "trx_ref": "chewatatest-6669",
+            "customization": null,
+            "meta": null,
+            "created_at": "2023-02-02T07:05:23.000000Z",
+            "updated_at": "2023-02-02T07:05:23.000000Z"
+        }))
+        .unwrap();
+        let transaction: Transaction = serde_json::from_value(serde_json::json!({
+            "status": "success",
+            "chapa_reference": "6jnheVKQEmy",
+            "type": "API",
+            "created_at": "2023-02-02T07:05:23.000000Z",
+            "currency": "ETB",
+            "amount": "100",
+            "charge": "3.5",
+            "trans_id": "1234",
+            "payment_method": "test",
+            "customer": {
+                "id": 1,
+                "first_name": "Bilen",
+                "last_name": "Gizachew",
+                "email": "abebech_bekele@gmail.com",
+                "mobile": "0900123456"
+            }
+        }))
+        .unwrap();
+
+        let from_verify = Payment::from(verify);
+        let from_list = Payment::from(transaction);
+
+        assert_eq!(from_verify.source, PaymentSource::Verification);
+        assert_eq!(from_list.source, PaymentSource::TransactionList);
+        assert_eq!(from_verify.id.as_deref(), Some("6jnheVKQEmy"));
+        assert_eq!(from_verify.tx_ref.as_deref(), Some("chewatatest-6669"));
+        assert_eq!(from_verify.id, from_list.id);
+        assert_eq!(from_verify.amount, from_list.amount);
+        assert_eq!(from_verify.status, PaymentStatus::Success);
+        assert_eq!(from_verify.status, from_list.status);
+        assert_eq!(from_verify.customer.email, from_list.customer.email);
+    }
+}
@@ -2,6 +2,9 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::error::{ChapaError, Result};
 
 // TODO: check the type of `amount` field has some inconsistency in the docs, sometimes it's string sometimes number
 // ------------------------------------- Initialize Payment ---------------------------------------------
@@ -36,7 +39,7 @@ pub struct InitializeOptions {
 }
 
 /// Represents a subaccount for payment splitting.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Subaccount {
     /// The unique identifier of the subaccount.
     pub id: String,
@@ -60,12 +63,116 @@ pub struct Customization {
     pub logo: Option<String>,
 }
 
+impl Customization {
+    /// The maximum length of the title, in user-perceived characters (graphemes).
+    pub const TITLE_MAX_LENGTH: usize = 16;
+    /// The maximum length of the description, in user-perceived characters (graphemes).
+    pub const DESCRIPTION_MAX_LENGTH: usize = 50;
+
+    /// The image types accepted for the logo, as MIME types.
+    pub const LOGO_TYPES: [&'static str; 5] = [
+        "image/png",
+        "image/jpeg",
+        "image/svg+xml",
+        "image/gif",
+        "image/webp",
+    ];
+    /// The file extensions accepted for the logo, matching [`Customization::LOGO_TYPES`].
+    pub const LOGO_EXTENSIONS: [&'static str; 6] = ["png", "jpg", "jpeg", "svg", "gif", "webp"];
+
+    /// Checks the title and description lengths against the checkout limits, and the logo URL.
+    ///
+    /// Lengths are counted in graphemes rather than bytes, so Amharic (Ge'ez script) text,
+    /// which takes 3 bytes per character in UTF-8, gets the same limits as Latin text.
+    ///
+    /// The logo must be an HTTPS URL, since the checkout page is served over HTTPS, and its file
+    /// extension, if any, one of [`Customization::LOGO_EXTENSIONS`]. Whether the logo is
+    /// reachable is only checked by the client, see
+    /// [`ChapaConfigBuilder::check_logo`](crate::config::ChapaConfigBuilder::check_logo).
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidCustomization`] if a field is too long or the logo URL is
+    /// invalid.
+    pub fn validate(&self) -> Result<()> {
+        check_length("title", self.title.as_deref(), Self::TITLE_MAX_LENGTH)?;
+        check_length(
+            "description",
+            self.description.as_deref(),
+            Self::DESCRIPTION_MAX_LENGTH,
+        )?;
+        match &self.logo {
+            Some(logo) => check_logo_url(logo),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks the `content-type` a logo is served with against [`Customization::LOGO_TYPES`].
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidCustomization`] if the type isn't an allowed image type.
+    pub fn check_logo_type(content_type: &str) -> Result<()> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if Self::LOGO_TYPES.contains(&mime.as_str()) {
+            Ok(())
+        } else {
+            Err(ChapaError::InvalidCustomization(format!(
+                "logo is served as {content_type}, expected one of {}",
+                Self::LOGO_TYPES.join(", ")
+            )))
+        }
+    }
+}
+
+fn check_logo_url(logo: &str) -> Result<()> {
+    let invalid = |reason: String| Err(ChapaError::InvalidCustomization(reason));
+    let url = match url::Url::parse(logo) {
+        Ok(url) => url,
+        Err(e) => return invalid(format!("logo is not a valid URL: {e}")),
+    };
+    if url.scheme() != "https" {
+        return invalid(format!("logo must be an HTTPS URL, got {logo}"));
+    }
+    let file_name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default();
+    match file_name.rsplit_once('.') {
+        Some((_, extension))
+            if !Customization::LOGO_EXTENSIONS
+                .contains(&extension.to_ascii_lowercase().as_str()) =>
+        {
+            invalid(format!(
+                "logo must be a {} image, got .{extension}",
+                Customization::LOGO_EXTENSIONS.join("/")
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_length(field: &str, value: Option<&str>, max: usize) -> Result<()> {
+    let length = value.map_or(0, |v| v.graphemes(true).count());
+    if length > max {
+        return Err(ChapaError::InvalidCustomization(format!(
+            "{field} is {length} characters long, the maximum is {max}"
+        )));
+    }
+    Ok(())
+}
+
 /// Enum representing the type of split for subaccounts.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SplitType {
     /// Percentage-based split.
+    #[serde(rename = "percentage", alias = "PERCENTAGE")]
     PERCENTAGE,
     /// Flat amount split.
+    #[serde(rename = "flat", alias = "FLAT")]
     FLAT,
 }
 
@@ -76,6 +183,115 @@ pub struct CheckoutURL {
     pub checkout_url: String,
 }
 
+/// A checkout URL with an SDK-side expiry, see
+/// [`ChapaClient::start_checkout`](crate::client::ChapaClient::start_checkout).
+///
+/// Chapa doesn't take an expiry when initializing a transaction, so the expiry is only tracked
+/// here: once expired, the session stops handing out its checkout URL, and
+/// [`ChapaClient::renew_checkout`](crate::client::ChapaClient::renew_checkout) initializes a new
+/// checkout with the next `tx_ref` variant of the order (`order-1042`, `order-1042-r5f3a9c01-2`,
+/// ...). Variants carry a random token of the session, so they can't collide with the `tx_ref`
+/// of another order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckoutSession {
+    /// The `tx_ref` of the order, used by the first checkout.
+    pub base_tx_ref: String,
+    /// The number of checkouts initialized for the order, starting at 1.
+    pub attempt: u32,
+    /// The random token of the `tx_ref` variants of the session.
+    token: String,
+    /// The checkout URL of the current checkout.
+    checkout_url: String,
+    /// When the current checkout was initialized.
+    pub created_at: DateTime<Utc>,
+    /// When the current checkout stops being handed out.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl CheckoutSession {
+    /// Creates the session of the `attempt`th checkout of the order `base_tx_ref`, initialized
+    /// now with `checkout_url`.
+    #[cfg(feature = "async")]
+    pub(crate) fn new(
+        base_tx_ref: String,
+        token: String,
+        attempt: u32,
+        checkout_url: String,
+        lifetime: chrono::Duration,
+    ) -> Self {
+        let created_at = Utc::now();
+        Self {
+            base_tx_ref,
+            attempt,
+            token,
+            checkout_url,
+            created_at,
+            expires_at: created_at
+                .checked_add_signed(lifetime)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        }
+    }
+
+    /// Generates the random token of the `tx_ref` variants of a new session.
+    #[cfg(feature = "async")]
+    pub(crate) fn new_token() -> String {
+        use std::hash::{BuildHasher, Hasher, RandomState};
+
+        format!("r{:08x}", RandomState::new().build_hasher().finish() as u32)
+    }
+
+    /// The `tx_ref` of the `attempt`th checkout of the order: the base `tx_ref` for the first
+    /// one, the base `tx_ref` followed by the token of the session and the attempt otherwise.
+    pub fn tx_ref_variant(&self, attempt: u32) -> String {
+        Self::variant(&self.base_tx_ref, &self.token, attempt)
+    }
+
+    /// Helper function building the `attempt`th `tx_ref` variant of `base_tx_ref`.
+    pub(crate) fn variant(base_tx_ref: &str, token: &str, attempt: u32) -> String {
+        if attempt <= 1 {
+            base_tx_ref.to_string()
+        } else {
+            format!("{}-{}-{}", base_tx_ref, token, attempt)
+        }
+    }
+
+    /// The `tx_ref` of the current checkout.
+    pub fn tx_ref(&self) -> String {
+        self.tx_ref_variant(self.attempt)
+    }
+
+    /// The `tx_ref` of every checkout initialized for the order, oldest first. An expired
+    /// checkout isn't cancelled on Chapa's side, so a customer who kept the page open may still
+    /// pay it: reconcile the order against all of them.
+    pub fn tx_refs(&self) -> Vec<String> {
+        (1..=self.attempt)
+            .map(|attempt| self.tx_ref_variant(attempt))
+            .collect()
+    }
+
+    /// Returns `true` if the checkout expired.
+    pub fn is_expired(&self) -> bool {
+        Utc::now() >= self.expires_at
+    }
+
+    /// The checkout URL, unless the checkout expired.
+    pub fn checkout_url(&self) -> Option<&str> {
+        (!self.is_expired()).then_some(self.checkout_url.as_str())
+    }
+
+    /// The random token of the `tx_ref` variants of the session.
+    #[cfg(feature = "async")]
+    pub(crate) fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// How long the checkouts of the session stay valid.
+    #[cfg(feature = "async")]
+    pub(crate) fn lifetime(&self) -> chrono::Duration {
+        self.expires_at - self.created_at
+    }
+}
+
 // ------------------------------------- Verify Payment ---------------------------------------------
 
 /// Represents the detailed data received when verifying a payment transaction.
@@ -103,15 +319,194 @@ pub struct VerifyData {
     /// The status of the transaction.
     pub status: Option<String>,
     /// The reference for the transaction.
+    #[serde(alias = "ref_id", alias = "chapa_reference")]
     pub reference: Option<String>,
     /// The transaction reference.
+    #[serde(alias = "trx_ref")]
     pub tx_ref: Option<String>,
     /// The customization details of the transaction.
     pub customization: Option<Customization>,
     /// Additional metadata associated with the transaction.
     pub meta: Option<String>, // TODO: Adjust the type as needed(could be the map or a specific struct), to my knowledge the type is not documented
+    /// The split settlement of the payment, for transactions initialized with subaccounts.
+    #[serde(default, alias = "subaccounts", alias = "splits")]
+    pub split: Option<Vec<SplitSettlement>>,
     /// The timestamp when the transaction was created.
     pub created_at: DateTime<Utc>,
     /// The timestamp when the transaction was last updated.
     pub updated_at: DateTime<Utc>,
 }
+
+/// The share of a payment settled to a subaccount.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitSettlement {
+    /// The identifier of the subaccount.
+    #[serde(alias = "id")]
+    pub subaccount_id: String,
+    /// The type of split applied.
+    pub split_type: Option<SplitType>,
+    /// The value of the split applied (percentage value or flat amount).
+    pub split_value: Option<f64>,
+    /// The amount settled to the subaccount.
+    pub amount: Option<f64>,
+    /// The fee deducted from the subaccount's share.
+    pub charge: Option<f64>,
+}
+
+impl VerifyData {
+    /// The Chapa reference of the transaction, whichever of `reference`, `ref_id` or
+    /// `chapa_reference` the API used.
+    pub fn chapa_reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+
+    /// The merchant's transaction reference, whichever of `tx_ref` or `trx_ref` the API used.
+    pub fn tx_ref(&self) -> Option<&str> {
+        self.tx_ref.as_deref()
+    }
+
+    /// The split settlement of the payment, empty for payments without subaccounts.
+    pub fn splits(&self) -> &[SplitSettlement] {
+        self.split.as_deref().unwrap_or_default()
+    }
+
+    /// Checks that the payment was split between exactly the `requested` subaccounts, with the
+    /// requested split type and value where they were set.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::SplitMismatch`] describing the first difference found.
+    pub fn check_split(&self, requested: &[Subaccount]) -> Result<()> {
+        let mismatch = |reason: String| Err(ChapaError::SplitMismatch(reason));
+        let settled = self.splits();
+        for subaccount in requested {
+            let Some(settlement) = settled
+                .iter()
+                .find(|settlement| settlement.subaccount_id == subaccount.id)
+            else {
+                return mismatch(format!("subaccount {} received no share", subaccount.id));
+            };
+            if subaccount.split_type.is_some() && settlement.split_type != subaccount.split_type {
+                return mismatch(format!(
+                    "subaccount {} was split by {:?} instead of {:?}",
+                    subaccount.id, settlement.split_type, subaccount.split_type
+                ));
+            }
+            let value_differs = subaccount.split_value.is_some_and(|value| {
+                settlement
+                    .split_value
+                    .is_none_or(|settled| (settled - value).abs() > f64::EPSILON)
+            });
+            if value_differs {
+                return mismatch(format!(
+                    "subaccount {} was split with {:?} instead of {:?}",
+                    subaccount.id, settlement.split_value, subaccount.split_value
+                ));
+            }
+        }
+        if let Some(extra) = settled
+            .iter()
+            .find(|settlement| !requested.iter().any(|s| s.id == settlement.subaccount_id))
+        {
+            return mismatch(format!(
+                "subaccount {} received a share that was not requested",
+                extra.subaccount_id
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn customization(title: &str, description: &str) -> Customization {
+        Customization {
+            title: Some(title.to_string()),
+            description: Some(description.to_string()),
+            logo: None,
+        }
+    }
+
+    #[test]
+    fn test_customization_logo() {
+        let with_logo = |logo: &str| Customization {
+            logo: Some(logo.to_string()),
+            ..customization("Coffee", "")
+        };
+        assert!(
+            with_logo("https://cdn.example.com/brand/logo.PNG")
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            with_logo("https://cdn.example.com/logo?size=64")
+                .validate()
+                .is_ok()
+        );
+        for logo in [
+            "http://cdn.example.com/logo.png",
+            "https://cdn.example.com/logo.pdf",
+            "not a url",
+        ] {
+            assert!(matches!(
+                with_logo(logo).validate(),
+                Err(ChapaError::InvalidCustomization(_))
+            ));
+        }
+        assert!(Customization::check_logo_type("image/svg+xml; charset=utf-8").is_ok());
+        assert!(Customization::check_logo_type("text/html").is_err());
+    }
+
+    #[test]
+    fn test_customization_counts_amharic_graphemes() {
+        // 16 graphemes but 38 bytes, would be rejected by a byte count.
+        let title = "የቡና ቤት ክፍያ ቁጥር 1";
+        assert!(title.len() > Customization::TITLE_MAX_LENGTH);
+        assert!(customization(title, "ለአገልግሎቱ እናመሰግናለን").validate().is_ok());
+
+        let too_long = "የአዲስ አበባ ቡና ቤት ወርሃዊ ክፍያ";
+        let err = customization(too_long, "").validate().unwrap_err();
+        assert!(matches!(err, ChapaError::InvalidCustomization(msg) if msg.starts_with("title")));
+    }
+
+    #[test]
+    fn test_customization_preserves_amharic_text() {
+        let original = customization("ቡና ክፍያ", "ለአገልግሎቱ እናመሰግናለን");
+        let json = serde_json::to_string(&original).unwrap();
+        assert!(json.contains("ቡና ክፍያ"));
+
+        let parsed: Customization = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.title, original.title);
+        assert_eq!(parsed.description, original.description);
+    }
+
+    #[test]
+    fn test_check_split() {
+        let data: VerifyData = serde_json::from_value(serde_json::json!({
+            "amount": 100,
+            "status": "success",
+            "tx_ref": "order-1",
+            "subaccounts": [
+                {"id": "sub-1", "split_type": "percentage", "split_value": 0.2, "amount": 20, "charge": 0.7}
+            ],
+            "created_at": "2023-02-02T07:05:23.000000Z",
+            "updated_at": "2023-02-02T07:05:23.000000Z"
+        }))
+        .unwrap();
+        assert_eq!(data.splits()[0].amount, Some(20.0));
+
+        let requested = |id: &str, value: f64| Subaccount {
+            id: id.to_string(),
+            split_type: Some(SplitType::PERCENTAGE),
+            split_value: Some(value),
+        };
+        assert!(data.check_split(&[requested("sub-1", 0.2)]).is_ok());
+        assert!(matches!(
+            data.check_split(&[requested("sub-1", 0.3)]),
+            Err(ChapaError::SplitMismatch(_))
+        ));
+        assert!(data.check_split(&[requested("sub-2", 0.2)]).is_err());
+        assert!(data.check_split(&[]).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+//! Models related to payment receipts.
+//!
+//! Chapa only exposes receipts as a hosted page (`https://chapa.link/payment-receipt/{reference}`),
+//! so the [`Receipt`] is built from the transaction verification payload, which carries the same details.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::payment::VerifyData;
+
+const RECEIPT_BASE_URL: &str = "https://chapa.link/payment-receipt";
+
+/// The receipt details of a completed payment, suitable for generating customer-facing invoices.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Receipt {
+    /// The receipt number, which is the Chapa reference of the payment.
+    pub receipt_number: String,
+    /// The merchant's transaction reference.
+    pub tx_ref: Option<String>,
+    /// The amount paid.
+    pub amount: f64,
+    /// The fee charged by Chapa for the payment.
+    pub charge: Option<f64>,
+    /// The currency of the payment (e.g., "ETB", "USD").
+    pub currency: Option<String>,
+    /// The payment method used.
+    pub method: Option<String>,
+    /// The first name of the payer.
+    pub first_name: Option<String>,
+    /// The last name of the payer.
+    pub last_name: Option<String>,
+    /// The email address of the payer.
+    pub email: Option<String>,
+    /// When the payment was last updated, i.e. completed for successful payments.
+    pub paid_at: DateTime<Utc>,
+    /// The URL of the receipt page hosted by Chapa.
+    pub receipt_url: String,
+}
+
+impl Receipt {
+    /// Builds a receipt from verification data, returning `None` if the payment has no Chapa reference.
+    pub fn from_verify_data(data: VerifyData) -> Option<Self> {
+        let receipt_number = data.reference?;
+        Some(Receipt {
+            receipt_url: format!("{}/{}", RECEIPT_BASE_URL, receipt_number),
+            receipt_number,
+            tx_ref: data.tx_ref,
+            amount: data.amount,
+            charge: data.charge,
+            currency: data.currency,
+            method: data.method,
+            first_name: data.first_name,
+            last_name: data.last_name,
+            email: data.email,
+            paid_at: data.updated_at,
+        })
+    }
+}
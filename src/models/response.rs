@@ -1,23 +1,227 @@
 //! Response model for chapa API
 
-use serde::Deserialize;
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use url::Url;
 
+use crate::error::{ChapaError, ResponseHeaders};
 use crate::models::{
+    balance::Balance,
     bank::Bank,
+    direct_charge::DirectChargeData,
     payment::{CheckoutURL, VerifyData},
+    subaccount::{CreateSubaccountData, SubaccountDetails},
+    swap::SwapData,
+    transaction::TransactionLog,
+    transfer::{BulkTransferData, VerifyTransferData},
 };
 
 /// Represents a generic response from the Chapa API.
 #[derive(Debug, Clone, Deserialize)]
 pub struct ChapaResponse<T> {
     /// The status message of the response.
-    pub message: Value, // NOTE: Changed to Value to handle empty strings or other types, since some responses might return non-string messages
+    #[serde(default)]
+    pub message: Message,
     #[serde(default = "unspecified_status")]
     /// The status of the response.
     pub status: String,
     /// The data section of the response.
     pub data: T,
+    /// The original JSON payload, only populated when the client is configured with
+    /// `retain_raw_response(true)`. Useful to archive exact payloads for audit purposes.
+    #[serde(skip)]
+    pub raw: Option<Value>,
+    /// The selected headers of the response, only populated when Chapa answered with an HTTP
+    /// error status.
+    #[serde(skip)]
+    pub headers: Option<Box<ResponseHeaders>>,
+}
+
+impl<T> ChapaResponse<T> {
+    /// Builds a [`ChapaError::ApiError`] from the message of the response, with its headers.
+    pub fn api_error(&self) -> ChapaError {
+        ChapaError::api_error_with_headers(
+            self.message.to_display_string(),
+            self.headers.as_deref().cloned().unwrap_or_default(),
+        )
+        .with_validation_errors(self.validation_errors().cloned())
+    }
+
+    /// The validation errors per field, when the request was refused because of them.
+    pub fn validation_errors(&self) -> Option<&ValidationErrors> {
+        self.message.field_errors()
+    }
+}
+
+/// The `message` of a Chapa response, which is usually a sentence but is an object of
+/// validation errors per field when a request is refused.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Message {
+    /// A plain message, e.g. `"Hosted Link"`.
+    Text(String),
+    /// Validation errors per field, e.g. `{"email": ["The email must be a valid email address."]}`.
+    FieldErrors(ValidationErrors),
+    /// A message of any other shape, or `null`.
+    Other(Value),
+}
+
+impl Message {
+    /// Returns the message if it is plain text.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Message::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the validation errors per field, if the message is made of them.
+    pub fn field_errors(&self) -> Option<&ValidationErrors> {
+        match self {
+            Message::FieldErrors(errors) => Some(errors),
+            _ => None,
+        }
+    }
+
+    /// The validation errors of `field`, empty if the field has none or the message isn't made
+    /// of validation errors.
+    pub fn errors_for(&self, field: &str) -> &[String] {
+        self.field_errors()
+            .and_then(|errors| errors.get(field))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns `true` if the response had no message.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Message::Text(text) => text.trim().is_empty(),
+            Message::FieldErrors(errors) => errors.is_empty(),
+            Message::Other(value) => value.is_null(),
+        }
+    }
+
+    /// Renders the message for humans: the text as is, field errors as `field: error; ...`, and
+    /// anything else as JSON.
+    pub fn to_display_string(&self) -> String {
+        match self {
+            Message::Text(text) => text.clone(),
+            Message::FieldErrors(errors) => errors.to_string(),
+            Message::Other(value) => value.to_string(),
+        }
+    }
+}
+
+/// Validation errors per field, returned by Chapa as the `message` of a refused request.
+///
+/// Fields of list items are named by their path, e.g. `bulk_data.1.amount` for the amount of the
+/// second transfer of a bulk transfer, see [`ValidationErrors::by_index`]. Dereferences to the
+/// map of field to messages.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ValidationErrors(BTreeMap<String, Vec<String>>);
+
+impl ValidationErrors {
+    /// The errors of the items of the list `collection` (e.g. `"bulk_data"`), by index of the
+    /// item, with the fields named relative to the item (e.g. `"amount"`).
+    pub fn by_index(&self, collection: &str) -> BTreeMap<usize, ValidationErrors> {
+        let prefix = format!("{}.", collection);
+        let mut items: BTreeMap<usize, ValidationErrors> = BTreeMap::new();
+        for (field, messages) in &self.0 {
+            let Some((index, field)) = field
+                .strip_prefix(&prefix)
+                .and_then(|path| path.split_once('.'))
+                .and_then(|(index, field)| Some((index.parse().ok()?, field)))
+            else {
+                continue;
+            };
+            items
+                .entry(index)
+                .or_default()
+                .0
+                .insert(field.to_string(), messages.clone());
+        }
+        items
+    }
+
+    /// Consumes the errors, returning the map of field to messages.
+    pub fn into_inner(self) -> BTreeMap<String, Vec<String>> {
+        self.0
+    }
+}
+
+impl std::ops::Deref for ValidationErrors {
+    type Target = BTreeMap<String, Vec<String>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<BTreeMap<String, Vec<String>>> for ValidationErrors {
+    fn from(errors: BTreeMap<String, Vec<String>>) -> Self {
+        Self(errors)
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    /// Renders the errors as `field: error, error; field: error`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rendered = self
+            .0
+            .iter()
+            .map(|(field, messages)| format!("{}: {}", field, messages.join(", ")))
+            .collect::<Vec<_>>()
+            .join("; ");
+        f.write_str(&rendered)
+    }
+}
+
+impl Default for Message {
+    fn default() -> Self {
+        Message::Other(Value::Null)
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Message::Text(text)
+    }
+}
+
+impl From<&str> for Message {
+    fn from(text: &str) -> Self {
+        Message::Text(text.to_string())
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_display_string())
+    }
+}
+
+/// Implemented by response types that can carry the raw JSON payload they were parsed from.
+///
+/// The default implementation discards the payload, so types without a place to store it
+/// only need an empty `impl`.
+pub trait RawResponse {
+    /// Attaches the original JSON payload to the typed response.
+    fn attach_raw(&mut self, _raw: Value) {}
+
+    /// Attaches the selected headers of an error response to the typed response.
+    fn attach_headers(&mut self, _headers: ResponseHeaders) {}
+}
+
+impl<T> RawResponse for ChapaResponse<T> {
+    fn attach_raw(&mut self, raw: Value) {
+        self.raw = Some(raw);
+    }
+
+    fn attach_headers(&mut self, headers: ResponseHeaders) {
+        self.headers = Some(Box::new(headers));
+    }
 }
 
 fn unspecified_status() -> String {
@@ -26,7 +230,263 @@ fn unspecified_status() -> String {
 
 /// Type alias for GetBanksResponse, which contains a list of banks.
 pub type GetBanksResponse = ChapaResponse<Option<Vec<Bank>>>;
+/// Type alias for GetBalancesResponse, which contains the balance of every currency.
+pub type GetBalancesResponse = ChapaResponse<Option<Vec<Balance>>>;
+/// Type alias for GetPaymentChannelsResponse, which contains the identifiers of the enabled channels.
+pub type GetPaymentChannelsResponse = ChapaResponse<Option<Vec<String>>>;
 /// Type alias for InitializeResponse, which contains the checkout URL.
 pub type InitializeResponse = ChapaResponse<Option<CheckoutURL>>;
 /// Type alias for VerifyResponse, which contains the verification data.
 pub type VerifyResponse = ChapaResponse<Option<VerifyData>>;
+/// Type alias for TransactionLogsResponse, which contains the logs of a payment.
+pub type TransactionLogsResponse = ChapaResponse<Option<Vec<TransactionLog>>>;
+/// Type alias for BulkTransferResponse, which contains the identifier of the queued batch.
+pub type BulkTransferResponse = ChapaResponse<Option<BulkTransferData>>;
+/// Type alias for DirectChargeResponse, which contains the authorization details of the charge.
+pub type DirectChargeResponse = ChapaResponse<Option<DirectChargeData>>;
+/// Type alias for VerifyTransferResponse, which contains the details of the transfer.
+pub type VerifyTransferResponse = ChapaResponse<Option<VerifyTransferData>>;
+/// Type alias for SwapResponse, which contains the amount swapped and the rate applied.
+pub type SwapResponse = ChapaResponse<Option<SwapData>>;
+/// Type alias for CreateSubaccountResponse, which contains the identifier of the new subaccount.
+pub type CreateSubaccountResponse = ChapaResponse<Option<CreateSubaccountData>>;
+/// Type alias for GetSubaccountsResponse, which contains the merchant's subaccounts.
+pub type GetSubaccountsResponse = ChapaResponse<Option<Vec<SubaccountDetails>>>;
+
+impl InitializeResponse {
+    /// The checkout URL to redirect the customer to.
+    ///
+    /// # Errors
+    /// Returns the refusal as a [`ChapaError::ApiError`] (see [`ChapaResponse::api_error`]) if
+    /// Chapa returned no checkout URL, or a [`ChapaError::ApiError`] if the URL is not valid.
+    pub fn checkout_url(&self) -> Result<Url, ChapaError> {
+        let Some(checkout) = self.data.as_ref().filter(|_| self.status == "success") else {
+            return Err(self.api_error());
+        };
+        Url::parse(&checkout.checkout_url).map_err(|e| {
+            ChapaError::api_error(format!(
+                "invalid checkout URL {}: {}",
+                checkout.checkout_url, e
+            ))
+        })
+    }
+
+    /// Redirects the customer to the checkout URL with a `303 See Other`, for Axum handlers.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use axum::response::Redirect;
+    /// use chapa_rust::{error::ChapaError, models::payment::InitializeOptions, state::ChapaState};
+    ///
+    /// async fn pay(chapa: ChapaState) -> Result<Redirect, ChapaError> {
+    ///     let options = InitializeOptions {
+    ///         amount: "100".to_string(),
+    ///         currency: "ETB".to_string(),
+    ///         tx_ref: "order-1042".to_string(),
+    ///         ..Default::default()
+    ///     };
+    ///     chapa.initialize_transaction(options).await?.axum_redirect()
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// See [`InitializeResponse::checkout_url`].
+    #[cfg(feature = "axum")]
+    pub fn axum_redirect(&self) -> Result<axum::response::Redirect, ChapaError> {
+        Ok(axum::response::Redirect::to(self.checkout_url()?.as_str()))
+    }
+
+    /// Redirects the customer to the checkout URL with a `303 See Other`, for Actix-web
+    /// handlers.
+    ///
+    /// # Errors
+    /// See [`InitializeResponse::checkout_url`].
+    #[cfg(feature = "actix")]
+    pub fn actix_redirect(&self) -> Result<actix_web::HttpResponse, ChapaError> {
+        Ok(actix_web::HttpResponse::SeeOther()
+            .insert_header((
+                actix_web::http::header::LOCATION,
+                self.checkout_url()?.as_str(),
+            ))
+            .finish())
+    }
+}
+
+impl fmt::Display for InitializeResponse {
+    /// A one-line summary, e.g. `success: checkout https://checkout.chapa.co/...`, or the status
+    /// and message of a refused initialization.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.data {
+            Some(checkout) => write!(f, "{}: checkout {}", self.status, checkout.checkout_url),
+            None => write!(f, "{}: {}", self.status, self.message),
+        }
+    }
+}
+
+impl fmt::Display for VerifyResponse {
+    /// A one-line summary, e.g. `success: payment TX-abc of 100 ETB is success (reference
+    /// APfxkC4wuFSu)`, or the status and message when Chapa returned no payment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(payment) = &self.data else {
+            return write!(f, "{}: {}", self.status, self.message);
+        };
+        write!(f, "{}: payment", self.status)?;
+        if let Some(tx_ref) = &payment.tx_ref {
+            write!(f, " {}", tx_ref)?;
+        }
+        write!(f, " of {}", payment.amount)?;
+        if let Some(currency) = &payment.currency {
+            write!(f, " {}", currency)?;
+        }
+        write!(f, " is {}", payment.status.as_deref().unwrap_or("unknown"))?;
+        if let Some(reference) = &payment.reference {
+            write!(f, " (reference {})", reference)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of [`ChapaClient::initialize_or_fetch`](crate::client::ChapaClient::initialize_or_fetch).
+#[derive(Debug)]
+pub enum InitializeOutcome {
+    /// The transaction was initialized, with its checkout URL.
+    Initialized(InitializeResponse),
+    /// The `tx_ref` was already used, with the current state of the existing transaction.
+    Existing(Box<VerifyResponse>),
+}
+
+/// The result of [`ChapaClient::checkout`](crate::client::ChapaClient::checkout).
+#[derive(Debug)]
+pub enum CheckoutOutcome {
+    /// A new transaction was initialized, with its checkout URL.
+    Created(String),
+    /// A checkout initialized earlier for the `tx_ref` is still pending, with its checkout URL.
+    Pending(String),
+    /// The `tx_ref` was already used and can't be paid anymore (or its checkout URL is unknown),
+    /// with the current state of the transaction.
+    Existing(Box<VerifyResponse>),
+}
+
+impl CheckoutOutcome {
+    /// The checkout URL the customer should be sent to, unless the transaction already exists.
+    pub fn checkout_url(&self) -> Option<&str> {
+        match self {
+            CheckoutOutcome::Created(url) | CheckoutOutcome::Pending(url) => Some(url),
+            CheckoutOutcome::Existing(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_summaries() {
+        let initialize: InitializeResponse = serde_json::from_str(
+            r#"{"message":"Hosted Link","status":"success","data":{"checkout_url":"https://checkout.chapa.co/checkout/payment/abc"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            initialize.to_string(),
+            "success: checkout https://checkout.chapa.co/checkout/payment/abc"
+        );
+        let refused: InitializeResponse =
+            serde_json::from_str(r#"{"message":"Invalid API Key","status":"failed","data":null}"#)
+                .unwrap();
+        assert_eq!(refused.to_string(), "failed: Invalid API Key");
+
+        let verify: VerifyResponse = serde_json::from_str(
+            r#"{"message":"Payment details","status":"success","data":{"currency":"ETB","amount":100,"status":"pending","reference":"APfxkC4wuFSu","tx_ref":"TX-abc","created_at":"2023-02-02T07:05:23.000000Z","updated_at":"2023-02-02T07:05:23.000000Z"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            verify.to_string(),
+            "success: payment TX-abc of 100 ETB is pending (reference APfxkC4wuFSu)"
+        );
+    }
+
+    #[test]
+    fn test_checkout_url() {
+        let initialize: InitializeResponse = serde_json::from_str(
+            r#"{"message":"Hosted Link","status":"success","data":{"checkout_url":"https://checkout.chapa.co/checkout/payment/abc"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            initialize.checkout_url().unwrap().path(),
+            "/checkout/payment/abc"
+        );
+        let refused: InitializeResponse =
+            serde_json::from_str(r#"{"message":"Invalid API Key","status":"failed","data":null}"#)
+                .unwrap();
+        assert!(
+            matches!(refused.checkout_url(), Err(ChapaError::ApiError(details)) if details.message == "Invalid API Key")
+        );
+
+        #[cfg(feature = "axum")]
+        {
+            use axum::response::IntoResponse;
+
+            let response = initialize.axum_redirect().unwrap().into_response();
+            assert_eq!(response.status(), axum::http::StatusCode::SEE_OTHER);
+            assert_eq!(
+                response.headers()["location"],
+                "https://checkout.chapa.co/checkout/payment/abc"
+            );
+        }
+        #[cfg(feature = "actix")]
+        {
+            let response = initialize.actix_redirect().unwrap();
+            assert_eq!(response.status(), actix_web::http::StatusCode::SEE_OTHER);
+            assert_eq!(
+                response.headers().get("location").unwrap(),
+                "https://checkout.chapa.co/checkout/payment/abc"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bulk_validation_errors() {
+        let response: BulkTransferResponse = serde_json::from_str(
+            r#"{"status":"failed","data":null,"message":{"bulk_data.1.amount":["The amount must be at least 10."],"bulk_data.1.bank_code":["Invalid Bank"],"bulk_data.3.account_number":["The account number is required."],"title":["The title is required."]}}"#,
+        )
+        .unwrap();
+        let errors = response.validation_errors().unwrap();
+        assert_eq!(errors.len(), 4);
+        let items = errors.by_index("bulk_data");
+        assert_eq!(items.keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(items[&1]["amount"], vec!["The amount must be at least 10."]);
+
+        let error = response.api_error();
+        let ChapaError::ApiError(details) = &error else {
+            panic!("expected an API error, got {:?}", error);
+        };
+        assert_eq!(details.validation_errors.as_ref(), Some(errors));
+    }
+
+    #[test]
+    fn test_message_shapes() {
+        let text: Message = serde_json::from_str(r#""Hosted Link""#).unwrap();
+        assert_eq!(text.as_text(), Some("Hosted Link"));
+
+        let errors: Message = serde_json::from_str(
+            r#"{"email":["The email must be a valid email address."],"amount":["The amount field is required."]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            errors.to_display_string(),
+            "amount: The amount field is required.; email: The email must be a valid email address."
+        );
+        assert_eq!(
+            errors.errors_for("amount"),
+            ["The amount field is required."]
+        );
+        assert!(errors.errors_for("currency").is_empty());
+        assert!(Message::from("Hosted Link").errors_for("amount").is_empty());
+        assert_eq!(Message::from("Hosted Link"), text);
+
+        let response: ChapaResponse<Option<Value>> =
+            serde_json::from_str(r#"{"status":"failed","data":null}"#).unwrap();
+        assert!(response.message.is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! Models related to subaccounts, which receive a share of the payments of a transaction
+//! (split payments).
+
+use crate::{
+    error::{ChapaError, Result},
+    models::payment::SplitType,
+};
+
+crate::chapa_model! {
+    /// The Request structure for creating a subaccount.
+    pub struct CreateSubaccountOptions {
+        /// The name of the business the subaccount belongs to.
+        pub business_name: String,
+        /// The name of the account holder.
+        pub account_name: String,
+        /// The bank code of the subaccount's bank, see [`ChapaClient::get_banks`](crate::client::ChapaClient::get_banks).
+        pub bank_code: u32,
+        /// The bank account number the share is settled to.
+        pub account_number: String,
+        /// The share of each payment: a fraction between 0 and 1 for [`SplitType::PERCENTAGE`], an
+        /// amount for [`SplitType::FLAT`].
+        pub split_value: f64,
+        /// How `split_value` is applied.
+        pub split_type: SplitType,
+    }
+}
+
+impl CreateSubaccountOptions {
+    /// Checks the split settings.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidAmount`] if a percentage split is not within `(0, 1]` or a flat
+    /// split is not positive.
+    pub fn validate(&self) -> Result<()> {
+        let valid = match self.split_type {
+            SplitType::PERCENTAGE => self.split_value > 0.0 && self.split_value <= 1.0,
+            SplitType::FLAT => self.split_value > 0.0,
+        };
+        if !valid || !self.split_value.is_finite() {
+            return Err(ChapaError::InvalidAmount(format!(
+                "{} is not a valid {:?} split",
+                self.split_value, self.split_type
+            )));
+        }
+        Ok(())
+    }
+}
+
+crate::chapa_model! {
+    /// Represents the data received after creating a subaccount.
+    pub struct CreateSubaccountData {
+        /// The identifier of the new subaccount, used in split payments.
+        #[serde(rename = "subaccounts[id]", alias = "subaccount_id", alias = "id")]
+        pub subaccount_id: String,
+    }
+}
+
+crate::chapa_model! {
+    /// A subaccount, as listed by Chapa.
+    pub struct SubaccountDetails {
+        /// The identifier of the subaccount.
+        pub id: String,
+        /// The name of the business the subaccount belongs to.
+        pub business_name: Option<String>,
+        /// The name of the account holder.
+        pub account_name: Option<String>,
+        /// The bank code of the subaccount's bank.
+        pub bank_code: Option<u32>,
+        /// The name of the subaccount's bank.
+        pub bank_name: Option<String>,
+        /// The bank account number the share is settled to.
+        pub account_number: Option<String>,
+        /// The share of each payment.
+        pub split_value: Option<f64>,
+        /// How `split_value` is applied.
+        pub split_type: Option<SplitType>,
+        /// The creation timestamp of the subaccount.
+        pub created_at: Option<String>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subaccount_models() {
+        let mut options = CreateSubaccountOptions {
+            business_name: "Abebe Souq".to_string(),
+            account_name: "Abebe Bikila".to_string(),
+            bank_code: 130,
+            account_number: "0123456789".to_string(),
+            split_value: 0.2,
+            split_type: SplitType::PERCENTAGE,
+        };
+        assert!(options.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&options).unwrap()["split_type"],
+            "percentage"
+        );
+        options.split_value = 20.0;
+        assert!(options.validate().is_err());
+
+        let created: CreateSubaccountData =
+            serde_json::from_str(r#"{"subaccounts[id]":"837b4e5e-57b3-4a7c-9a83-1234567890ab"}"#)
+                .unwrap();
+        assert_eq!(
+            created.subaccount_id,
+            "837b4e5e-57b3-4a7c-9a83-1234567890ab"
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Models related to currency swaps (e.g. USD to ETB).
+
+use crate::{
+    error::{ChapaError, Result},
+    models::response::Message,
+};
+
+crate::chapa_model! {
+    /// The Request structure for swapping an amount from one currency to another.
+    pub struct SwapOptions {
+        /// The amount to swap, in the `from` currency. Chapa requires at least 1.
+        pub amount: f64,
+        /// The currency to swap from, usually "USD".
+        pub from: String,
+        /// The currency to swap to, usually "ETB".
+        pub to: String,
+    }
+}
+
+impl SwapOptions {
+    /// The smallest amount Chapa swaps.
+    pub const MIN_AMOUNT: f64 = 1.0;
+
+    /// Checks the amount against [`SwapOptions::MIN_AMOUNT`].
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::SwapAmountTooLow`] if the amount is below the minimum (or not a
+    /// number).
+    pub fn validate(&self) -> Result<()> {
+        if self.amount >= Self::MIN_AMOUNT {
+            Ok(())
+        } else {
+            Err(ChapaError::SwapAmountTooLow {
+                amount: self.amount,
+                minimum: Self::MIN_AMOUNT,
+            })
+        }
+    }
+
+    /// Maps the message of a refused swap to the matching typed error, e.g.
+    /// `"Amount must be at least 1"` to [`ChapaError::SwapAmountTooLow`]. Returns `None` for
+    /// messages that aren't specific to swaps.
+    pub fn refusal(&self, message: &Message) -> Option<ChapaError> {
+        let pair_refused = message
+            .field_errors()
+            .is_some_and(|errors| errors.contains_key("from") || errors.contains_key("to"));
+        let text = message.to_display_string().to_lowercase();
+        if pair_refused
+            || [
+                "not supported",
+                "unsupported",
+                "invalid currency",
+                "currency pair",
+            ]
+            .iter()
+            .any(|needle| text.contains(needle))
+        {
+            return Some(ChapaError::UnsupportedSwapPair {
+                from: self.from.clone(),
+                to: self.to.clone(),
+            });
+        }
+        match bound(&text)? {
+            Bound::Minimum => Some(ChapaError::SwapAmountTooLow {
+                amount: self.amount,
+                minimum: last_number(&text).unwrap_or(Self::MIN_AMOUNT),
+            }),
+            Bound::Maximum => last_number(&text).map(|maximum| ChapaError::SwapAmountTooHigh {
+                amount: self.amount,
+                maximum,
+            }),
+        }
+    }
+}
+
+/// The kind of limit an amount refusal states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Minimum,
+    Maximum,
+}
+
+/// The limit stated by the lowercase refusal message `text`, if any.
+///
+/// A comparator alone doesn't tell: `"must be less than 10000"` states a maximum while
+/// `"cannot be less than 1"` states a minimum, so a comparator counts as the limit itself when
+/// it is required (`must be`, `should be`) and as the violation otherwise.
+fn bound(text: &str) -> Option<Bound> {
+    if ["maximum", "at most", "exceed"]
+        .iter()
+        .any(|needle| text.contains(needle))
+    {
+        return Some(Bound::Maximum);
+    }
+    if ["minimum", "at least"]
+        .iter()
+        .any(|needle| text.contains(needle))
+    {
+        return Some(Bound::Minimum);
+    }
+    let required = |comparator: &str| {
+        ["must be ", "should be "]
+            .iter()
+            .any(|requirement| text.contains(&format!("{}{}", requirement, comparator)))
+    };
+    if text.contains("less than") {
+        return Some(if required("less than") {
+            Bound::Maximum
+        } else {
+            Bound::Minimum
+        });
+    }
+    ["greater than", "more than"]
+        .into_iter()
+        .find(|comparator| text.contains(comparator))
+        .map(|comparator| {
+            if required(comparator) {
+                Bound::Minimum
+            } else {
+                Bound::Maximum
+            }
+        })
+}
+
+/// The last number of `text`, e.g. `10000` in `"must not be greater than 10,000."`.
+fn last_number(text: &str) -> Option<f64> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            word.trim_matches(|c: char| !c.is_ascii_digit())
+                .replace(',', "")
+                .parse()
+                .ok()
+        })
+        .next_back()
+}
+
+crate::chapa_model! {
+    /// Represents the data received after a swap.
+    pub struct SwapData {
+        /// The currency swapped from.
+        pub from: String,
+        /// The currency swapped to.
+        pub to: String,
+        /// The amount swapped, in the `from` currency.
+        pub amount: f64,
+        /// The exchange rate applied, in `to` per `from`.
+        pub rate: f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_refusal() {
+        let options = SwapOptions {
+            amount: 25000.0,
+            from: "USD".to_string(),
+            to: "ETB".to_string(),
+        };
+        let text = |message: &str| Message::Text(message.to_string());
+
+        assert!(matches!(
+            options.refusal(&text("The amount must not be greater than 10,000.")),
+            Some(ChapaError::SwapAmountTooHigh { amount, maximum }) if amount == 25000.0 && maximum == 10000.0
+        ));
+        assert!(matches!(
+            options.refusal(&text("Amount must be at least 1")),
+            Some(ChapaError::SwapAmountTooLow { minimum, .. }) if minimum == 1.0
+        ));
+        // the comparator alone doesn't tell a minimum from a maximum.
+        assert!(matches!(
+            options.refusal(&text("The amount must be less than 10000")),
+            Some(ChapaError::SwapAmountTooHigh { maximum, .. }) if maximum == 10000.0
+        ));
+        assert!(matches!(
+            options.refusal(&text("The amount cannot be less than 1")),
+            Some(ChapaError::SwapAmountTooLow { minimum, .. }) if minimum == 1.0
+        ));
+        assert!(matches!(
+            options.refusal(&text("The amount must be greater than 1")),
+            Some(ChapaError::SwapAmountTooLow { minimum, .. }) if minimum == 1.0
+        ));
+        assert!(matches!(
+            options.refusal(&text("Currency pair not supported")),
+            Some(ChapaError::UnsupportedSwapPair { from, to }) if from == "USD" && to == "ETB"
+        ));
+        let field_errors: Message =
+            serde_json::from_str(r#"{"to": ["The selected to is invalid."]}"#).unwrap();
+        assert!(matches!(
+            options.refusal(&field_errors),
+            Some(ChapaError::UnsupportedSwapPair { .. })
+        ));
+        assert!(options.refusal(&text("Insufficient Balance")).is_none());
+
+        let too_low = SwapOptions {
+            amount: 0.5,
+            ..options
+        };
+        assert!(matches!(
+            too_low.validate(),
+            Err(ChapaError::SwapAmountTooLow { amount, minimum }) if amount == 0.5 && minimum == 1.0
+        ));
+    }
+}
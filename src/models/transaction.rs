@@ -1,7 +1,13 @@
 //! Models related to get_transactions API responses.
+//!
+//! List pages can hold hundreds of transactions, so the per-transaction text fields are
+//! `Box<str>` rather than `String`: they are never mutated, and dropping the capacity word
+//! keeps each transaction smaller.
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::models::response::RawResponse;
+
 /// Represents the response from Chapa when fetching all transactions.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetTransactionsResponse {
@@ -13,6 +19,8 @@ pub struct GetTransactionsResponse {
     pub data: GetTransactionsData,
 }
 
+impl RawResponse for GetTransactionsResponse {}
+
 /// Represents the data section of the GetTransactionsResponse.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetTransactionsData {
@@ -23,43 +31,129 @@ pub struct GetTransactionsData {
 }
 
 /// Represents a customer associated with a transaction.
+///
+/// Every detail is optional: Chapa returns `null` for customers who paid without providing them.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Customer {
     /// The unique identifier of the customer.
     pub id: u32,
     /// The first name of the customer.
-    pub first_name: String,
+    pub first_name: Option<Box<str>>,
     /// The last name of the customer.
-    pub last_name: String,
+    pub last_name: Option<Box<str>>,
     /// The email address of the customer.
-    pub email: String,
+    pub email: Option<Box<str>>,
     /// The mobile number of the customer.
-    pub mobile: String,
+    pub mobile: Option<Box<str>>,
 }
 
 /// Represents a transaction in Chapa.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Transaction {
     /// The status of the transaction.
-    pub status: String,
+    pub status: Box<str>,
     /// The reference ID of the transaction.
-    pub ref_id: String,
+    #[serde(alias = "reference", alias = "chapa_reference")]
+    pub ref_id: Box<str>,
     /// The type of the transaction. eg. "Payment Link"
-    pub r#type: String,
+    pub r#type: Box<str>,
     /// The date and time when the transaction was created.
     pub created_at: DateTime<Utc>,
     /// The currency in which the transaction was made.
-    pub currency: String,
+    pub currency: Box<str>,
     /// The amount of money that is involved in the transaction.
-    pub amount: String,
+    pub amount: Box<str>,
     /// The charge applied to the transaction.
-    pub charge: String,
+    pub charge: Box<str>,
     /// The unique identifier of the transaction.
-    pub trans_id: String,
+    pub trans_id: Option<Box<str>>,
     /// The payment method used for the transaction.
-    pub payment_method: String,
+    pub payment_method: Box<str>,
     /// The customer associated with the transaction.
     pub customer: Customer,
+    /// The merchant's transaction reference, when the API includes it.
+    #[serde(default, alias = "trx_ref")]
+    pub tx_ref: Option<Box<str>>,
+}
+
+impl Transaction {
+    /// The Chapa reference of the transaction, whichever of `ref_id`, `reference` or
+    /// `chapa_reference` the API used.
+    pub fn chapa_reference(&self) -> &str {
+        &self.ref_id
+    }
+}
+
+/// An entry of the logs of a payment, e.g. an attempt to pay with a given method.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionLog {
+    /// The position of the entry in the logs.
+    pub item: Option<u64>,
+    /// What happened, e.g. "Attempted to pay with telebirr".
+    pub message: String,
+    /// The kind of the entry (e.g., "log", "error").
+    pub r#type: Option<String>,
+    /// When the entry was logged.
+    pub created_at: DateTime<Utc>,
+    /// When the entry was last updated.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Criteria for searching transactions, see
+/// [`ChapaClient::search_transactions`](crate::client::ChapaClient::search_transactions).
+///
+/// Every criterion set must match. Emails are compared case-insensitively, references exactly.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionSearch {
+    /// The email address of the customer.
+    pub email: Option<String>,
+    /// The Chapa reference of the transaction.
+    pub ref_id: Option<String>,
+    /// The merchant's transaction reference.
+    pub tx_ref: Option<String>,
+}
+
+impl TransactionSearch {
+    /// Creates a search matching every transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches transactions of the customer with this email address.
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Only matches the transaction with this Chapa reference.
+    pub fn ref_id(mut self, ref_id: impl Into<String>) -> Self {
+        self.ref_id = Some(ref_id.into());
+        self
+    }
+
+    /// Only matches the transaction with this merchant reference.
+    pub fn tx_ref(mut self, tx_ref: impl Into<String>) -> Self {
+        self.tx_ref = Some(tx_ref.into());
+        self
+    }
+
+    /// Returns `true` if `transaction` matches every criterion set.
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        self.email.as_deref().is_none_or(|email| {
+            transaction
+                .customer
+                .email
+                .as_deref()
+                .is_some_and(|customer| customer.eq_ignore_ascii_case(email))
+        }) && self
+            .ref_id
+            .as_deref()
+            .is_none_or(|ref_id| &*transaction.ref_id == ref_id)
+            && self
+                .tx_ref
+                .as_deref()
+                .is_none_or(|tx_ref| transaction.tx_ref.as_deref() == Some(tx_ref))
+    }
 }
 
 /// Represents pagination details for a list of transactions.
@@ -70,9 +164,9 @@ pub struct Pagination {
     /// Page number of the current set of transactions.
     pub current_page: u32,
     /// URL to the first page of transactions.
-    pub first_page_url: String,
+    pub first_page_url: Box<str>,
     /// URL to the next page of transactions.
-    pub next_page_url: Option<String>,
+    pub next_page_url: Option<Box<str>>,
     /// URL to the previous page of transactions.
-    pub prev_page_url: Option<String>,
+    pub prev_page_url: Option<Box<str>>,
 }
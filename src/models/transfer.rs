@@ -1,6 +1,15 @@
 //! Models related to bank transfers.
 
+use std::fmt;
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{ChapaError, Result},
+    models::{response::RawResponse, transaction::Pagination},
+};
 
 /// Represents the options required to initiate a bank transfer.
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +26,190 @@ pub struct TransferOptions {
     pub reference: String,
     /// The bank code of the recipient's bank.
     pub bank_code: u32,
+    /// A description of the transfer, shown to the recipient where the bank supports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub narration: Option<String>,
+}
+
+impl TransferOptions {
+    /// Creates a transfer of `amount` ETB to a bank account. Set the account name and reference
+    /// with [`TransferOptions::account_name`] and [`TransferOptions::reference`]: transfers
+    /// without them are refused before being sent, see [`TransferOptions::check_details`].
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidBeneficiary`] if the account number is not made of digits,
+    /// or if `bank_code` is the code of a mobile money wallet (use [`TransferOptions::to_wallet`]).
+    pub fn to_bank(
+        bank_code: u32,
+        account_number: impl Into<String>,
+        amount: impl Into<String>,
+    ) -> Result<Self> {
+        let account_number = account_number.into();
+        if Wallet::ALL
+            .iter()
+            .any(|wallet| wallet.bank_code() == bank_code)
+        {
+            return Err(ChapaError::InvalidBeneficiary(format!(
+                "bank code {} is a mobile money wallet, use TransferOptions::to_wallet",
+                bank_code
+            )));
+        }
+        if account_number.is_empty() || !account_number.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ChapaError::InvalidBeneficiary(format!(
+                "{} is not a bank account number",
+                account_number
+            )));
+        }
+        Ok(Self::new(bank_code, account_number, amount.into()))
+    }
+
+    /// Creates a transfer of `amount` ETB to a mobile money wallet, using the wallet's bank code.
+    /// The phone number may be given in local (`09...`) or international (`+2519...`) format.
+    /// Set the account name and reference with [`TransferOptions::account_name`] and
+    /// [`TransferOptions::reference`].
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidBeneficiary`] if the phone number is not valid for the wallet.
+    pub fn to_wallet(
+        wallet: Wallet,
+        phone: impl AsRef<str>,
+        amount: impl Into<String>,
+    ) -> Result<Self> {
+        let phone = wallet.normalize_phone(phone.as_ref())?;
+        Ok(Self::new(wallet.bank_code(), phone, amount.into()))
+    }
+
+    fn new(bank_code: u32, account_number: String, amount: String) -> Self {
+        Self {
+            account_name: String::new(),
+            account_number,
+            amount,
+            currency: "ETB".to_string(),
+            reference: String::new(),
+            bank_code,
+            narration: None,
+        }
+    }
+
+    /// Checks that the account name and reference were set, which the constructors leave empty.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidBeneficiary`] if the account name is empty, or
+    /// [`ChapaError::MissingReference`] if the reference is.
+    pub fn check_details(&self) -> Result<()> {
+        check_details(&self.account_name, &self.account_number, &self.reference)
+    }
+
+    /// Sets the name of the account holder.
+    pub fn account_name(mut self, account_name: impl Into<String>) -> Self {
+        self.account_name = account_name.into();
+        self
+    }
+
+    /// Sets the unique reference of the transfer.
+    pub fn reference(mut self, reference: impl Into<String>) -> Self {
+        self.reference = reference.into();
+        self
+    }
+
+    /// Derives the reference from `seed` when none was set, e.g. from a payroll run id and a row
+    /// number, so resubmitting the same row after a failure reuses the same reference instead of
+    /// paying twice. See [`seeded_reference`].
+    pub fn reference_from_seed(mut self, seed: impl AsRef<str>) -> Self {
+        if self.reference.is_empty() {
+            self.reference = seeded_reference(seed);
+        }
+        self
+    }
+
+    /// Sets the currency of the transfer. default to "ETB".
+    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+        self.currency = currency.into();
+        self
+    }
+
+    /// Sets the description of the transfer.
+    pub fn narration(mut self, narration: impl Into<String>) -> Self {
+        self.narration = Some(narration.into());
+        self
+    }
+}
+
+/// Derives a stable transfer reference from `seed`: the same seed always gives the same
+/// reference, e.g. `seeded_reference("payroll-2024-05:17")`.
+///
+/// The reference is `TRF-` followed by 24 hex characters of the SHA-256 of the seed, so it
+/// doesn't leak the seed and fits the reference length accepted by Chapa.
+pub fn seeded_reference(seed: impl AsRef<str>) -> String {
+    let digest = Sha256::digest(seed.as_ref().as_bytes());
+    let hex: String = digest[..12]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("TRF-{}", hex)
+}
+
+/// A mobile money wallet that can receive transfers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wallet {
+    /// Ethio Telecom's telebirr.
+    Telebirr,
+    /// Commercial Bank of Ethiopia's CBE Birr.
+    Cbebirr,
+    /// Safaricom M-Pesa.
+    Mpesa,
+}
+
+impl Wallet {
+    /// Every wallet supported for transfers.
+    pub const ALL: [Wallet; 3] = [Wallet::Telebirr, Wallet::Cbebirr, Wallet::Mpesa];
+
+    /// The bank code Chapa uses for the wallet in transfers.
+    pub fn bank_code(&self) -> u32 {
+        match self {
+            Wallet::Telebirr => 855,
+            Wallet::Cbebirr => 128,
+            Wallet::Mpesa => 266,
+        }
+    }
+
+    /// The local prefixes of the phone numbers the wallet accepts.
+    fn prefixes(&self) -> &'static [&'static str] {
+        match self {
+            Wallet::Telebirr => &["09"],
+            Wallet::Cbebirr => &["09", "07"],
+            Wallet::Mpesa => &["07"],
+        }
+    }
+
+    /// Converts `phone` to the local 10 digit format (`09...`/`07...`) expected by Chapa.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidBeneficiary`] if the phone number is not valid for the wallet.
+    pub fn normalize_phone(&self, phone: &str) -> Result<String> {
+        let digits: String = phone
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .collect();
+        let digits = digits.trim_start_matches('+');
+        let local = match digits.strip_prefix("251") {
+            Some(rest) => format!("0{}", rest),
+            None => digits.to_string(),
+        };
+        let valid = local.len() == 10
+            && local.bytes().all(|b| b.is_ascii_digit())
+            && self
+                .prefixes()
+                .iter()
+                .any(|prefix| local.starts_with(prefix));
+        if !valid {
+            return Err(ChapaError::InvalidBeneficiary(format!(
+                "{} is not a valid {:?} phone number",
+                phone, self
+            )));
+        }
+        Ok(local)
+    }
 }
 
 /// Represents the response received after initiating a bank transfer.
@@ -26,6 +219,512 @@ pub struct TransferResponse {
     pub message: String,
     /// The status of the transfer (e.g., "pending", "completed").
     pub status: String,
-    /// Additional data related to the transfer.
-    pub data: String,
+    /// The reference of the queued transfer, absent when the transfer was refused.
+    pub data: Option<String>,
+}
+
+impl RawResponse for TransferResponse {}
+
+impl fmt::Display for TransferResponse {
+    /// A one-line summary, e.g. `success: transfer payout-1 (Transfer Queued Successfully)`, or
+    /// the status and message of a refused transfer.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.data {
+            Some(reference) => write!(
+                f,
+                "{}: transfer {} ({})",
+                self.status, reference, self.message
+            ),
+            None => write!(f, "{}: {}", self.status, self.message),
+        }
+    }
+}
+
+/// Checks the account name and reference of a transfer to `account_number`.
+fn check_details(account_name: &str, account_number: &str, reference: &str) -> Result<()> {
+    if account_name.trim().is_empty() {
+        return Err(ChapaError::InvalidBeneficiary(format!(
+            "the account name of {} is empty",
+            account_number
+        )));
+    }
+    if reference.trim().is_empty() {
+        return Err(ChapaError::MissingReference(format!(
+            "the transfer to {} has no reference",
+            account_number
+        )));
+    }
+    Ok(())
+}
+
+/// A single transfer inside a bulk transfer batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkData {
+    /// The name of the account holder.
+    pub account_name: String,
+    /// The bank account number to which the transfer will be made.
+    pub account_number: String,
+    /// The amount to be transferred.
+    pub amount: String,
+    /// A unique reference for the transfer.
+    pub reference: String,
+    /// The bank code of the recipient's bank.
+    pub bank_code: u32,
+    /// A description of the transfer, shown to the recipient where the bank supports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub narration: Option<String>,
+}
+
+/// Represents the options required to initiate a bulk transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkTransferOptions {
+    /// The title of the bulk transfer batch.
+    pub title: String,
+    /// The currency in which the transfers will be made.
+    pub currency: String,
+    /// The individual transfers of the batch.
+    pub bulk_data: Vec<BulkData>,
+}
+
+impl BulkTransferOptions {
+    /// Checks that every transfer of the batch has an account name and a reference, see
+    /// [`TransferOptions::check_details`].
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidBeneficiary`] or [`ChapaError::MissingReference`] for the
+    /// first transfer missing one.
+    pub fn check_details(&self) -> Result<()> {
+        self.bulk_data.iter().try_for_each(|transfer| {
+            check_details(
+                &transfer.account_name,
+                &transfer.account_number,
+                &transfer.reference,
+            )
+        })
+    }
+
+    /// Returns the references of every transfer in the batch.
+    pub fn references(&self) -> Vec<&str> {
+        self.bulk_data
+            .iter()
+            .map(|transfer| transfer.reference.as_str())
+            .collect()
+    }
+
+    /// Splits the batch into batches of at most `size` transfers (Chapa accepts up to 100 per batch).
+    /// The titles of the resulting batches are suffixed with their position, e.g. `"Salary (2/3)"`.
+    ///
+    /// # Panics
+    /// Panics if `size` is zero.
+    pub fn chunks(&self, size: usize) -> Vec<BulkTransferOptions> {
+        let total = self.bulk_data.len().div_ceil(size);
+        self.bulk_data
+            .chunks(size)
+            .enumerate()
+            .map(|(index, chunk)| BulkTransferOptions {
+                title: if total > 1 {
+                    format!("{} ({}/{})", self.title, index + 1, total)
+                } else {
+                    self.title.clone()
+                },
+                currency: self.currency.clone(),
+                bulk_data: chunk.to_vec(),
+            })
+            .collect()
+    }
+}
+
+/// Represents the data returned after queuing a bulk transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkTransferData {
+    /// The identifier of the queued batch.
+    pub id: u64,
+    /// The creation timestamp of the batch.
+    pub created_at: Option<String>,
+}
+
+/// How a transfer is delivered to the recipient.
+///
+/// Chapa reports it as `transfer_type` on listed transfers and `transfer_method` on verified ones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum TransferType {
+    /// A transfer to a bank account.
+    Bank,
+    /// A transfer to a mobile money wallet (telebirr, M-Pesa, ...).
+    Wallet,
+    /// A transfer type not known to the SDK, kept verbatim.
+    Other(String),
+}
+
+impl TransferType {
+    /// The identifier Chapa uses for the transfer type, e.g. `"bank"`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            TransferType::Bank => "bank",
+            TransferType::Wallet => "wallet",
+            TransferType::Other(other) => other,
+        }
+    }
+
+    /// Returns `true` for transfers to a bank account.
+    pub fn is_bank(&self) -> bool {
+        matches!(self, TransferType::Bank)
+    }
+
+    /// Returns `true` for transfers to a mobile money wallet.
+    pub fn is_wallet(&self) -> bool {
+        matches!(self, TransferType::Wallet)
+    }
+}
+
+impl From<String> for TransferType {
+    fn from(value: String) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "bank" => TransferType::Bank,
+            "wallet" => TransferType::Wallet,
+            _ => TransferType::Other(value),
+        }
+    }
+}
+
+impl From<TransferType> for String {
+    fn from(value: TransferType) -> Self {
+        match value {
+            TransferType::Other(other) => other,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+/// Represents the details of a transfer, as returned by the `/transfers/verify/{reference}` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyTransferData {
+    /// The name of the account holder.
+    pub account_name: Option<String>,
+    /// The bank account number or wallet the transfer was made to.
+    pub account_number: Option<String>,
+    /// The mobile number of the recipient, for wallet transfers.
+    pub mobile: Option<String>,
+    /// The currency of the transfer.
+    pub currency: Option<String>,
+    /// The amount transferred.
+    pub amount: Option<f64>,
+    /// The fee charged by Chapa for the transfer.
+    pub charge: Option<f64>,
+    /// The mode of the transfer ("live" or "test").
+    pub mode: Option<String>,
+    /// How the transfer is delivered to the recipient.
+    pub transfer_method: Option<TransferType>,
+    /// The narration of the transfer.
+    pub narration: Option<String>,
+    /// The Chapa identifier of the transfer.
+    pub chapa_transfer_id: Option<String>,
+    /// The reference of the transfer at the recipient's bank, once it was paid out.
+    pub bank_reference: Option<String>,
+    /// The bank code of the recipient's bank.
+    pub bank_code: Option<u32>,
+    /// The name of the recipient's bank.
+    pub bank_name: Option<String>,
+    /// The status of the transfer (e.g., "pending", "success").
+    pub status: Option<String>,
+    /// The merchant's reference of the transfer.
+    pub tx_ref: Option<String>,
+    /// The date and time when the transfer was created.
+    pub created_at: Option<DateTime<Utc>>,
+    /// The date and time when the transfer was last updated.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Represents the response from Chapa when fetching all transfers.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetTransfersResponse {
+    /// The status message of the response.
+    pub message: String,
+    /// The status of the response.
+    pub status: String,
+    /// The transfers of the requested page.
+    pub data: Vec<Transfer>,
+    /// The pagination information.
+    pub meta: Option<Pagination>,
+}
+
+impl RawResponse for GetTransfersResponse {}
+
+/// Represents a transfer in Chapa, as listed by the `/transfers` endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transfer {
+    /// The name of the account holder.
+    pub account_name: Option<Box<str>>,
+    /// The bank account number the transfer was made to.
+    pub account_number: Option<Box<str>>,
+    /// The currency of the transfer.
+    pub currency: Option<Box<str>>,
+    /// The amount transferred.
+    pub amount: Option<f64>,
+    /// The fee charged by Chapa for the transfer.
+    pub charge: Option<f64>,
+    /// How the transfer is delivered to the recipient.
+    pub transfer_type: Option<TransferType>,
+    /// The Chapa reference of the transfer.
+    pub chapa_reference: Option<Box<str>>,
+    /// The reference of the transfer at the recipient's bank, once it was paid out.
+    pub bank_reference: Option<Box<str>>,
+    /// The bank code of the recipient's bank.
+    pub bank_code: Option<u32>,
+    /// The name of the recipient's bank.
+    pub bank_name: Option<Box<str>>,
+    /// The status of the transfer (e.g., "pending", "success").
+    pub status: Option<Box<str>>,
+    /// The merchant's reference of the transfer.
+    pub reference: Option<Box<str>>,
+    /// The date and time when the transfer was created.
+    pub created_at: Option<DateTime<Utc>>,
+    /// The date and time when the transfer was last updated.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl VerifyTransferData {
+    /// The outcome of the transfer, see [`TransferOutcome::parse`].
+    pub fn outcome(&self) -> TransferOutcome {
+        TransferOutcome::parse(
+            self.status.as_deref().unwrap_or_default(),
+            self.bank_reference.as_deref(),
+        )
+    }
+}
+
+impl Transfer {
+    /// The outcome of the transfer, see [`TransferOutcome::parse`].
+    pub fn outcome(&self) -> TransferOutcome {
+        TransferOutcome::parse(
+            self.status.as_deref().unwrap_or_default(),
+            self.bank_reference.as_deref(),
+        )
+    }
+}
+
+/// The outcome of a transfer, parsed from its status and bank reference.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum TransferOutcome {
+    /// The transfer was paid out.
+    Success {
+        /// The reference of the transfer at the recipient's bank, if Chapa reported one.
+        bank_reference: Option<String>,
+    },
+    /// The transfer failed or was reverted.
+    Failed {
+        /// Why the transfer failed, as far as its status tells.
+        reason: String,
+    },
+    /// The transfer was cancelled before being paid out.
+    Cancelled,
+    /// The transfer has not reached a final state yet.
+    Pending,
+    /// A status not known to the SDK (or a missing one), kept verbatim. It is not final: check
+    /// the transfer again, or have someone look at it.
+    Unknown {
+        /// The status as reported by Chapa.
+        status: String,
+    },
+}
+
+impl TransferOutcome {
+    /// Parses a transfer status case-insensitively, including compound statuses such as
+    /// `"failed/cancelled"` (a cancellation wins over a failure, which wins over a success).
+    ///
+    /// A failed status keeps its other parts (or the status itself) as the reason. Empty
+    /// references count as missing. Only `"pending"`, `"processing"` and `"queued"` are
+    /// [`TransferOutcome::Pending`]; any other status is [`TransferOutcome::Unknown`] rather than
+    /// assumed to be in flight.
+    ///
+    /// # Example
+    /// ```rust
+    /// use chapa_rust::models::transfer::TransferOutcome;
+    ///
+    /// assert_eq!(TransferOutcome::parse("failed/cancelled", None), TransferOutcome::Cancelled);
+    /// assert_eq!(
+    ///     TransferOutcome::parse("failed/reverted", None),
+    ///     TransferOutcome::Failed { reason: "reverted".to_string() }
+    /// );
+    /// ```
+    pub fn parse(status: &str, bank_reference: Option<&str>) -> Self {
+        let verbatim = status;
+        let status = status.trim().to_ascii_lowercase();
+        let parts: Vec<&str> = status
+            .split('/')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect();
+        let has = |words: &[&str]| parts.iter().any(|part| words.contains(part));
+        if has(&["cancelled", "canceled"]) {
+            return TransferOutcome::Cancelled;
+        }
+        let failures = ["failed", "failure", "reverted", "reversed"];
+        if has(&failures) {
+            let details: Vec<&str> = parts
+                .iter()
+                .copied()
+                .filter(|part| !matches!(*part, "failed" | "failure"))
+                .collect();
+            let reason = if details.is_empty() {
+                status.clone()
+            } else {
+                details.join("/")
+            };
+            return TransferOutcome::Failed { reason };
+        }
+        if has(&["success", "successful", "completed"]) {
+            return TransferOutcome::Success {
+                bank_reference: bank_reference
+                    .map(str::trim)
+                    .filter(|reference| !reference.is_empty())
+                    .map(str::to_string),
+            };
+        }
+        if has(&["pending", "processing", "queued"]) {
+            return TransferOutcome::Pending;
+        }
+        TransferOutcome::Unknown {
+            status: verbatim.to_string(),
+        }
+    }
+
+    /// Returns `true` once the transfer reached a final state.
+    pub fn is_final(&self) -> bool {
+        !matches!(
+            self,
+            TransferOutcome::Pending | TransferOutcome::Unknown { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_summary() {
+        let queued: TransferResponse = serde_json::from_str(
+            r#"{"message":"Transfer Queued Successfully","status":"success","data":"payout-1"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            queued.to_string(),
+            "success: transfer payout-1 (Transfer Queued Successfully)"
+        );
+        let refused: TransferResponse = serde_json::from_str(
+            r#"{"message":"Insufficient Balance","status":"failed","data":null}"#,
+        )
+        .unwrap();
+        assert_eq!(refused.to_string(), "failed: Insufficient Balance");
+    }
+
+    #[test]
+    fn test_transfer_outcome() {
+        assert_eq!(
+            TransferOutcome::parse("Success", Some("FT24122Q3QNS")),
+            TransferOutcome::Success {
+                bank_reference: Some("FT24122Q3QNS".to_string())
+            }
+        );
+        assert_eq!(
+            TransferOutcome::parse("success", Some(" ")),
+            TransferOutcome::Success {
+                bank_reference: None
+            }
+        );
+        assert_eq!(
+            TransferOutcome::parse("failed/cancelled", None),
+            TransferOutcome::Cancelled
+        );
+        assert_eq!(
+            TransferOutcome::parse("failed", None),
+            TransferOutcome::Failed {
+                reason: "failed".to_string()
+            }
+        );
+        assert_eq!(
+            TransferOutcome::parse("queued", None),
+            TransferOutcome::Pending
+        );
+        assert_eq!(
+            TransferOutcome::parse("On Hold", None),
+            TransferOutcome::Unknown {
+                status: "On Hold".to_string()
+            }
+        );
+        assert!(!TransferOutcome::parse("On Hold", None).is_final());
+        assert!(!TransferOutcome::parse("", None).is_final());
+
+        let transfer: Transfer = serde_json::from_value(serde_json::json!({
+            "status": "success/completed",
+            "bank_reference": "FT24122Q3QNS"
+        }))
+        .unwrap();
+        assert!(matches!(
+            transfer.outcome(),
+            TransferOutcome::Success {
+                bank_reference: Some(_)
+            }
+        ));
+    }
+
+    #[test]
+    fn test_reference_from_seed() {
+        let transfer = |reference: &str| {
+            TransferOptions::to_bank(656, "1000123456789", "100")
+                .unwrap()
+                .reference(reference)
+                .reference_from_seed("payroll-2024-05:17")
+        };
+        let seeded = transfer("").reference;
+        assert_eq!(seeded, transfer("").reference);
+        assert_eq!(seeded.len(), 28);
+        assert!(seeded.starts_with("TRF-"));
+        assert_ne!(seeded, seeded_reference("payroll-2024-05:18"));
+        assert_eq!(transfer("salary-17").reference, "salary-17");
+    }
+
+    #[test]
+    fn test_transfer_type_round_trip() {
+        let types: Vec<TransferType> =
+            serde_json::from_str(r#"["bank", "Wallet", "swift"]"#).unwrap();
+        assert_eq!(
+            types,
+            vec![
+                TransferType::Bank,
+                TransferType::Wallet,
+                TransferType::Other("swift".to_string())
+            ]
+        );
+        assert!(types[1].is_wallet() && !types[1].is_bank());
+        assert_eq!(
+            serde_json::to_string(&types).unwrap(),
+            r#"["bank","wallet","swift"]"#
+        );
+    }
+
+    #[test]
+    fn test_transfer_constructors() {
+        let wallet = TransferOptions::to_wallet(Wallet::Telebirr, "+251 912 345 678", "250")
+            .unwrap()
+            .account_name("Abebe Bikila")
+            .reference("payout-1");
+        assert_eq!(wallet.bank_code, 855);
+        assert_eq!(wallet.account_number, "0912345678");
+        assert_eq!(wallet.currency, "ETB");
+
+        assert!(TransferOptions::to_wallet(Wallet::Telebirr, "0712345678", "250").is_err());
+        assert!(TransferOptions::to_wallet(Wallet::Mpesa, "0712345678", "250").is_ok());
+        assert!(TransferOptions::to_bank(855, "0912345678", "250").is_err());
+        assert!(TransferOptions::to_bank(130, "1000 2000", "250").is_err());
+        assert_eq!(
+            TransferOptions::to_bank(130, "1000200030004000", "250")
+                .unwrap()
+                .bank_code,
+            130
+        );
+    }
 }
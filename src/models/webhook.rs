@@ -0,0 +1,278 @@
+//! Models of the webhook events Chapa delivers to the merchant's callback URL.
+//!
+//! Chapa posts a flat JSON object whose `event` field names the event type, e.g.
+//! `charge.success` for a payment or `payout.success` for a transfer.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::models::{
+    normalized::{Payment, PaymentCustomer, PaymentSource, PaymentStatus},
+    transfer::TransferOutcome,
+};
+
+/// A webhook event, typed by its `event` field.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum WebhookEvent {
+    /// A payment event (`charge.*`).
+    Charge(ChargeEvent),
+    /// A transfer event (`payout.*`).
+    Payout(PayoutEvent),
+    /// An event type not known to the SDK, kept verbatim.
+    Other(Value),
+}
+
+impl WebhookEvent {
+    /// The event type, e.g. `charge.success`, or `unknown` if the body has none.
+    pub fn event_type(&self) -> &str {
+        match self {
+            WebhookEvent::Charge(charge) => &charge.event,
+            WebhookEvent::Payout(payout) => &payout.event,
+            WebhookEvent::Other(value) => value
+                .get("event")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown"),
+        }
+    }
+
+    /// The merchant's transaction reference, for payment events.
+    pub fn tx_ref(&self) -> Option<&str> {
+        match self {
+            WebhookEvent::Charge(charge) => Some(&charge.tx_ref),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WebhookEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let event = value.get("event").and_then(Value::as_str).unwrap_or("");
+        if event.starts_with("charge.") {
+            ChargeEvent::deserialize(value)
+                .map(WebhookEvent::Charge)
+                .map_err(serde::de::Error::custom)
+        } else if event.starts_with("payout.") {
+            PayoutEvent::deserialize(value)
+                .map(WebhookEvent::Payout)
+                .map_err(serde::de::Error::custom)
+        } else {
+            Ok(WebhookEvent::Other(value))
+        }
+    }
+}
+
+/// The customer of a payment event.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebhookCustomer {
+    /// The first name of the customer.
+    pub first_name: Option<String>,
+    /// The last name of the customer.
+    pub last_name: Option<String>,
+    /// The email address of the customer.
+    pub email: Option<String>,
+    /// The mobile number of the customer.
+    pub mobile: Option<String>,
+}
+
+/// A payment event, e.g. `charge.success`, `charge.failed` or `charge.refunded`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChargeEvent {
+    /// The event type.
+    pub event: String,
+    /// The customer who made the payment.
+    #[serde(flatten)]
+    pub customer: WebhookCustomer,
+    /// The currency of the payment (e.g., "ETB", "USD").
+    pub currency: String,
+    /// The amount of the payment, kept as a decimal string to avoid precision loss.
+    #[serde(deserialize_with = "decimal_string")]
+    pub amount: String,
+    /// The fee charged by Chapa, as a decimal string.
+    #[serde(default, deserialize_with = "optional_decimal_string")]
+    pub charge: Option<String>,
+    /// The status of the payment (e.g., "success", "failed").
+    pub status: String,
+    /// Why the payment failed, for failed payments.
+    pub failure_reason: Option<String>,
+    /// The mode of the payment ("live" or "test").
+    pub mode: Option<String>,
+    /// The Chapa reference of the payment.
+    pub reference: Option<String>,
+    /// The merchant's transaction reference.
+    #[serde(alias = "trx_ref")]
+    pub tx_ref: String,
+    /// The payment method used (e.g., "telebirr", "card").
+    pub payment_method: Option<String>,
+    /// The type of the payment (e.g., "API").
+    pub r#type: Option<String>,
+    /// The customization of the checkout page.
+    pub customization: Option<Value>,
+    /// The metadata passed when the payment was initialized.
+    pub meta: Option<Value>,
+    /// When the payment was created.
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the payment was last updated.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A transfer event, e.g. `payout.success` or `payout.failed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutEvent {
+    /// The event type.
+    pub event: String,
+    /// The name of the account holder.
+    pub account_name: Option<String>,
+    /// The bank account number or wallet the transfer was made to.
+    pub account_number: Option<String>,
+    /// The bank code of the recipient's bank.
+    #[serde(alias = "bank_code")]
+    pub bank_id: Option<u32>,
+    /// The name of the recipient's bank.
+    pub bank_name: Option<String>,
+    /// The currency of the transfer.
+    pub currency: String,
+    /// The amount transferred, kept as a decimal string to avoid precision loss.
+    #[serde(deserialize_with = "decimal_string")]
+    pub amount: String,
+    /// The fee charged by Chapa, as a decimal string.
+    #[serde(default, deserialize_with = "optional_decimal_string")]
+    pub charge: Option<String>,
+    /// The status of the transfer (e.g., "success", "failed").
+    pub status: String,
+    /// The merchant's reference of the transfer.
+    pub reference: String,
+    /// The Chapa reference of the transfer.
+    pub chapa_reference: Option<String>,
+    /// The reference of the transfer at the recipient's bank.
+    pub bank_reference: Option<String>,
+    /// When the transfer was created.
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the transfer was last updated.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl PayoutEvent {
+    /// The outcome of the transfer, see [`TransferOutcome::parse`].
+    pub fn outcome(&self) -> TransferOutcome {
+        TransferOutcome::parse(&self.status, self.bank_reference.as_deref())
+    }
+}
+
+impl From<ChargeEvent> for Payment {
+    fn from(event: ChargeEvent) -> Self {
+        Payment {
+            id: event.reference,
+            tx_ref: Some(event.tx_ref),
+            amount: event.amount,
+            currency: Some(event.currency),
+            status: PaymentStatus::parse(&event.status),
+            method: event.payment_method,
+            customer: PaymentCustomer {
+                first_name: event.customer.first_name,
+                last_name: event.customer.last_name,
+                email: event.customer.email,
+                mobile: event.customer.mobile,
+            },
+            created_at: event.created_at,
+            source: PaymentSource::Webhook,
+        }
+    }
+}
+
+/// Accepts an amount sent either as a string or as a number.
+fn decimal_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(amount) => Ok(amount),
+        Value::Number(amount) => Ok(amount.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected an amount, found {}",
+            other
+        ))),
+    }
+}
+
+/// Like [`decimal_string`], for amounts that may be missing or `null`.
+fn optional_decimal_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::String(amount) => Ok(Some(amount)),
+        Value::Number(amount) => Ok(Some(amount.to_string())),
+        other => Err(serde::de::Error::custom(format!(
+            "expected an amount, found {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_events() {
+        let charge: WebhookEvent = serde_json::from_value(serde_json::json!({
+            "event": "charge.success",
+            "first_name": "Bilen",
+            "last_name": "Gizachew",
+            "email": "abebech_bekele@gmail.com",
+            "mobile": null,
+            "currency": "ETB",
+            "amount": "100.00",
+            "charge": 3.5,
+            "status": "success",
+            "failure_reason": null,
+            "mode": "test",
+            "reference": "6jnheVKQEmy",
+            "created_at": "2023-02-02T07:05:23.000000Z",
+            "updated_at": "2023-02-02T07:05:23.000000Z",
+            "type": "API",
+            "tx_ref": "chewatatest-6669",
+            "payment_method": "telebirr",
+            "customization": {"title": null, "description": null, "logo": null},
+            "meta": null
+        }))
+        .unwrap();
+        assert_eq!(charge.event_type(), "charge.success");
+        assert_eq!(charge.tx_ref(), Some("chewatatest-6669"));
+        let WebhookEvent::Charge(event) = charge else {
+            panic!("expected a charge event");
+        };
+        assert_eq!(event.charge.as_deref(), Some("3.5"));
+        let payment = Payment::from(event);
+        assert_eq!(payment.source, PaymentSource::Webhook);
+        assert_eq!(payment.status, PaymentStatus::Success);
+        assert_eq!(payment.amount, "100.00");
+        assert_eq!(payment.customer.first_name.as_deref(), Some("Bilen"));
+
+        let payout: WebhookEvent = serde_json::from_value(serde_json::json!({
+            "event": "payout.success",
+            "type": "Payout",
+            "account_name": "Israel Goytom",
+            "account_number": "32423423",
+            "bank_id": 656,
+            "bank_name": "Awash Bank",
+            "amount": "10.00",
+            "charge": "0.00",
+            "currency": "ETB",
+            "status": "success",
+            "reference": "3241342142sfdd",
+            "chapa_reference": "TRFc4cEA9yU2z",
+            "bank_reference": "FT24122Q3QNS",
+            "created_at": "2024-05-01T10:35:02.000000Z",
+            "updated_at": "2024-05-01T10:35:02.000000Z"
+        }))
+        .unwrap();
+        assert!(matches!(&payout, WebhookEvent::Payout(p) if p.bank_id == Some(656)));
+        assert_eq!(payout.tx_ref(), None);
+
+        let other: WebhookEvent =
+            serde_json::from_value(serde_json::json!({"event": "refund.success"})).unwrap();
+        assert_eq!(other.event_type(), "refund.success");
+        assert!(matches!(other, WebhookEvent::Other(_)));
+    }
+}
@@ -0,0 +1,49 @@
+//! # Poll Module
+//!
+//! Settings for the client methods that wait for an asynchronous operation to settle, such as
+//! [`ChapaClient::direct_charge_and_wait`](crate::client::ChapaClient::direct_charge_and_wait).
+//!
+//! ## Example
+//! ```rust
+//! use std::time::Duration;
+//! use chapa_rust::poll::PollOptions;
+//!
+//! let options = PollOptions::default()
+//!     .interval(Duration::from_secs(5))
+//!     .timeout(Duration::from_secs(300));
+//! assert_eq!(options.interval, Duration::from_secs(5));
+//! ```
+use std::time::Duration;
+
+/// Settings controlling how often and how long an operation is polled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollOptions {
+    /// The delay before each poll. default to 3s.
+    pub interval: Duration,
+    /// How long to keep polling before giving up. default to 2 minutes, enough for a customer
+    /// to confirm a USSD push on their phone.
+    pub timeout: Duration,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(3),
+            timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+impl PollOptions {
+    /// Sets the delay before each poll.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets how long to keep polling before giving up.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
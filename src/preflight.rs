@@ -0,0 +1,65 @@
+//! # Preflight Module
+//!
+//! The report of [`ChapaClient::preflight`](crate::client::ChapaClient::preflight), a startup
+//! check meant for service readiness probes: it tells apart an unreachable API (DNS, TLS,
+//! firewall), a rejected secret key and a misconfigured API version, instead of surfacing all of
+//! them as the first failed payment.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # #[cfg(feature = "async")]
+//! # async fn run(client: chapa_rust::client::ChapaClient) {
+//! let report = client.preflight().await;
+//! if !report.is_ready() {
+//!     eprintln!("Chapa is not ready: {report:?}");
+//! }
+//! # }
+//! ```
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// The outcome of a single preflight check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "detail", rename_all = "snake_case")]
+pub enum PreflightCheck {
+    /// The check passed.
+    Passed,
+    /// The check failed, with the reason.
+    Failed(String),
+    /// The check could not run because an earlier one failed.
+    Skipped,
+}
+
+impl PreflightCheck {
+    /// Returns `true` if the check passed.
+    pub fn is_passed(&self) -> bool {
+        matches!(self, PreflightCheck::Passed)
+    }
+}
+
+/// The report of [`ChapaClient::preflight`](crate::client::ChapaClient::preflight).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PreflightReport {
+    /// The base URL that was checked.
+    pub base_url: String,
+    /// The API version that was checked.
+    pub version: String,
+    /// Whether the base URL could be resolved and connected to, TLS handshake included.
+    pub connectivity: PreflightCheck,
+    /// Whether the secret key was accepted.
+    pub credentials: PreflightCheck,
+    /// Whether the configured API version exists.
+    pub api_version: PreflightCheck,
+    /// How long the connectivity check took to get a response.
+    pub latency: Option<Duration>,
+}
+
+impl PreflightReport {
+    /// Returns `true` if every check passed.
+    pub fn is_ready(&self) -> bool {
+        self.connectivity.is_passed()
+            && self.credentials.is_passed()
+            && self.api_version.is_passed()
+    }
+}
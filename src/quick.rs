@@ -0,0 +1,94 @@
+//! # Quick Module
+//!
+//! One-shot functions for scripts and serverless handlers making a single call, where building a
+//! configuration and a client is overkill. Each call builds a throwaway [`ChapaClient`] with the
+//! default configuration for `secret_key` and drops it afterwards, so nothing (connections,
+//! registries, stats) is shared between calls: keep a [`ChapaClient`] around for anything more.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn run() -> chapa_rust::error::Result<()> {
+//! let secret_key = std::env::var("CHAPA_SECRET_KEY").unwrap();
+//! let payment = chapa_rust::quick::verify(&secret_key, "order-1042").await?;
+//! println!("{}", payment);
+//! # Ok(())
+//! # }
+//! ```
+use crate::{
+    client::ChapaClient,
+    error::Result,
+    models::{
+        payment::InitializeOptions,
+        response::{GetBanksResponse, InitializeResponse, VerifyResponse, VerifyTransferResponse},
+        transfer::{TransferOptions, TransferResponse},
+    },
+};
+
+/// Retrieves the list of all banks supported by Chapa, see [`ChapaClient::get_banks`].
+///
+/// # Errors
+/// Returns an error if `secret_key` isn't a Chapa secret key, or the errors of
+/// [`ChapaClient::get_banks`].
+pub async fn get_banks(secret_key: &str) -> Result<GetBanksResponse> {
+    ChapaClient::new(secret_key)?.get_banks().await
+}
+
+/// Initializes a transaction, see [`ChapaClient::initialize_transaction`].
+///
+/// # Errors
+/// Returns an error if `secret_key` isn't a Chapa secret key, or the errors of
+/// [`ChapaClient::initialize_transaction`].
+pub async fn initialize(
+    secret_key: &str,
+    options: InitializeOptions,
+) -> Result<InitializeResponse> {
+    ChapaClient::new(secret_key)?
+        .initialize_transaction(options)
+        .await
+}
+
+/// Verifies the status of a transaction, see [`ChapaClient::verify_transaction`].
+///
+/// # Errors
+/// Returns an error if `secret_key` isn't a Chapa secret key, or the errors of
+/// [`ChapaClient::verify_transaction`].
+pub async fn verify(secret_key: &str, tx_ref: &str) -> Result<VerifyResponse> {
+    ChapaClient::new(secret_key)?
+        .verify_transaction(tx_ref)
+        .await
+}
+
+/// Initiates a transfer, see [`ChapaClient::transfer`].
+///
+/// # Errors
+/// Returns an error if `secret_key` isn't a Chapa secret key, or the errors of
+/// [`ChapaClient::transfer`].
+pub async fn transfer(secret_key: &str, options: TransferOptions) -> Result<TransferResponse> {
+    ChapaClient::new(secret_key)?.transfer(options).await
+}
+
+/// Verifies the status of a transfer, see [`ChapaClient::verify_transfer`].
+///
+/// # Errors
+/// Returns an error if `secret_key` isn't a Chapa secret key, or the errors of
+/// [`ChapaClient::verify_transfer`].
+pub async fn verify_transfer(secret_key: &str, reference: &str) -> Result<VerifyTransferResponse> {
+    ChapaClient::new(secret_key)?
+        .verify_transfer(reference)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ChapaError;
+
+    #[tokio::test]
+    async fn test_quick_rejects_invalid_key() {
+        // the key is checked before anything is sent.
+        assert!(matches!(
+            verify("not-a-chapa-key", "order-1").await,
+            Err(ChapaError::InvalidApiKey(_))
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+//! # Rate Limit Module
+//!
+//! A small client-side rate limiter spacing out requests by a minimum interval. It can be
+//! attached to every request of a client via [`ChapaConfigBuilder::rate_limit`](crate::config::ChapaConfigBuilder::rate_limit)
+//! and is used by the bulk transfer executor to respect Chapa's recommended pause between batches.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::rate_limit::RateLimiter;
+//! use std::time::Duration;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let limiter = RateLimiter::new(Duration::from_millis(10));
+//! limiter.acquire().await; // returns immediately
+//! limiter.acquire().await; // waits ~10ms
+//! # }
+//! ```
+use std::{sync::Arc, time::Duration};
+
+use tokio::{sync::Mutex, time::Instant};
+
+/// Spaces out operations so that two permits are never handed out less than `interval` apart.
+///
+/// Cloning the limiter is cheap and clones share the same schedule.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    interval: Duration,
+    next: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter handing out at most one permit per `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Returns the minimum interval between two permits.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Waits until the next permit is available.
+    pub async fn acquire(&self) {
+        // the lock is held while sleeping so that waiters are served one interval apart, in order.
+        let mut next = self.next.lock().await;
+        if let Some(at) = *next {
+            tokio::time::sleep_until(at).await;
+        }
+        *next = Some(Instant::now() + self.interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_permits_are_spaced() {
+        let limiter = RateLimiter::new(Duration::from_millis(50));
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+        limiter.clone().acquire().await;
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,284 @@
+//! # Registry Module
+//!
+//! Pluggable stores remembering which references were already submitted to Chapa, so the client
+//! can refuse to submit the same reference twice (e.g. when an application retries a payout after
+//! a timeout, or initializes a second checkout for the same `tx_ref`). The [`InMemoryRegistry`]
+//! covers a single process; implement [`ReferenceRegistry`] on top of a shared store (Redis,
+//! a database table, ...) to guard across processes.
+//!
+//! A [`CheckoutStore`] remembers the checkout URL of every initialized transaction, so
+//! [`ChapaClient::checkout`](crate::client::ChapaClient::checkout) can hand out the pending
+//! checkout of an order again instead of initializing a second one.
+//!
+//! A [`BulkProgressStore`] records how far a bulk transfer run got, so
+//! [`ChapaClient::resume`](crate::client::ChapaClient::resume) can continue a crashed payroll
+//! run from its last accepted batch.
+//!
+//! An [`ExportCheckpointStore`] records how far an export job got, so
+//! [`ChapaClient::export`](crate::client::ChapaClient::export) can continue a long export after a
+//! restart.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::{config::ChapaConfig, registry::{InMemoryCheckoutStore, InMemoryRegistry}};
+//!
+//! let config = ChapaConfig::builder()
+//!     .api_key("CHASECK_TEST-xxxxxxxxxxxx")
+//!     .transfer_registry(InMemoryRegistry::new())
+//!     .tx_ref_registry(InMemoryRegistry::new())
+//!     .checkout_store(InMemoryCheckoutStore::new())
+//!     .build()
+//!     .unwrap();
+//! ```
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+
+use crate::{bulk::BulkRun, error::Result, export::ExportCheckpoint};
+
+/// A store of submitted references with check-and-reserve semantics.
+#[async_trait]
+pub trait ReferenceRegistry: Debug + Send + Sync {
+    /// Atomically records `reference` as submitted.
+    ///
+    /// Returns `Ok(true)` if the reference was not known yet, `Ok(false)` if it was already reserved.
+    async fn reserve(&self, reference: &str) -> Result<bool>;
+
+    /// Forgets `reference`, allowing it to be submitted again.
+    async fn release(&self, reference: &str) -> Result<()>;
+}
+
+/// A registry of transaction references (`tx_ref`) consulted by
+/// [`ChapaClient::initialize_transaction`](crate::client::ChapaClient::initialize_transaction) before sending,
+/// to stop "transaction reference has been used before" errors at the source.
+///
+/// It has the same check-and-reserve semantics as [`ReferenceRegistry`], which every tx_ref
+/// registry implements; any [`ReferenceRegistry`] (such as [`InMemoryRegistry`]) can be used as one.
+pub trait TxRefRegistry: ReferenceRegistry {}
+
+impl<T: ReferenceRegistry + ?Sized> TxRefRegistry for T {}
+
+/// A [`ReferenceRegistry`] keeping the references in memory, for the lifetime of the process,
+/// or up to a capacity.
+///
+/// Clones share the same set of references.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryRegistry {
+    references: Arc<Mutex<References>>,
+}
+
+/// The references of an [`InMemoryRegistry`], in the order they were reserved.
+#[derive(Debug, Default)]
+struct References {
+    set: HashSet<String>,
+    order: VecDeque<String>,
+    capacity: Option<usize>,
+}
+
+impl InMemoryRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty registry remembering at most `capacity` references: reserving one more
+    /// forgets the oldest.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            references: Arc::new(Mutex::new(References {
+                capacity: Some(capacity.max(1)),
+                ..References::default()
+            })),
+        }
+    }
+
+    /// Returns `true` if `reference` is currently reserved.
+    pub fn contains(&self, reference: &str) -> bool {
+        self.lock().set.contains(reference)
+    }
+
+    /// Returns the number of references currently reserved.
+    pub fn len(&self) -> usize {
+        self.lock().set.len()
+    }
+
+    /// Returns `true` if no reference is reserved.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, References> {
+        self.references.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl ReferenceRegistry for InMemoryRegistry {
+    async fn reserve(&self, reference: &str) -> Result<bool> {
+        let mut references = self.lock();
+        if !references.set.insert(reference.to_string()) {
+            return Ok(false);
+        }
+        references.order.push_back(reference.to_string());
+        while references
+            .capacity
+            .is_some_and(|capacity| references.set.len() > capacity)
+        {
+            let Some(oldest) = references.order.pop_front() else {
+                break;
+            };
+            references.set.remove(&oldest);
+        }
+        Ok(true)
+    }
+
+    async fn release(&self, reference: &str) -> Result<()> {
+        let mut references = self.lock();
+        if references.set.remove(reference) {
+            references.order.retain(|reserved| reserved != reference);
+        }
+        Ok(())
+    }
+}
+
+/// A store of the checkout URLs of initialized transactions, keyed by `tx_ref`.
+#[async_trait]
+pub trait CheckoutStore: Debug + Send + Sync {
+    /// Returns the checkout URL recorded for `tx_ref`, if any.
+    async fn get(&self, tx_ref: &str) -> Result<Option<String>>;
+
+    /// Records `checkout_url` as the checkout of `tx_ref`.
+    async fn put(&self, tx_ref: &str, checkout_url: &str) -> Result<()>;
+
+    /// Forgets the checkout of `tx_ref`, once it is paid or failed.
+    async fn remove(&self, tx_ref: &str) -> Result<()>;
+}
+
+/// A [`CheckoutStore`] keeping the checkout URLs in memory, for the lifetime of the process.
+///
+/// Clones share the same checkouts.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryCheckoutStore {
+    checkouts: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl InMemoryCheckoutStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, String>> {
+        self.checkouts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl CheckoutStore for InMemoryCheckoutStore {
+    async fn get(&self, tx_ref: &str) -> Result<Option<String>> {
+        Ok(self.lock().get(tx_ref).cloned())
+    }
+
+    async fn put(&self, tx_ref: &str, checkout_url: &str) -> Result<()> {
+        self.lock()
+            .insert(tx_ref.to_string(), checkout_url.to_string());
+        Ok(())
+    }
+
+    async fn remove(&self, tx_ref: &str) -> Result<()> {
+        self.lock().remove(tx_ref);
+        Ok(())
+    }
+}
+
+/// A store of the progress of bulk transfer runs, keyed by run id.
+///
+/// [`BulkRun`] is serializable, so a store backed by a database can keep it as JSON.
+#[async_trait]
+pub trait BulkProgressStore: Debug + Send + Sync {
+    /// Returns the progress recorded for `run_id`, if any.
+    async fn load(&self, run_id: &str) -> Result<Option<BulkRun>>;
+
+    /// Records `run` as the latest progress of its run, replacing the previous one.
+    async fn save(&self, run: &BulkRun) -> Result<()>;
+}
+
+/// A [`BulkProgressStore`] keeping the runs in memory, for the lifetime of the process.
+///
+/// Clones share the same runs.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryBulkProgressStore {
+    runs: Arc<Mutex<HashMap<String, BulkRun>>>,
+}
+
+impl InMemoryBulkProgressStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, BulkRun>> {
+        self.runs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl BulkProgressStore for InMemoryBulkProgressStore {
+    async fn load(&self, run_id: &str) -> Result<Option<BulkRun>> {
+        Ok(self.lock().get(run_id).cloned())
+    }
+
+    async fn save(&self, run: &BulkRun) -> Result<()> {
+        self.lock().insert(run.run_id.clone(), run.clone());
+        Ok(())
+    }
+}
+
+/// A store of the checkpoints of export jobs, keyed by job id.
+///
+/// [`ExportCheckpoint`] is serializable, so a store backed by a database can keep it as JSON.
+#[async_trait]
+pub trait ExportCheckpointStore: Debug + Send + Sync {
+    /// Returns the checkpoint recorded for `job_id`, if any.
+    async fn load(&self, job_id: &str) -> Result<Option<ExportCheckpoint>>;
+
+    /// Records `checkpoint` as the latest one of its job, replacing the previous one.
+    async fn save(&self, checkpoint: &ExportCheckpoint) -> Result<()>;
+}
+
+/// An [`ExportCheckpointStore`] keeping the checkpoints in memory, for the lifetime of the
+/// process.
+///
+/// Clones share the same checkpoints.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryExportCheckpointStore {
+    checkpoints: Arc<Mutex<HashMap<String, ExportCheckpoint>>>,
+}
+
+impl InMemoryExportCheckpointStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ExportCheckpoint>> {
+        self.checkpoints.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl ExportCheckpointStore for InMemoryExportCheckpointStore {
+    async fn load(&self, job_id: &str) -> Result<Option<ExportCheckpoint>> {
+        Ok(self.lock().get(job_id).cloned())
+    }
+
+    async fn save(&self, checkpoint: &ExportCheckpoint) -> Result<()> {
+        self.lock()
+            .insert(checkpoint.job_id.clone(), checkpoint.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,420 @@
+//! # Reload Module
+//!
+//! A [`ConfigWatcher`], available behind the `reload` feature, watching a configuration file for
+//! changes and applying the updated API key, base URL and webhook secret to running clients, so
+//! rotating a key doesn't require a restart.
+//!
+//! The file is either a single line holding the API key (e.g. a mounted secret), or `KEY=VALUE`
+//! lines in the `.env` format with any of the keys:
+//! - `CHAPA_SECRET_KEY`, the API key (`CHAPA_API_PUBLIC_KEY` is read when it isn't set);
+//! - `CHAPA_BASE_URL`, the base URL of the API;
+//! - `CHAPA_WEBHOOK_SECRET`, the webhook secret.
+//!
+//! Empty lines and lines starting with `#` are ignored. Settings missing from the file keep the
+//! value of the client configuration.
+//!
+//! Clients follow a watcher once attached with
+//! [`ChapaClient::with_config_watcher`](crate::client::ChapaClient::with_config_watcher). Every
+//! change is validated before being applied as a whole, and reported as a [`ReloadEvent`] to the
+//! subscribers of the watcher; an invalid file is rejected and the previous settings are kept.
+//!
+//! A watcher is bound to one [`Environment`]: the one set with [`ConfigWatcher::environment`],
+//! or else the one of the first client attached or of the first key applied. A key for the other
+//! environment is rejected, so rotating a key can't turn a test deployment into a live one, and
+//! the environment reported by the client and its configuration stays accurate.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//!
+//! use chapa_rust::{client::ChapaClient, reload::{ConfigWatcher, ReloadEvent}};
+//!
+//! # async fn run(client: ChapaClient) -> chapa_rust::error::Result<()> {
+//! let watcher = ConfigWatcher::new("/run/secrets/chapa").interval(Duration::from_secs(10));
+//! watcher.reload().await?;
+//! let client = client.with_config_watcher(&watcher)?;
+//!
+//! let mut events = watcher.subscribe();
+//! watcher.spawn();
+//! while let Ok(event) = events.recv().await {
+//!     if let ReloadEvent::Rejected { reason } = event {
+//!         eprintln!("chapa configuration rejected: {}", reason);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use tokio::sync::broadcast;
+
+use crate::{
+    config::{Environment, validate_base_url},
+    error::{ChapaError, Result},
+};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The settings read from the watched file. `None` fields keep the value of the client
+/// configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadableSettings {
+    /// The API key.
+    pub api_key: Option<String>,
+    /// The base URL of the API.
+    pub base_url: Option<String>,
+    /// The webhook secret.
+    pub webhook_secret: Option<String>,
+}
+
+impl ReloadableSettings {
+    /// Parses the content of a configuration file, see the [module documentation](self).
+    pub fn parse(content: &str) -> Self {
+        let lines: Vec<&str> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        match lines.as_slice() {
+            [key] if !key.contains('=') => {
+                return Self {
+                    api_key: Some(key.to_string()),
+                    ..Default::default()
+                };
+            }
+            _ => {}
+        }
+
+        let mut settings = Self::default();
+        let mut legacy_key = None;
+        for line in lines {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            let field = match key.trim() {
+                "CHAPA_SECRET_KEY" => &mut settings.api_key,
+                "CHAPA_API_PUBLIC_KEY" => &mut legacy_key,
+                "CHAPA_BASE_URL" => &mut settings.base_url,
+                "CHAPA_WEBHOOK_SECRET" => &mut settings.webhook_secret,
+                _ => continue,
+            };
+            *field = Some(value.to_string());
+        }
+        settings.api_key = settings.api_key.or(legacy_key);
+        settings
+    }
+
+    /// Checks the API key and base URL, see [`Environment::from_api_key`], returning the
+    /// environment of the key if any.
+    /// # Errors
+    /// Returns [`ChapaError::EnvironmentMismatch`] if the key isn't for the `expected`
+    /// environment.
+    fn validate(
+        &self,
+        allow_insecure: bool,
+        expected: Option<Environment>,
+    ) -> Result<Option<Environment>> {
+        let environment = self
+            .api_key
+            .as_deref()
+            .map(Environment::from_api_key)
+            .transpose()?;
+        match (expected, environment) {
+            (Some(expected), Some(actual)) if expected != actual => {
+                return Err(ChapaError::EnvironmentMismatch { expected, actual });
+            }
+            _ => {}
+        }
+        if let Some(url) = &self.base_url {
+            validate_base_url(url, allow_insecure)?;
+        }
+        Ok(environment)
+    }
+
+    /// The names of the settings that differ between `self` and `other`.
+    fn changes(&self, other: &Self) -> Vec<&'static str> {
+        [
+            ("api_key", self.api_key != other.api_key),
+            ("base_url", self.base_url != other.base_url),
+            (
+                "webhook_secret",
+                self.webhook_secret != other.webhook_secret,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect()
+    }
+}
+
+/// The outcome of a change of the watched file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReloadEvent {
+    /// The new settings were applied.
+    Applied {
+        /// The names of the changed settings (`"api_key"`, `"base_url"`, `"webhook_secret"`).
+        changed: Vec<&'static str>,
+    },
+    /// The file is invalid, the previous settings are kept.
+    Rejected {
+        /// Why the file was rejected.
+        reason: String,
+    },
+}
+
+#[derive(Debug)]
+struct Shared {
+    settings: RwLock<ReloadableSettings>,
+    /// The environment the keys must be for, once known.
+    environment: Mutex<Option<Environment>>,
+    /// The content of the file when last read, to skip unchanged files.
+    content: Mutex<Option<String>>,
+    events: broadcast::Sender<ReloadEvent>,
+}
+
+/// Watches a configuration file and shares its settings with the clients attached to it.
+///
+/// Clones share the same settings and subscribers.
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    interval: Duration,
+    allow_insecure: bool,
+    shared: Arc<Shared>,
+}
+
+impl ConfigWatcher {
+    /// Creates a watcher of the file at `path`. Nothing is read until [`ConfigWatcher::reload`]
+    /// or [`ConfigWatcher::spawn`] is called.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            interval: DEFAULT_INTERVAL,
+            allow_insecure: false,
+            shared: Arc::new(Shared {
+                settings: RwLock::new(ReloadableSettings::default()),
+                environment: Mutex::new(None),
+                content: Mutex::new(None),
+                events: broadcast::channel(16).0,
+            }),
+        }
+    }
+
+    /// Sets how often [`ConfigWatcher::spawn`] checks the file. default to 5s.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Accepts plain `http://` base URLs in the file, e.g. for tests against a local server.
+    pub fn allow_insecure(mut self) -> Self {
+        self.allow_insecure = true;
+        self
+    }
+
+    /// Binds the watcher to `environment`: keys for the other environment are rejected.
+    pub fn environment(self, environment: Environment) -> Self {
+        *self
+            .shared
+            .environment
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(environment);
+        self
+    }
+
+    /// Binds the watcher to `environment` unless it is bound already, see the
+    /// [module documentation](self).
+    /// # Errors
+    /// Returns [`ChapaError::EnvironmentMismatch`] if the watcher is bound to the other
+    /// environment.
+    pub(crate) fn bind(&self, environment: Environment) -> Result<()> {
+        let mut bound = self
+            .shared
+            .environment
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match *bound {
+            Some(expected) if expected != environment => Err(ChapaError::EnvironmentMismatch {
+                expected,
+                actual: environment,
+            }),
+            _ => {
+                *bound = Some(environment);
+                Ok(())
+            }
+        }
+    }
+
+    /// The settings currently applied.
+    pub fn settings(&self) -> ReloadableSettings {
+        self.shared
+            .settings
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Subscribes to the [`ReloadEvent`]s of the watcher.
+    pub fn subscribe(&self) -> broadcast::Receiver<ReloadEvent> {
+        self.shared.events.subscribe()
+    }
+
+    /// Reads the file and applies its settings if it changed since it was last read.
+    ///
+    /// Returns the event sent to the subscribers, or `None` if the file didn't change.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::IoError`] if the file cannot be read.
+    pub async fn reload(&self) -> Result<Option<ReloadEvent>> {
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|e| ChapaError::IoError(format!("{}: {}", self.path.display(), e)))?;
+        {
+            let mut last = self
+                .shared
+                .content
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if last.as_deref() == Some(content.as_str()) {
+                return Ok(None);
+            }
+            *last = Some(content.clone());
+        }
+
+        let settings = ReloadableSettings::parse(&content);
+        let mut bound = self
+            .shared
+            .environment
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let event = match settings.validate(self.allow_insecure, *bound) {
+            Ok(environment) => {
+                *bound = bound.or(environment);
+                let mut current = self
+                    .shared
+                    .settings
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner());
+                let changed = settings.changes(&current);
+                *current = settings;
+                ReloadEvent::Applied { changed }
+            }
+            Err(error) => ReloadEvent::Rejected {
+                reason: error.to_string(),
+            },
+        };
+        drop(bound);
+
+        #[cfg(feature = "tracing")]
+        match &event {
+            ReloadEvent::Applied { changed } => {
+                tracing::info!(target: "chapa_rust::reload", path = %self.path.display(), ?changed, "configuration reloaded")
+            }
+            ReloadEvent::Rejected { reason } => {
+                tracing::warn!(target: "chapa_rust::reload", path = %self.path.display(), reason, "configuration rejected")
+            }
+        }
+
+        // no subscriber is not an error.
+        let _ = self.shared.events.send(event.clone());
+        Ok(Some(event))
+    }
+
+    /// Checks the file every [interval](ConfigWatcher::interval) in a background task, until the
+    /// returned handle is aborted. Read errors (e.g. while the file is being replaced) are
+    /// retried at the next check.
+    pub fn spawn(&self) -> tokio::task::JoinHandle<()> {
+        let watcher = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let _ = watcher.reload().await;
+                tokio::time::sleep(watcher.interval).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reload() {
+        let path = std::env::temp_dir().join(format!("chapa-reload-{}.env", std::process::id()));
+        std::fs::write(
+            &path,
+            "# rotated weekly\nCHAPA_API_PUBLIC_KEY=CHASECK_TEST-first\nCHAPA_WEBHOOK_SECRET=\"whsec\"\n",
+        )
+        .unwrap();
+        let watcher = ConfigWatcher::new(&path);
+        let mut events = watcher.subscribe();
+
+        assert_eq!(
+            watcher.reload().await.unwrap(),
+            Some(ReloadEvent::Applied {
+                changed: vec!["api_key", "webhook_secret"]
+            })
+        );
+        assert_eq!(watcher.reload().await.unwrap(), None);
+        assert_eq!(watcher.settings().webhook_secret.as_deref(), Some("whsec"));
+
+        std::fs::write(&path, "CHAPA_API_PUBLIC_KEY=not-a-key\n").unwrap();
+        assert!(matches!(
+            watcher.reload().await.unwrap(),
+            Some(ReloadEvent::Rejected { .. })
+        ));
+        assert_eq!(
+            watcher.settings().api_key.as_deref(),
+            Some("CHASECK_TEST-first")
+        );
+
+        // a live key can't replace a test one.
+        std::fs::write(&path, "CHAPA_SECRET_KEY=CHASECK-live\n").unwrap();
+        let Some(ReloadEvent::Rejected { reason }) = watcher.reload().await.unwrap() else {
+            panic!("expected the live key to be rejected");
+        };
+        assert!(reason.contains("Live"), "{}", reason);
+
+        // the secret key wins over the legacy name, whatever the order.
+        std::fs::write(
+            &path,
+            "CHAPA_SECRET_KEY=CHASECK_TEST-secret\nCHAPA_API_PUBLIC_KEY=CHASECK_TEST-legacy\n",
+        )
+        .unwrap();
+        watcher.reload().await.unwrap();
+        assert_eq!(
+            watcher.settings().api_key.as_deref(),
+            Some("CHASECK_TEST-secret")
+        );
+
+        std::fs::write(&path, "CHASECK_TEST-second\n").unwrap();
+        watcher.reload().await.unwrap();
+        assert_eq!(
+            watcher.settings(),
+            ReloadableSettings {
+                api_key: Some("CHASECK_TEST-second".to_string()),
+                ..Default::default()
+            }
+        );
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            ReloadEvent::Applied { .. }
+        ));
+        assert_eq!(
+            events.len(),
+            4,
+            "the rejected and the later applied events are queued"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            watcher.reload().await,
+            Err(ChapaError::IoError(_))
+        ));
+    }
+}
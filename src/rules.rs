@@ -0,0 +1,127 @@
+//! # Rules Module
+//!
+//! Per-currency minimum amounts for charges (checkout, direct charge) and transfers, checked by
+//! the client before anything is sent, so amounts Chapa would refuse (e.g. a 0.5 ETB checkout)
+//! fail fast with [`ChapaError::InvalidAmount`].
+//!
+//! The defaults follow Chapa's minimums; merchants can tighten them further, but not loosen them.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::{config::ChapaConfig, rules::AmountRules};
+//!
+//! let config = ChapaConfig::builder()
+//!     .api_key("CHASECK_TEST-xxxxxxxxxxxx")
+//!     .amount_rules(AmountRules::default().min_charge("ETB", 50.0))
+//!     .build()
+//!     .unwrap();
+//! assert!(config.amount_rules.check_charge("20", "ETB").is_err());
+//! ```
+use std::collections::HashMap;
+
+use crate::error::{ChapaError, Result};
+
+/// Chapa's minimum charge amount per currency.
+const CHAPA_MIN_CHARGE: [(&str, f64); 2] = [("ETB", 1.0), ("USD", 1.0)];
+/// Chapa's minimum transfer amount per currency.
+const CHAPA_MIN_TRANSFER: [(&str, f64); 2] = [("ETB", 10.0), ("USD", 1.0)];
+
+/// Minimum amounts per currency, for charges and for transfers.
+///
+/// Currencies are matched case-insensitively; currencies without a rule only need a positive amount.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmountRules {
+    min_charge: HashMap<String, f64>,
+    min_transfer: HashMap<String, f64>,
+}
+
+impl Default for AmountRules {
+    fn default() -> Self {
+        let table = |rules: &[(&str, f64)]| {
+            rules
+                .iter()
+                .map(|(currency, min)| (currency.to_string(), *min))
+                .collect()
+        };
+        Self {
+            min_charge: table(&CHAPA_MIN_CHARGE),
+            min_transfer: table(&CHAPA_MIN_TRANSFER),
+        }
+    }
+}
+
+impl AmountRules {
+    /// Raises the minimum charge amount for `currency`. Values below Chapa's own minimum are ignored.
+    pub fn min_charge(mut self, currency: &str, min: f64) -> Self {
+        Self::tighten(&mut self.min_charge, currency, min);
+        self
+    }
+
+    /// Raises the minimum transfer amount for `currency`. Values below Chapa's own minimum are ignored.
+    pub fn min_transfer(mut self, currency: &str, min: f64) -> Self {
+        Self::tighten(&mut self.min_transfer, currency, min);
+        self
+    }
+
+    /// Checks the amount of a charge (checkout or direct charge).
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidAmount`] if the amount is not a number or below the minimum.
+    pub fn check_charge(&self, amount: &str, currency: &str) -> Result<()> {
+        Self::check(&self.min_charge, amount, currency)
+    }
+
+    /// Checks the amount of a transfer.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::InvalidAmount`] if the amount is not a number or below the minimum.
+    pub fn check_transfer(&self, amount: &str, currency: &str) -> Result<()> {
+        Self::check(&self.min_transfer, amount, currency)
+    }
+
+    fn tighten(table: &mut HashMap<String, f64>, currency: &str, min: f64) {
+        let current = table.entry(currency.to_ascii_uppercase()).or_insert(0.0);
+        *current = current.max(min);
+    }
+
+    fn check(table: &HashMap<String, f64>, amount: &str, currency: &str) -> Result<()> {
+        let value: f64 = amount
+            .trim()
+            .parse()
+            .ok()
+            .filter(|value: &f64| value.is_finite())
+            .ok_or_else(|| ChapaError::InvalidAmount(format!("{} is not a number", amount)))?;
+        let min = table
+            .get(&currency.to_ascii_uppercase())
+            .copied()
+            .unwrap_or(0.0);
+        if value <= 0.0 || value < min {
+            return Err(ChapaError::InvalidAmount(format!(
+                "{} {} is below the minimum of {} {}",
+                amount, currency, min, currency
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_rules() {
+        let rules = AmountRules::default();
+        assert!(rules.check_charge("0.5", "ETB").is_err());
+        assert!(rules.check_charge("1", "etb").is_ok());
+        assert!(rules.check_charge("abc", "ETB").is_err());
+        assert!(rules.check_charge("0", "KES").is_err());
+        assert!(rules.check_transfer("5", "ETB").is_err());
+
+        let tightened = rules.min_charge("ETB", 50.0).min_transfer("ETB", 0.1);
+        assert!(tightened.check_charge("20", "ETB").is_err());
+        assert!(tightened.check_charge("50", "ETB").is_ok());
+        // Chapa's own minimum still applies.
+        assert!(tightened.check_transfer("5", "ETB").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+//! # Serializer Module
+//!
+//! A hook overriding how the JSON bodies of requests are serialized, for the rare cases where
+//! `serde_json`'s output must be adjusted for every request: forcing a number format on
+//! amounts, wrapping the payload in an encrypted envelope, ...
+//!
+//! The hook receives the body as a [`serde_json::Value`], with the [`Endpoint`] it is sent to,
+//! and returns the bytes to send. Attach it with
+//! [`ChapaConfigBuilder::body_serializer`](crate::config::ChapaConfigBuilder::body_serializer).
+//! It doesn't apply to the `multipart/form-data` bodies of direct charges.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::{config::ChapaConfig, endpoint::Endpoint, error::Result, serializer::BodySerializer};
+//! use serde_json::Value;
+//!
+//! /// Sends amounts as strings with two decimals, e.g. `"100.00"`.
+//! #[derive(Debug)]
+//! struct FixedAmounts;
+//!
+//! impl BodySerializer for FixedAmounts {
+//!     fn serialize(&self, _endpoint: &Endpoint<'_>, mut body: Value) -> Result<Vec<u8>> {
+//!         let amount = body.get("amount").and_then(Value::as_str).and_then(|a| a.parse::<f64>().ok());
+//!         if let Some(amount) = amount {
+//!             body["amount"] = Value::String(format!("{:.2}", amount));
+//!         }
+//!         Ok(serde_json::to_vec(&body)?)
+//!     }
+//! }
+//!
+//! let config = ChapaConfig::builder()
+//!     .api_key("CHASECK_TEST-xxxxxxxxxxxx")
+//!     .body_serializer(FixedAmounts)
+//!     .build()
+//!     .unwrap();
+//! ```
+use std::fmt::Debug;
+
+use serde_json::Value;
+
+use crate::{endpoint::Endpoint, error::Result};
+
+/// Serializes the JSON body of requests.
+pub trait BodySerializer: Debug + Send + Sync {
+    /// Turns `body`, the request body sent to `endpoint`, into the bytes to send.
+    ///
+    /// # Errors
+    /// The error is returned by the client method that sent the request, before anything is
+    /// sent.
+    fn serialize(&self, endpoint: &Endpoint<'_>, body: Value) -> Result<Vec<u8>>;
+}
@@ -0,0 +1,120 @@
+//! # Sink Module
+//!
+//! Destinations verified webhook events can be forwarded to, e.g. to feed payment events into
+//! a message queue or a data warehouse. Implement [`WebhookSink`] for your own pipeline (Kafka,
+//! BigQuery, ...), or use the [`FileSink`] (JSON lines) and [`StdoutSink`] implementations.
+//!
+//! Events are published as the JSON body Chapa delivered, so sinks don't need to track the
+//! SDK's event types.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use chapa_rust::sink::{FileSink, WebhookSink};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let sink = FileSink::open("webhooks.jsonl").await.unwrap();
+//! let event = serde_json::json!({"event": "charge.success", "tx_ref": "chewatatest-6669"});
+//! sink.publish(&event).await.unwrap();
+//! # }
+//! ```
+use std::{fmt::Debug, io::Write, path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::{fs::File, io::AsyncWriteExt, sync::Mutex};
+
+use crate::error::{ChapaError, Result};
+
+/// A destination for verified webhook events.
+#[async_trait]
+pub trait WebhookSink: Debug + Send + Sync {
+    /// Forwards `event`, the verified webhook body, to the sink.
+    async fn publish(&self, event: &Value) -> Result<()>;
+}
+
+/// A [`WebhookSink`] appending every event as a line of JSON to a file.
+///
+/// Clones share the same file handle.
+#[derive(Debug, Clone)]
+pub struct FileSink {
+    file: Arc<Mutex<File>>,
+}
+
+impl FileSink {
+    /// Opens `path` for appending, creating the file if needed.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::IoError`] if the file cannot be opened.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(|e| ChapaError::IoError(format!("{}: {}", path.display(), e)))?;
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+}
+
+#[async_trait]
+impl WebhookSink for FileSink {
+    async fn publish(&self, event: &Value) -> Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+        let mut file = self.file.lock().await;
+        file.write_all(&line)
+            .await
+            .map_err(|e| ChapaError::IoError(e.to_string()))?;
+        file.flush()
+            .await
+            .map_err(|e| ChapaError::IoError(e.to_string()))
+    }
+}
+
+/// A [`WebhookSink`] printing every event as a line of JSON on the standard output,
+/// e.g. for log collectors reading container output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+#[async_trait]
+impl WebhookSink for StdoutSink {
+    async fn publish(&self, event: &Value) -> Result<()> {
+        let line = serde_json::to_string(event)?;
+        writeln!(std::io::stdout().lock(), "{}", line)
+            .map_err(|e| ChapaError::IoError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_sink_appends_json_lines() {
+        let path = std::env::temp_dir().join(format!("chapa-sink-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let sink = FileSink::open(&path).await.unwrap();
+        sink.publish(&serde_json::json!({"event": "charge.success", "tx_ref": "tx-1"}))
+            .await
+            .unwrap();
+        sink.clone()
+            .publish(&serde_json::json!({"event": "charge.failed", "tx_ref": "tx-2"}))
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let events: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1]["tx_ref"], "tx-2");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,211 @@
+//! # State Module
+//!
+//! [`ChapaState`], a cheaply cloneable handle to a shared [`ChapaClient`], meant to be stored as
+//! web application state. All the client methods take `&self`, so handlers call them straight
+//! through the handle.
+//!
+//! With the `axum` feature, `ChapaState` is an extractor for any router state it can be
+//! obtained from with `FromRef`, and [`ChapaState::extension`] provides it as a layer instead.
+//! With the `actix` feature, it is an extractor for the app data registered with
+//! `App::app_data`. With the `rocket` feature, it is a request guard for the state managed by
+//! Rocket, which [`ChapaState::fairing`] attaches.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use chapa_rust::{config::ChapaConfig, state::{ChapaState, chapa_state}};
+//!
+//! // a handler, extracting the state with the `axum` or `actix` feature.
+//! async fn banks(chapa: ChapaState) -> String {
+//!     format!("{:?}", chapa.get_banks().await)
+//! }
+//!
+//! # fn main() -> chapa_rust::error::Result<()> {
+//! let state = chapa_state(ChapaConfig::builder().api_key("CHASECK_TEST-xxxxxxxxxxxx").build()?)?;
+//! // axum:  Router::new().route("/banks", get(banks)).with_state(state)
+//! // actix: App::new().app_data(state).route("/banks", web::get().to(banks))
+//! // rocket: rocket::build().attach(state.fairing()).mount("/", routes![banks])
+//! # Ok(())
+//! # }
+//! ```
+use std::{ops::Deref, sync::Arc};
+
+use crate::{client::ChapaClient, config::ChapaConfig, error::Result};
+
+/// A shared [`ChapaClient`], cloneable at the cost of a reference count.
+#[derive(Debug, Clone)]
+pub struct ChapaState(Arc<ChapaClient>);
+
+impl ChapaState {
+    /// Wraps an existing client.
+    pub fn new(client: ChapaClient) -> Self {
+        Self(Arc::new(client))
+    }
+
+    /// Returns the wrapped client.
+    pub fn client(&self) -> &ChapaClient {
+        &self.0
+    }
+
+    /// Returns a layer adding the state to every request, for handlers extracting
+    /// `Extension<ChapaState>` in routers whose state doesn't hold it.
+    #[cfg(feature = "axum")]
+    pub fn extension(&self) -> axum::Extension<ChapaState> {
+        axum::Extension(self.clone())
+    }
+
+    /// Returns a fairing adding the state to the state managed by Rocket, for the request guards
+    /// and the webhook data guard.
+    #[cfg(feature = "rocket")]
+    pub fn fairing(&self) -> rocket::fairing::AdHoc {
+        let state = self.clone();
+        rocket::fairing::AdHoc::on_ignite(
+            "Chapa client",
+            |rocket| async move { rocket.manage(state) },
+        )
+    }
+}
+
+impl Deref for ChapaState {
+    type Target = ChapaClient;
+
+    fn deref(&self) -> &ChapaClient {
+        &self.0
+    }
+}
+
+impl From<ChapaClient> for ChapaState {
+    fn from(client: ChapaClient) -> Self {
+        Self::new(client)
+    }
+}
+
+/// Builds a client from `config` and wraps it for use as application state.
+///
+/// # Errors
+/// Returns an error if the client can not be built, see [`ChapaClient::from_config`].
+pub fn chapa_state(config: ChapaConfig) -> Result<ChapaState> {
+    Ok(ChapaState::new(ChapaClient::from_config(config)?))
+}
+
+#[cfg(feature = "axum")]
+impl<S> axum::extract::FromRequestParts<S> for ChapaState
+where
+    S: Send + Sync,
+    ChapaState: axum::extract::FromRef<S>,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        _parts: &mut axum::http::request::Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        Ok(<ChapaState as axum::extract::FromRef<S>>::from_ref(state))
+    }
+}
+
+#[cfg(feature = "actix")]
+impl actix_web::FromRequest for ChapaState {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<std::result::Result<Self, Self::Error>>;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        _payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        let state = req
+            .app_data::<ChapaState>()
+            .cloned()
+            .or_else(|| {
+                req.app_data::<actix_web::web::Data<ChapaState>>()
+                    .map(|data| data.get_ref().clone())
+            })
+            .ok_or_else(|| {
+                actix_web::error::ErrorInternalServerError(
+                    "ChapaState is not registered with App::app_data",
+                )
+            });
+        std::future::ready(state)
+    }
+}
+
+#[cfg(feature = "rocket")]
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for ChapaState {
+    type Error = &'static str;
+
+    async fn from_request(
+        req: &'r rocket::Request<'_>,
+    ) -> rocket::request::Outcome<Self, Self::Error> {
+        use rocket::{http::Status, request::Outcome};
+
+        match req.rocket().state::<ChapaState>() {
+            Some(state) => Outcome::Success(state.clone()),
+            None => Outcome::Error((
+                Status::InternalServerError,
+                "ChapaState is not managed by Rocket, attach ChapaState::fairing",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> ChapaState {
+        chapa_state(
+            ChapaConfig::builder()
+                .api_key("CHASECK_TEST-XXXXXXXXXXXXXXX")
+                .build()
+                .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_state_shares_client() {
+        let state = state();
+        let clone = state.clone();
+        assert!(Arc::ptr_eq(&state.0, &clone.0));
+        assert_eq!(clone.environment(), state.client().environment());
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_axum_extractor() {
+        use axum::extract::{FromRef, FromRequestParts};
+
+        #[derive(Clone)]
+        struct AppState {
+            chapa: ChapaState,
+        }
+        impl FromRef<AppState> for ChapaState {
+            fn from_ref(app: &AppState) -> Self {
+                app.chapa.clone()
+            }
+        }
+
+        let app = AppState { chapa: state() };
+        let (mut parts, _) = axum::http::Request::new(()).into_parts();
+        let extracted = ChapaState::from_request_parts(&mut parts, &app)
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&extracted.0, &app.chapa.0));
+    }
+
+    #[cfg(feature = "actix")]
+    #[tokio::test]
+    async fn test_actix_extractor() {
+        use actix_web::{FromRequest, test::TestRequest};
+
+        let state = state();
+        let req = TestRequest::default()
+            .app_data(state.clone())
+            .to_http_request();
+        let extracted = ChapaState::extract(&req).await.unwrap();
+        assert!(Arc::ptr_eq(&extracted.0, &state.0));
+
+        let missing = TestRequest::default().to_http_request();
+        assert!(ChapaState::extract(&missing).await.is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! # Stats Module
+//!
+//! Simple per-endpoint counters kept by every client, for basic observability without any
+//! metrics or tracing setup. See [`ChapaClient::stats`](crate::client::ChapaClient::stats).
+//!
+//! Endpoints are keyed by their path without the API version and query string, with the
+//! identifiers after the first two segments replaced by `:id`, e.g. `transaction/verify/:id`.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # #[cfg(feature = "async")]
+//! # async fn run(client: chapa_rust::client::ChapaClient) {
+//! client.get_banks().await.ok();
+//! for (endpoint, stats) in client.stats() {
+//!     println!("{endpoint}: {} requests, {} API failures", stats.requests, stats.api_failures);
+//! }
+//! # }
+//! ```
+#[cfg(feature = "async")]
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+
+/// The counters of a single endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointStats {
+    /// How many requests were sent.
+    pub requests: u64,
+    /// How many requests were answered with an HTTP error status.
+    pub api_failures: u64,
+    /// How many requests failed before a response was received (connection, timeout, ...).
+    pub network_failures: u64,
+    /// When the last failure of either kind happened.
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+/// The outcome of a request, as counted by the [`StatsRecorder`].
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    Success,
+    ApiFailure,
+    NetworkFailure,
+}
+
+/// Per-endpoint counters shared by a client and its clones.
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatsRecorder {
+    endpoints: Arc<Mutex<HashMap<String, EndpointStats>>>,
+}
+
+#[cfg(feature = "async")]
+impl StatsRecorder {
+    /// Counts a request to the endpoint at `path` (relative to the API version).
+    pub(crate) fn record(&self, path: &str, outcome: Outcome) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = endpoints.entry(endpoint_key(path)).or_default();
+        stats.requests += 1;
+        match outcome {
+            Outcome::Success => return,
+            Outcome::ApiFailure => stats.api_failures += 1,
+            Outcome::NetworkFailure => stats.network_failures += 1,
+        }
+        stats.last_error_at = Some(Utc::now());
+    }
+
+    /// Returns a copy of the counters of every endpoint called so far.
+    pub(crate) fn snapshot(&self) -> HashMap<String, EndpointStats> {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+#[cfg(feature = "async")]
+fn endpoint_key(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+    path.trim_matches('/')
+        .split('/')
+        .enumerate()
+        .map(|(index, segment)| if index < 2 { segment } else { ":id" })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_per_endpoint() {
+        let recorder = StatsRecorder::default();
+        recorder.record("transaction/verify/tx-1", Outcome::Success);
+        recorder.record("transaction/verify/tx-2", Outcome::ApiFailure);
+        recorder.record("banks", Outcome::NetworkFailure);
+
+        let stats = recorder.snapshot();
+        let verify = &stats["transaction/verify/:id"];
+        assert_eq!((verify.requests, verify.api_failures), (2, 1));
+        assert!(verify.last_error_at.is_some());
+        assert_eq!(stats["banks"].network_failures, 1);
+    }
+}
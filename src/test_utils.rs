@@ -0,0 +1,159 @@
+//! # Test Utils Module
+//!
+//! The test credentials documented by Chapa for test mode, available behind the `test-utils`
+//! feature, so sandbox test suites use typed constants instead of values copied from the docs.
+//!
+//! They only work with a test key (`CHASECK_TEST-...`); in live mode they are ordinary,
+//! most likely invalid, payment details.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::{models::{channel::PaymentChannel, direct_charge::DirectChargeOptions}, test_utils};
+//!
+//! let phone = test_utils::test_phone(PaymentChannel::Telebirr).unwrap();
+//! let options = DirectChargeOptions {
+//!     amount: "100".to_string(),
+//!     currency: "ETB".to_string(),
+//!     tx_ref: test_utils::sample_tx_ref("order"),
+//!     mobile: Some(phone.number.to_string()),
+//!     ..Default::default()
+//! };
+//! assert!(options.tx_ref.starts_with("order-"));
+//! ```
+use crate::models::channel::PaymentChannel;
+
+/// A wallet phone number accepted in test mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestPhone {
+    /// The channel the number can be charged on.
+    pub channel: PaymentChannel,
+    /// The phone number, in the local `09...`/`07...` format.
+    pub number: &'static str,
+}
+
+/// A card accepted in test mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestCard {
+    /// The card brand.
+    pub brand: &'static str,
+    /// The card number, without spaces.
+    pub number: &'static str,
+    /// The card verification value.
+    pub cvv: &'static str,
+    /// The expiry month, `MM`.
+    pub expiry_month: &'static str,
+    /// The expiry year, `YY`.
+    pub expiry_year: &'static str,
+}
+
+/// A bank accepted for transfers in test mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestBank {
+    /// The name of the bank.
+    pub name: &'static str,
+    /// The bank code, as used in transfers.
+    pub bank_code: u32,
+    /// An account number of the expected length.
+    pub account_number: &'static str,
+}
+
+/// The wallet phone numbers documented by Chapa for test mode.
+pub const TEST_PHONES: [TestPhone; 4] = [
+    TestPhone {
+        channel: PaymentChannel::Telebirr,
+        number: "0900123456",
+    },
+    TestPhone {
+        channel: PaymentChannel::Cbebirr,
+        number: "0900123456",
+    },
+    TestPhone {
+        channel: PaymentChannel::Awashbirr,
+        number: "0900123456",
+    },
+    TestPhone {
+        channel: PaymentChannel::Mpesa,
+        number: "0700123456",
+    },
+];
+
+/// The cards documented by Chapa for test mode.
+pub const TEST_CARDS: [TestCard; 3] = [
+    TestCard {
+        brand: "Visa",
+        number: "4200000000000000",
+        cvv: "123",
+        expiry_month: "12",
+        expiry_year: "34",
+    },
+    TestCard {
+        brand: "Mastercard",
+        number: "5400000000000005",
+        cvv: "123",
+        expiry_month: "12",
+        expiry_year: "34",
+    },
+    TestCard {
+        brand: "American Express",
+        number: "340000000000009",
+        cvv: "1234",
+        expiry_month: "12",
+        expiry_year: "34",
+    },
+];
+
+/// Banks usable for transfers in test mode, with account numbers of the expected length.
+pub const TEST_BANKS: [TestBank; 2] = [
+    TestBank {
+        name: "Abay Bank",
+        bank_code: 130,
+        account_number: "1000000000000000",
+    },
+    TestBank {
+        name: "telebirr",
+        bank_code: 855,
+        account_number: "0900123456",
+    },
+];
+
+/// A transaction reference used in Chapa's own examples.
+pub const SAMPLE_TX_REF: &str = "chewatatest-6669";
+
+/// A transfer reference used in Chapa's own examples.
+pub const SAMPLE_TRANSFER_REF: &str = "3241342142sfdd";
+
+/// Returns the test phone number of `channel`, if Chapa documents one.
+pub fn test_phone(channel: PaymentChannel) -> Option<TestPhone> {
+    TEST_PHONES
+        .into_iter()
+        .find(|phone| phone.channel == channel)
+}
+
+/// Returns a transaction reference starting with `prefix` that is unique for the process, since
+/// Chapa rejects a reused `tx_ref` even in test mode.
+pub fn sample_tx_ref(prefix: &str) -> String {
+    static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    format!("{}-{}-{}", prefix, chrono::Utc::now().timestamp_millis(), n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_are_consistent() {
+        for phone in TEST_PHONES {
+            assert!(phone.channel.is_mobile_money());
+            assert_eq!(phone.number.len(), 10);
+        }
+        assert!(test_phone(PaymentChannel::Card).is_none());
+        assert_ne!(sample_tx_ref("order"), sample_tx_ref("order"));
+
+        let banks = crate::fixtures::get_banks().unwrap().data.unwrap();
+        for test_bank in TEST_BANKS {
+            let bank = banks.iter().find(|b| b.id == test_bank.bank_code).unwrap();
+            assert_eq!(test_bank.account_number.len() as u32, bank.acct_length);
+        }
+    }
+}
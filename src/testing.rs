@@ -0,0 +1,591 @@
+//! # Testing Module
+//!
+//! A [`ScriptedTransport`], available behind the `test-utils` feature, answering the requests of
+//! a [`ChapaClient`] with scripted JSON responses, so code using the client can be tested without
+//! an HTTP server. Unlike the `MockChapaClient` of the `mock` module, the whole client runs,
+//! including its validation, registries and decoding.
+//!
+//! Responses are scripted per endpoint, by the path template of the
+//! [`Endpoint`](crate::endpoint::Endpoint) (e.g. `"transaction/verify/{tx_ref}"`), optionally
+//! prefixed with the HTTP method when a template is shared by several endpoints (e.g.
+//! `"POST transfers"`). The responses scripted for an endpoint are served in order, and the last
+//! one is repeated. Requests to an endpoint without a response are answered with a `404`.
+//!
+//! ## Scenarios
+//! A [`Scenario`] scripts a whole unhappy path that cannot be triggered on demand against the
+//! sandbox: a payment that stays pending, a webhook arriving late, a balance running out after a
+//! few transfers, or the API rate limiting every request. Scenarios are selected per transport,
+//! hence per test, and can be combined with each other and with scripted responses.
+//!
+//! ## Example
+//! ```rust
+//! use chapa_rust::testing::ScriptedTransport;
+//! use serde_json::json;
+//!
+//! # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+//! let transport = ScriptedTransport::new()
+//!     .respond("banks", json!({"message": "Banks retrieved", "data": []}))
+//!     .respond_with_status(
+//!         "POST transfers",
+//!         400,
+//!         json!({"message": "Insufficient Balance", "status": "failed", "data": null}),
+//!     );
+//! let client = transport.client();
+//!
+//! let banks = client.get_banks().await.unwrap();
+//! assert!(banks.data.unwrap().is_empty());
+//! assert_eq!(transport.calls()[0].path, "/v1/banks");
+//! # });
+//! ```
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::ResponseBuilderExt;
+use serde_json::{Value, json};
+
+use crate::{
+    client::ChapaClient,
+    config::ChapaConfigBuilder,
+    error::{ChapaError, Result},
+    fixtures,
+    transport::Transport,
+};
+
+/// A request received by a [`ScriptedTransport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedCall {
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The path of the request, e.g. `"/v1/transaction/verify/order-1"`.
+    pub path: String,
+    /// The query string of the request, if any.
+    pub query: Option<String>,
+    /// The JSON body of the request, if any.
+    pub body: Option<Value>,
+}
+
+impl ScriptedCall {
+    /// The last segment of the path, e.g. the `tx_ref` of a verification.
+    fn last_segment(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or_default()
+    }
+
+    /// The string field `name` of the JSON body, or an empty string.
+    fn body_str(&self, name: &str) -> &str {
+        self.body
+            .as_ref()
+            .and_then(|body| body.get(name))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// A response served by a [`ScriptedTransport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedResponse {
+    /// The HTTP status of the response.
+    pub status: u16,
+    /// The JSON body of the response.
+    pub body: Value,
+    /// The headers of the response, besides `content-type`.
+    pub headers: Vec<(String, String)>,
+}
+
+impl ScriptedResponse {
+    /// Creates a response with `status` and `body`, without extra headers.
+    pub fn new(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            body,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds the header `name` with `value` to the response.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// A named unhappy path, scripted with [`ScriptedTransport::scenario`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scenario {
+    /// Checkouts are initialized, but their payment stays `pending` when verified.
+    AlwaysPendingPayment,
+    /// Checkouts are initialized, and their payment stays `pending` until `delay` has passed.
+    /// It then verifies as `success`, and its `charge.success` webhook is returned by
+    /// [`ScriptedTransport::due_webhooks`].
+    DelayedWebhook {
+        /// How long after its initialization a payment completes.
+        delay: Duration,
+    },
+    /// Transfers are queued until the `nth` one (counting from 1), which is refused with
+    /// `Insufficient Balance`, as are the following ones.
+    InsufficientBalanceOnTransfer {
+        /// The first transfer to be refused.
+        nth: usize,
+    },
+    /// Every request is refused with a `429` and a `Retry-After` header, whatever else is
+    /// scripted.
+    RateLimited {
+        /// The delay advertised in the `Retry-After` header, in whole seconds.
+        retry_after: Duration,
+    },
+}
+
+/// A [`Transport`] answering with scripted responses, see the [module documentation](self).
+///
+/// Clones share the same scripts, recorded calls and webhooks.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedTransport {
+    scripts: Arc<Mutex<Vec<Script>>>,
+    calls: Arc<Mutex<Vec<ScriptedCall>>>,
+    webhooks: Arc<Mutex<Vec<ScheduledWebhook>>>,
+    rate_limit: Arc<Mutex<Option<Duration>>>,
+}
+
+type Responder = Arc<dyn Fn(&ScriptedCall) -> ScriptedResponse + Send + Sync>;
+
+struct Script {
+    method: Option<String>,
+    segments: Vec<String>,
+    responses: VecDeque<Responder>,
+}
+
+impl fmt::Debug for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Script")
+            .field("method", &self.method)
+            .field("segments", &self.segments)
+            .field("responses", &self.responses.len())
+            .finish()
+    }
+}
+
+impl Script {
+    /// Whether the script answers a request with `method` to `path`.
+    fn matches(&self, method: &str, path: &str) -> bool {
+        if self.method.as_deref().is_some_and(|m| m != method) {
+            return false;
+        }
+        let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+        path.len() >= self.segments.len()
+            && path[path.len() - self.segments.len()..]
+                .iter()
+                .zip(&self.segments)
+                .all(|(actual, expected)| expected.starts_with('{') || actual == expected)
+    }
+
+    /// The responder of the next response, keeping the last one.
+    fn next(&mut self) -> Option<Responder> {
+        if self.responses.len() > 1 {
+            self.responses.pop_front()
+        } else {
+            self.responses.front().cloned()
+        }
+    }
+}
+
+/// A `charge.success` webhook of the [`Scenario::DelayedWebhook`] scenario.
+#[derive(Debug)]
+struct ScheduledWebhook {
+    tx_ref: String,
+    due: Instant,
+    payload: Value,
+    delivered: bool,
+}
+
+impl ScriptedTransport {
+    /// Creates a transport without any response.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers requests to `endpoint` with a `200` and `body`, after the responses already
+    /// scripted for it.
+    pub fn respond(self, endpoint: &str, body: Value) -> Self {
+        self.respond_with_status(endpoint, 200, body)
+    }
+
+    /// Answers requests to `endpoint` with `status` and `body`, after the responses already
+    /// scripted for it.
+    pub fn respond_with_status(self, endpoint: &str, status: u16, body: Value) -> Self {
+        let response = ScriptedResponse::new(status, body);
+        self.respond_with(endpoint, move |_| response.clone())
+    }
+
+    /// Answers requests to `endpoint` with the response built by `responder` from the request,
+    /// after the responses already scripted for it.
+    pub fn respond_with<F>(self, endpoint: &str, responder: F) -> Self
+    where
+        F: Fn(&ScriptedCall) -> ScriptedResponse + Send + Sync + 'static,
+    {
+        let (method, template) = match endpoint.split_once(' ') {
+            Some((method, template)) => (Some(method.to_uppercase()), template),
+            None => (None, endpoint),
+        };
+        let template = template.split('?').next().unwrap_or_default();
+        {
+            let mut scripts = self.lock_scripts();
+            let index = scripts.iter().position(|script| {
+                script.method == method && script.segments.join("/") == template
+            });
+            let script = match index {
+                Some(index) => &mut scripts[index],
+                None => {
+                    scripts.push(Script {
+                        method,
+                        segments: template.split('/').map(str::to_string).collect(),
+                        responses: VecDeque::new(),
+                    });
+                    scripts.last_mut().expect("a script was just pushed")
+                }
+            };
+            script.responses.push_back(Arc::new(responder));
+        }
+        self
+    }
+
+    /// Scripts the endpoints involved in `scenario`, after the responses already scripted for
+    /// them.
+    pub fn scenario(self, scenario: Scenario) -> Self {
+        match scenario {
+            Scenario::AlwaysPendingPayment => self
+                .respond_with("transaction/initialize", checkout)
+                .respond_with("transaction/verify/{tx_ref}", |call| {
+                    verification(call, "pending")
+                }),
+            Scenario::DelayedWebhook { delay } => {
+                let webhooks = self.webhooks.clone();
+                let initialize = move |call: &ScriptedCall| {
+                    lock(&webhooks).push(ScheduledWebhook {
+                        tx_ref: call.body_str("tx_ref").to_string(),
+                        due: Instant::now() + delay,
+                        payload: charge_success(call),
+                        delivered: false,
+                    });
+                    checkout(call)
+                };
+                let webhooks = self.webhooks.clone();
+                let verify = move |call: &ScriptedCall| {
+                    let completed = lock(&webhooks).iter().any(|webhook| {
+                        webhook.tx_ref == call.last_segment() && webhook.due <= Instant::now()
+                    });
+                    verification(call, if completed { "success" } else { "pending" })
+                };
+                self.respond_with("transaction/initialize", initialize)
+                    .respond_with("transaction/verify/{tx_ref}", verify)
+            }
+            Scenario::InsufficientBalanceOnTransfer { nth } => {
+                let transfers = AtomicUsize::new(0);
+                self.respond_with("POST transfers", move |call| {
+                    if transfers.fetch_add(1, Ordering::SeqCst) + 1 >= nth {
+                        ScriptedResponse::new(
+                            400,
+                            json!({"message": "Insufficient Balance", "status": "failed", "data": null}),
+                        )
+                    } else {
+                        ScriptedResponse::new(
+                            200,
+                            json!({
+                                "message": "Transfer Queued Successfully",
+                                "status": "success",
+                                "data": call.body_str("reference")
+                            }),
+                        )
+                    }
+                })
+            }
+            Scenario::RateLimited { retry_after } => {
+                *lock(&self.rate_limit) = Some(retry_after);
+                self
+            }
+        }
+    }
+
+    /// The requests received so far, oldest first.
+    pub fn calls(&self) -> Vec<ScriptedCall> {
+        lock(&self.calls).clone()
+    }
+
+    /// The payloads of the webhooks of the [`Scenario::DelayedWebhook`] scenario whose delay has
+    /// passed since the last call, oldest first, as Chapa would post them.
+    pub fn due_webhooks(&self) -> Vec<Value> {
+        let now = Instant::now();
+        lock(&self.webhooks)
+            .iter_mut()
+            .filter(|webhook| !webhook.delivered && webhook.due <= now)
+            .map(|webhook| {
+                webhook.delivered = true;
+                webhook.payload.clone()
+            })
+            .collect()
+    }
+
+    /// A client for the test environment sending its requests to this transport.
+    pub fn client(&self) -> ChapaClient {
+        let config = ChapaConfigBuilder::new()
+            .api_key("CHASECK_TEST-scripted")
+            .transport(self.clone())
+            .build()
+            .expect("the scripted configuration is valid");
+        ChapaClient::with_http_client(reqwest::Client::new(), config)
+    }
+
+    fn lock_scripts(&self) -> MutexGuard<'_, Vec<Script>> {
+        lock(&self.scripts)
+    }
+
+    /// The response to `call`.
+    fn answer(&self, call: &ScriptedCall) -> ScriptedResponse {
+        if let Some(retry_after) = *lock(&self.rate_limit) {
+            return ScriptedResponse::new(
+                429,
+                json!({"message": "Too Many Attempts.", "status": "failed", "data": null}),
+            )
+            .header("retry-after", retry_after.as_secs().to_string());
+        }
+        let responder = self
+            .lock_scripts()
+            .iter_mut()
+            // scripts bound to a method win over the ones matching any method.
+            .filter(|script| script.matches(&call.method, &call.path))
+            .min_by_key(|script| script.method.is_none())
+            .and_then(Script::next);
+        match responder {
+            Some(responder) => responder(call),
+            None => {
+                let message = format!("no scripted response for {} {}", call.method, call.path);
+                ScriptedResponse::new(
+                    404,
+                    json!({"message": message, "status": "failed", "data": null}),
+                )
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ScriptedTransport {
+    async fn send(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        let call = ScriptedCall {
+            method: request.method().to_string(),
+            path: request.url().path().to_string(),
+            query: request.url().query().map(str::to_string),
+            body: request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .and_then(|bytes| serde_json::from_slice(bytes).ok()),
+        };
+        lock(&self.calls).push(call.clone());
+
+        let scripted = self.answer(&call);
+        let mut response = http::Response::builder()
+            .status(scripted.status)
+            .url(request.url().clone())
+            .header("content-type", "application/json");
+        for (name, value) in &scripted.headers {
+            response = response.header(name, value);
+        }
+        let response = response
+            .body(scripted.body.to_string())
+            .map_err(|e| ChapaError::api_error(e.to_string()))?;
+        Ok(reqwest::Response::from(response))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The hosted link of an initialized checkout.
+fn checkout(call: &ScriptedCall) -> ScriptedResponse {
+    let mut body: Value =
+        serde_json::from_str(fixtures::INITIALIZE_SUCCESS).expect("the fixture is valid JSON");
+    body["data"]["checkout_url"] = format!(
+        "https://checkout.chapa.co/checkout/payment/{}",
+        call.body_str("tx_ref")
+    )
+    .into();
+    ScriptedResponse::new(200, body)
+}
+
+/// The details of the payment verified by `call`, with `status`.
+fn verification(call: &ScriptedCall, status: &str) -> ScriptedResponse {
+    let mut body: Value =
+        serde_json::from_str(fixtures::VERIFY_SUCCESS).expect("the fixture is valid JSON");
+    body["data"]["tx_ref"] = call.last_segment().into();
+    body["data"]["status"] = status.into();
+    ScriptedResponse::new(200, body)
+}
+
+/// The `charge.success` webhook of the checkout initialized by `call`.
+fn charge_success(call: &ScriptedCall) -> Value {
+    let field = |name: &str| call.body.as_ref().and_then(|body| body.get(name)).cloned();
+    let now = Utc::now();
+    json!({
+        "event": "charge.success",
+        "first_name": field("first_name"),
+        "last_name": field("last_name"),
+        "email": field("email"),
+        "mobile": field("phone_number"),
+        "currency": field("currency"),
+        "amount": field("amount"),
+        "charge": null,
+        "status": "success",
+        "mode": "test",
+        "reference": format!("AP{}", call.body_str("tx_ref")),
+        "tx_ref": call.body_str("tx_ref"),
+        "payment_method": "test",
+        "type": "API",
+        "customization": field("customization"),
+        "meta": field("meta"),
+        "created_at": now,
+        "updated_at": now
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::transfer::TransferOptions;
+
+    #[tokio::test]
+    async fn test_scripted_transport() {
+        let transfer = |reference: &str| TransferOptions {
+            account_name: "Israel Goytom".to_string(),
+            account_number: "32423423".to_string(),
+            amount: "100".to_string(),
+            currency: "ETB".to_string(),
+            reference: reference.to_string(),
+            bank_code: 656,
+            narration: None,
+        };
+        let transport = ScriptedTransport::new()
+            .respond(
+                "POST transfers",
+                serde_json::json!({"message": "Transfer Queued Successfully", "status": "success", "data": "payout-1"}),
+            )
+            .respond_with_status(
+                "POST transfers",
+                400,
+                serde_json::json!({"message": "Insufficient Balance", "status": "failed", "data": null}),
+            )
+            .respond(
+                "transfers/verify/{reference}",
+                serde_json::json!({"message": "Transfer details", "status": "success", "data": null}),
+            );
+        let client = transport.client();
+
+        assert_eq!(
+            client.transfer(transfer("payout-1")).await.unwrap().status,
+            "success"
+        );
+        assert_eq!(
+            client.transfer(transfer("payout-2")).await.unwrap().status,
+            "failed"
+        );
+        assert_eq!(
+            client.transfer(transfer("payout-3")).await.unwrap().status,
+            "failed"
+        );
+        client.verify_transfer("payout-1").await.unwrap();
+        assert_eq!(client.get_banks().await.unwrap().status, "failed");
+
+        let calls = transport.calls();
+        assert_eq!(calls.len(), 5);
+        assert_eq!(calls[1].body.as_ref().unwrap()["reference"], "payout-2");
+        assert_eq!(calls[3].path, "/v1/transfers/verify/payout-1");
+        assert_eq!(client.stats()["banks"].api_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_scenarios() {
+        use crate::models::{payment::InitializeOptions, webhook::WebhookEvent};
+
+        let checkout = |tx_ref: &str| InitializeOptions {
+            amount: "100".to_string(),
+            currency: "ETB".to_string(),
+            email: Some("customer@gmail.com".to_string()),
+            tx_ref: tx_ref.to_string(),
+            ..Default::default()
+        };
+        let transfer = |reference: &str| TransferOptions {
+            account_name: "Israel Goytom".to_string(),
+            account_number: "32423423".to_string(),
+            amount: "100".to_string(),
+            currency: "ETB".to_string(),
+            reference: reference.to_string(),
+            bank_code: 656,
+            narration: None,
+        };
+
+        let transport = ScriptedTransport::new()
+            .scenario(Scenario::DelayedWebhook {
+                delay: Duration::from_millis(50),
+            })
+            .scenario(Scenario::InsufficientBalanceOnTransfer { nth: 2 });
+        let client = transport.client();
+        let response = client
+            .initialize_transaction(checkout("order-1"))
+            .await
+            .unwrap();
+        assert!(response.data.unwrap().checkout_url.ends_with("/order-1"));
+        let status =
+            |response: crate::models::response::VerifyResponse| response.data.unwrap().status;
+        let verified = client.verify_transaction("order-1").await.unwrap();
+        assert_eq!(status(verified).as_deref(), Some("pending"));
+        assert!(transport.due_webhooks().is_empty());
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let verified = client.verify_transaction("order-1").await.unwrap();
+        assert_eq!(status(verified).as_deref(), Some("success"));
+        let webhooks = transport.due_webhooks();
+        assert_eq!(webhooks.len(), 1);
+        let event: WebhookEvent = serde_json::from_value(webhooks[0].clone()).unwrap();
+        assert_eq!(event.tx_ref(), Some("order-1"));
+        assert!(transport.due_webhooks().is_empty());
+
+        assert_eq!(
+            client.transfer(transfer("payout-1")).await.unwrap().status,
+            "success"
+        );
+        assert_eq!(
+            client.transfer(transfer("payout-2")).await.unwrap().status,
+            "failed"
+        );
+        assert_eq!(
+            client.transfer(transfer("payout-3")).await.unwrap().status,
+            "failed"
+        );
+
+        let pending = ScriptedTransport::new()
+            .scenario(Scenario::AlwaysPendingPayment)
+            .client();
+        let verified = pending.verify_transaction("order-2").await.unwrap();
+        assert_eq!(status(verified).as_deref(), Some("pending"));
+
+        let limited = ScriptedTransport::new()
+            .respond(
+                "banks",
+                serde_json::json!({"message": "Banks retrieved", "data": []}),
+            )
+            .scenario(Scenario::RateLimited {
+                retry_after: Duration::from_secs(30),
+            })
+            .client();
+        assert!(matches!(
+            limited.get_banks().await,
+            Err(ChapaError::RateLimited { retry_after: Some(delay) }) if delay == Duration::from_secs(30)
+        ));
+    }
+}
@@ -0,0 +1,287 @@
+//! # Timeline Module
+//!
+//! Everything known about a payment, ordered in time: the verification data, the transaction
+//! logs and the webhooks received for a `tx_ref`, as typed [`TimelineEvent`]s. The status of the
+//! payment at any point of the timeline can be reconstructed from its events, which answers
+//! "what happened to this payment?" for support tooling.
+//!
+//! [`ChapaClient::payment_timeline`](crate::client::ChapaClient::payment_timeline) fetches the
+//! verification data and logs in one call; [`PaymentTimeline::builder`] assembles a timeline
+//! from data already at hand.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # #[cfg(feature = "async")]
+//! use chapa_rust::{client::ChapaClient, models::webhook::WebhookEvent};
+//!
+//! # #[cfg(feature = "async")]
+//! # async fn run(client: ChapaClient, received: Vec<WebhookEvent>) -> chapa_rust::error::Result<()> {
+//! let timeline = client.payment_timeline("chewatatest-6669", &received).await?;
+//! for event in &timeline.events {
+//!     println!("{:?} {:?}", event.at, event.kind);
+//! }
+//! println!("current status: {:?}", timeline.state());
+//! # Ok(())
+//! # }
+//! ```
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    normalized::PaymentStatus, payment::VerifyData, transaction::TransactionLog,
+    webhook::WebhookEvent,
+};
+
+/// The ordered events of a payment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentTimeline {
+    /// The merchant's transaction reference of the payment.
+    pub tx_ref: String,
+    /// The events, oldest first. Events without a time come last, in the order they were added.
+    pub events: Vec<TimelineEvent>,
+}
+
+impl PaymentTimeline {
+    /// Starts a timeline for the payment `tx_ref`.
+    pub fn builder(tx_ref: impl Into<String>) -> PaymentTimelineBuilder {
+        PaymentTimelineBuilder {
+            tx_ref: tx_ref.into(),
+            events: Vec::new(),
+        }
+    }
+
+    /// The status of the payment after its last event, or `None` if no event carries a status.
+    pub fn state(&self) -> Option<&PaymentStatus> {
+        self.events.iter().rev().find_map(|event| event.status())
+    }
+
+    /// The status of the payment at `at`, from the events up to that time.
+    pub fn state_at(&self, at: DateTime<Utc>) -> Option<&PaymentStatus> {
+        self.events
+            .iter()
+            .take_while(|event| event.at.is_some_and(|time| time <= at))
+            .filter_map(TimelineEvent::status)
+            .last()
+    }
+
+    /// The events that changed the status of the payment, with the status they led to.
+    pub fn transitions(&self) -> Vec<(&TimelineEvent, &PaymentStatus)> {
+        let mut transitions: Vec<(&TimelineEvent, &PaymentStatus)> = Vec::new();
+        for event in &self.events {
+            let Some(status) = event.status() else {
+                continue;
+            };
+            if transitions.last().is_none_or(|(_, last)| *last != status) {
+                transitions.push((event, status));
+            }
+        }
+        transitions
+    }
+}
+
+/// A single event of a [`PaymentTimeline`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    /// When the event happened, if the source tells.
+    pub at: Option<DateTime<Utc>>,
+    /// What happened.
+    pub kind: TimelineEventKind,
+}
+
+impl TimelineEvent {
+    /// The status of the payment after the event, for events that carry one.
+    pub fn status(&self) -> Option<&PaymentStatus> {
+        match &self.kind {
+            TimelineEventKind::Created { .. } => Some(&PaymentStatus::Pending),
+            TimelineEventKind::Webhook { status, .. }
+            | TimelineEventKind::Verified { status, .. } => Some(status),
+            TimelineEventKind::Log { .. } => None,
+        }
+    }
+}
+
+/// What happened in a [`TimelineEvent`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    /// The payment was initialized.
+    Created {
+        /// The amount of the payment, as a decimal string.
+        amount: String,
+        /// The currency of the payment.
+        currency: Option<String>,
+    },
+    /// An entry of the transaction logs.
+    Log {
+        /// What happened, e.g. "Attempted to pay with telebirr".
+        message: String,
+        /// The kind of the entry (e.g., "log", "error").
+        log_type: Option<String>,
+    },
+    /// A webhook was received.
+    Webhook {
+        /// The event type, e.g. `charge.success`.
+        event: String,
+        /// The status reported by the webhook.
+        status: PaymentStatus,
+    },
+    /// The status last reported by the verification endpoint.
+    Verified {
+        /// The status of the payment.
+        status: PaymentStatus,
+        /// The payment method used, if any.
+        method: Option<String>,
+    },
+}
+
+/// A builder of [`PaymentTimeline`], see [`PaymentTimeline::builder`].
+#[derive(Debug, Clone)]
+pub struct PaymentTimelineBuilder {
+    tx_ref: String,
+    events: Vec<TimelineEvent>,
+}
+
+impl PaymentTimelineBuilder {
+    /// Adds the creation and the verified status of the payment.
+    pub fn verify(mut self, data: &VerifyData) -> Self {
+        self.events.push(TimelineEvent {
+            at: Some(data.created_at),
+            kind: TimelineEventKind::Created {
+                amount: data.amount.to_string(),
+                currency: data.currency.clone(),
+            },
+        });
+        self.events.push(TimelineEvent {
+            at: Some(data.updated_at),
+            kind: TimelineEventKind::Verified {
+                status: PaymentStatus::parse(data.status.as_deref().unwrap_or("pending")),
+                method: data.method.clone(),
+            },
+        });
+        self
+    }
+
+    /// Adds the entries of the transaction logs.
+    pub fn logs(mut self, logs: &[TransactionLog]) -> Self {
+        self.events.extend(logs.iter().map(|log| TimelineEvent {
+            at: Some(log.created_at),
+            kind: TimelineEventKind::Log {
+                message: log.message.clone(),
+                log_type: log.r#type.clone(),
+            },
+        }));
+        self
+    }
+
+    /// Adds a received webhook. Events of other payments, and events that are not payment
+    /// events, are ignored.
+    pub fn webhook(mut self, event: &WebhookEvent) -> Self {
+        match event {
+            WebhookEvent::Charge(charge) if charge.tx_ref == self.tx_ref => {
+                self.events.push(TimelineEvent {
+                    at: charge.updated_at.or(charge.created_at),
+                    kind: TimelineEventKind::Webhook {
+                        event: charge.event.clone(),
+                        status: PaymentStatus::parse(&charge.status),
+                    },
+                });
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Adds several received webhooks, see [`PaymentTimelineBuilder::webhook`].
+    pub fn webhooks<'a>(self, events: impl IntoIterator<Item = &'a WebhookEvent>) -> Self {
+        events.into_iter().fold(self, Self::webhook)
+    }
+
+    /// Orders the events and builds the timeline.
+    pub fn build(mut self) -> PaymentTimeline {
+        self.events
+            .sort_by_key(|event| (event.at.is_none(), event.at));
+        PaymentTimeline {
+            tx_ref: self.tx_ref,
+            events: self.events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_state() {
+        let time = |minute: u32| {
+            format!("2024-05-01T10:{:02}:00Z", minute)
+                .parse::<DateTime<Utc>>()
+                .unwrap()
+        };
+        let verify: VerifyData = serde_json::from_value(serde_json::json!({
+            "first_name": "Abebe",
+            "last_name": "Bikila",
+            "email": null,
+            "currency": "ETB",
+            "amount": 100,
+            "charge": 3.5,
+            "mode": "test",
+            "method": "telebirr",
+            "type": "API",
+            "status": "success",
+            "reference": "6jnheVKQEmy",
+            "tx_ref": "order-1",
+            "customization": null,
+            "meta": null,
+            "created_at": "2024-05-01T10:00:00Z",
+            "updated_at": "2024-05-01T10:05:00Z"
+        }))
+        .unwrap();
+        let logs: Vec<TransactionLog> = serde_json::from_value(serde_json::json!([
+            {"item": 1, "message": "Attempted to pay with cbebirr", "type": "log", "created_at": "2024-05-01T10:01:00Z"},
+            {"item": 2, "message": "Payment failed", "type": "error", "created_at": "2024-05-01T10:02:00Z"}
+        ]))
+        .unwrap();
+        let webhook = |tx_ref: &str, status: &str, minute: u32| {
+            serde_json::from_value::<WebhookEvent>(serde_json::json!({
+                "event": format!("charge.{}", status),
+                "currency": "ETB",
+                "amount": "100",
+                "status": status,
+                "tx_ref": tx_ref,
+                "updated_at": time(minute)
+            }))
+            .unwrap()
+        };
+        let webhooks = [
+            webhook("order-1", "failed", 3),
+            webhook("order-2", "success", 4),
+            webhook("order-1", "success", 4),
+        ];
+
+        let timeline = PaymentTimeline::builder("order-1")
+            .webhooks(&webhooks)
+            .logs(&logs)
+            .verify(&verify)
+            .build();
+
+        assert_eq!(timeline.events.len(), 6);
+        assert!(timeline.events.windows(2).all(|w| w[0].at <= w[1].at));
+        assert_eq!(timeline.state(), Some(&PaymentStatus::Success));
+        assert_eq!(timeline.state_at(time(2)), Some(&PaymentStatus::Pending));
+        assert_eq!(timeline.state_at(time(3)), Some(&PaymentStatus::Failed));
+        let statuses: Vec<&PaymentStatus> = timeline
+            .transitions()
+            .into_iter()
+            .map(|(_, status)| status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                &PaymentStatus::Pending,
+                &PaymentStatus::Failed,
+                &PaymentStatus::Success
+            ]
+        );
+    }
+}
@@ -0,0 +1,23 @@
+//! # Transport Module
+//!
+//! The [`Transport`] trait sends the requests of a client in place of its HTTP client, set with
+//! [`ChapaConfigBuilder::transport`](crate::config::ChapaConfigBuilder::transport). Everything
+//! else (validation, registries, rate limiting, decoding, ...) runs as with the HTTP client.
+//!
+//! The `ScriptedTransport` of the `testing` module (behind the `test-utils` feature) answers
+//! with scripted responses, so the client can be tested without a server.
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+
+/// Sends the requests of a client.
+#[async_trait]
+pub trait Transport: Debug + Send + Sync {
+    /// Sends `request` and returns the response, whatever its status.
+    ///
+    /// # Errors
+    /// Returns an error if no response could be obtained.
+    async fn send(&self, request: reqwest::Request) -> Result<reqwest::Response>;
+}
@@ -0,0 +1,1083 @@
+//! # Webhook Module
+//!
+//! Processing of the webhooks Chapa delivers to the merchant's callback URL.
+//!
+//! [`verify_signature`] authenticates a delivery with the webhook secret set in the Chapa
+//! dashboard: Chapa signs the payload with HMAC-SHA256 in the `x-chapa-signature` header, and
+//! the secret itself in the `Chapa-Signature` header. [`verify_headers`] requires the former;
+//! the latter doesn't cover the body, so it is only accepted with
+//! [`ChapaConfigBuilder::accept_secret_signature`](crate::config::ChapaConfigBuilder::accept_secret_signature),
+//! see [`verify_secret_signature`]. Signatures are compared in constant time.
+//!
+//! A valid signature doesn't stop a captured delivery from being sent again. A [`ReplayGuard`]
+//! rejects deliveries whose timestamp is outside a tolerance window, and deliveries already
+//! received, remembered in a [`ReferenceRegistry`] used as the dedup store.
+//!
+//! With the `axum`, `actix` or `rocket` feature, [`ChapaWebhook`] is an extractor doing all of the
+//! below: it reads the raw body, verifies its signature with the webhook secret of the
+//! [`ChapaState`](crate::state::ChapaState) (see
+//! [`ChapaConfigBuilder::webhook_secret`](crate::config::ChapaConfigBuilder::webhook_secret))
+//! and the replay guard, if one is configured (see
+//! [`ChapaConfigBuilder::webhook_replay_guard`](crate::config::ChapaConfigBuilder::webhook_replay_guard)),
+//! and deserializes it, rejecting unsigned or stale requests with `401 Unauthorized`.
+//! Duplicates are answered with `200 OK` without reaching the handler, so Chapa stops
+//! retrying them. A handler that fails must release the delivery with
+//! [`ChapaClient::release_webhook`](crate::client::ChapaClient::release_webhook) before answering
+//! with an error, so Chapa's retry reaches it again:
+//!
+//! ```rust,no_run
+//! use chapa_rust::{state::ChapaState, webhook::ChapaWebhook};
+//!
+//! # async fn fulfill(event: &chapa_rust::models::webhook::WebhookEvent) -> Result<(), ()> { Ok(()) }
+//! // a handler, extracting the verified event with the `axum` or `actix` feature.
+//! async fn webhook(chapa: ChapaState, ChapaWebhook(event): ChapaWebhook) -> &'static str {
+//!     println!("{} for {:?}", event.event_type(), event.tx_ref());
+//!     if fulfill(&event).await.is_err() {
+//!         let _ = chapa.release_webhook(&event).await;
+//!         return "retry"; // answered with an error status in a real handler
+//!     }
+//!     "ok"
+//! }
+//! // axum:  Router::new().route("/webhook", post(webhook)).with_state(chapa_state)
+//! // actix: App::new().app_data(chapa_state).route("/webhook", web::post().to(webhook))
+//! // rocket: a `data = "<webhook>"` route, with `chapa_state.fairing()` attached
+//! ```
+//!
+//! [`parse`] deserializes a webhook body into a typed
+//! [`WebhookEvent`](crate::models::webhook::WebhookEvent): a payment (`charge.*`) or transfer
+//! (`payout.*`) event, with the customer, amounts and references as fields.
+//!
+//! A webhook only tells that Chapa says a payment happened; [`cross_check`] compares its amount,
+//! currency and status with the verification of the payment, and
+//! [`ChapaClient::cross_check_webhook`](crate::client::ChapaClient::cross_check_webhook) fetches
+//! the verification too.
+//!
+//! Every stage of processing a delivery is reported as a [`WebhookOutcome`] with [`report`]:
+//! with the `tracing` feature it is emitted as a `tracing` event on the `chapa_rust::webhook`
+//! target, and it is passed to an optional [`WebhookObserver`], the hook for metrics.
+//! [`WebhookCounters`] is an observer counting the outcomes, for basic observability without
+//! any metrics setup.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use chapa_rust::{sink::StdoutSink, webhook::{self, WebhookCounters, WebhookOutcome}};
+//!
+//! # async fn run(body: &[u8], signature: &str) -> chapa_rust::error::Result<()> {
+//! let counters = WebhookCounters::default();
+//! webhook::report(&WebhookOutcome::Received { bytes: body.len() }, Some(&counters));
+//! webhook::verify_signature(body, signature, "my-webhook-secret")?;
+//! webhook::report(&WebhookOutcome::Verified, Some(&counters));
+//! let event: serde_json::Value = serde_json::from_slice(body)?;
+//! webhook::dispatch(&StdoutSink, &event, Some(&counters)).await?;
+//! println!("{:?}", counters.snapshot());
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use http::HeaderMap;
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::{
+    error::{ChapaError, Result},
+    models::{
+        normalized::PaymentStatus,
+        payment::VerifyData,
+        webhook::{ChargeEvent, WebhookEvent},
+    },
+    registry::{InMemoryRegistry, ReferenceRegistry},
+    sink::WebhookSink,
+};
+
+/// Deserializes the webhook `body` Chapa posted into a typed event.
+///
+/// # Errors
+/// Returns [`ChapaError::JsonError`] if the body is not JSON, or if a payment or transfer event
+/// lacks a required field.
+pub fn parse(body: &[u8]) -> Result<WebhookEvent> {
+    Ok(serde_json::from_slice(body)?)
+}
+
+/// The header carrying the HMAC-SHA256 of the payload, keyed with the webhook secret.
+pub const PAYLOAD_SIGNATURE_HEADER: &str = "x-chapa-signature";
+
+/// The header carrying the HMAC-SHA256 of the webhook secret, keyed with itself.
+pub const SECRET_SIGNATURE_HEADER: &str = "chapa-signature";
+
+/// Checks that `header`, the hex-encoded value of the `x-chapa-signature` header, is the
+/// HMAC-SHA256 of `payload` keyed with the webhook `secret`.
+///
+/// `payload` must be the body exactly as received: re-serializing a parsed body changes the
+/// bytes and breaks the signature.
+///
+/// # Errors
+/// Returns [`ChapaError::InvalidSignature`] if the signature is malformed or doesn't match.
+pub fn verify_signature(payload: &[u8], header: &str, secret: &str) -> Result<()> {
+    check_hmac(payload, header, secret)
+}
+
+/// Checks the signature of a webhook request from its `headers`: the `x-chapa-signature`
+/// header against `payload`.
+///
+/// # Errors
+/// Returns [`ChapaError::InvalidSignature`] if the header is missing, or if the signature is
+/// malformed or doesn't match.
+pub fn verify_headers(headers: &HeaderMap, payload: &[u8], secret: &str) -> Result<()> {
+    match header_value(headers, PAYLOAD_SIGNATURE_HEADER) {
+        Some(signature) => verify_signature(payload, signature, secret),
+        None => Err(ChapaError::InvalidSignature(format!(
+            "the request has no {} header",
+            PAYLOAD_SIGNATURE_HEADER
+        ))),
+    }
+}
+
+/// Checks that `header`, the hex-encoded value of the `Chapa-Signature` header, is the
+/// HMAC-SHA256 of the webhook `secret` keyed with itself.
+///
+/// This signature is the same for every delivery and doesn't cover the payload: it only proves
+/// the sender knows the secret, or saw any earlier delivery. Prefer [`verify_headers`], and
+/// only fall back to this for deliveries lacking the `x-chapa-signature` header.
+///
+/// # Errors
+/// Returns [`ChapaError::InvalidSignature`] if the signature is malformed or doesn't match.
+pub fn verify_secret_signature(header: &str, secret: &str) -> Result<()> {
+    check_hmac(secret.as_bytes(), header, secret)
+}
+
+/// Helper function returning the value of the header `name`, if present.
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .map(|value| value.to_str().unwrap_or_default())
+}
+
+/// Helper function comparing the hex-encoded `signature` with the HMAC-SHA256 of `message` in
+/// constant time.
+fn check_hmac(message: &[u8], signature: &str, secret: &str) -> Result<()> {
+    let signature = decode_hex(signature.trim()).ok_or_else(|| {
+        ChapaError::InvalidSignature("the signature is not hex-encoded".to_string())
+    })?;
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.verify_slice(&signature).map_err(|_| {
+        ChapaError::InvalidSignature("the signature doesn't match the payload".to_string())
+    })
+}
+
+/// Helper function decoding a hex string, `None` if it is malformed.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+/// How many deliveries the default dedup store of a [`ReplayGuard`] remembers.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 100_000;
+
+/// Replay protection for webhooks: rejects deliveries outside a freshness window and
+/// deliveries already received.
+///
+/// The time of a delivery is read from the `updated_at` (or `created_at`) field of the payload,
+/// which is covered by the signature; deliveries without any are only deduplicated. Since Chapa
+/// retries failed deliveries with the same payload, the tolerance should cover its retry
+/// schedule, on top of the clock skew between Chapa and the server.
+///
+/// Deliveries are identified by their event type and reference, and remembered in the dedup
+/// store: in memory by default, up to [`DEFAULT_DEDUP_CAPACITY`] deliveries. A delivery whose
+/// handler fails must be [released](Self::release), or Chapa's retry is taken for a duplicate.
+#[derive(Debug, Clone)]
+pub struct ReplayGuard {
+    tolerance: Duration,
+    store: Arc<dyn ReferenceRegistry>,
+}
+
+impl ReplayGuard {
+    /// Creates a guard accepting deliveries at most `tolerance` away from the current time,
+    /// deduplicated in memory.
+    pub fn new(tolerance: Duration) -> Self {
+        Self {
+            tolerance,
+            store: Arc::new(InMemoryRegistry::with_capacity(DEFAULT_DEDUP_CAPACITY)),
+        }
+    }
+
+    /// Sets the dedup store, e.g. one shared by every instance of the service.
+    pub fn store(mut self, store: impl ReferenceRegistry + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Checks the delivery of `event`, the parsed webhook body, recording it in the dedup store
+    /// if it is accepted.
+    ///
+    /// # Errors
+    /// Returns [`ChapaError::StaleWebhook`] if the delivery is outside the tolerance window,
+    /// [`ChapaError::DuplicateWebhook`] if it was already received, or the error of the store.
+    pub async fn check(&self, event: &Value) -> Result<()> {
+        self.check_freshness(event, Utc::now())?;
+        let Some(key) = Self::delivery_key(event) else {
+            return Ok(());
+        };
+        if !self.store.reserve(&key).await? {
+            return Err(ChapaError::DuplicateWebhook(key));
+        }
+        Ok(())
+    }
+
+    /// Forgets the delivery of `event`, so its next delivery is accepted again. Call it when the
+    /// handler of an accepted delivery fails, before answering Chapa with an error.
+    ///
+    /// # Errors
+    /// Returns the error of the dedup store.
+    pub async fn release(&self, event: &Value) -> Result<()> {
+        match Self::delivery_key(event) {
+            Some(key) => self.store.release(&key).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Helper function checking the time of the delivery against `now`.
+    fn check_freshness(&self, event: &Value, now: DateTime<Utc>) -> Result<()> {
+        let Some(sent_at) = ["updated_at", "created_at"]
+            .iter()
+            .find_map(|field| event.get(field).and_then(Value::as_str))
+            .and_then(parse_timestamp)
+        else {
+            return Ok(());
+        };
+        let skew = (now - sent_at).abs().to_std().unwrap_or(Duration::MAX);
+        if skew > self.tolerance {
+            return Err(ChapaError::StaleWebhook(format!(
+                "sent at {}, {}s away from now, the tolerance is {}s",
+                sent_at.to_rfc3339(),
+                skew.as_secs(),
+                self.tolerance.as_secs()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Helper function identifying a delivery by its event type and reference.
+    fn delivery_key(event: &Value) -> Option<String> {
+        let event_type = event.get("event").and_then(Value::as_str)?;
+        let reference = ["reference", "tx_ref", "trx_ref", "chapa_reference"]
+            .iter()
+            .find_map(|field| event.get(field).and_then(Value::as_str))?;
+        Some(format!("{}:{}", event_type, reference))
+    }
+}
+
+/// Helper function parsing a timestamp given as Unix seconds or RFC 3339.
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    match timestamp.trim().parse::<i64>() {
+        Ok(seconds) => DateTime::from_timestamp(seconds, 0),
+        Err(_) => DateTime::parse_from_rfc3339(timestamp.trim())
+            .ok()
+            .map(|date| date.with_timezone(&Utc)),
+    }
+}
+
+/// A stage reached while processing a webhook delivery.
+#[derive(Debug, Clone, Copy)]
+pub enum WebhookOutcome<'a> {
+    /// A delivery was received.
+    Received {
+        /// The size of the body, in bytes.
+        bytes: usize,
+    },
+    /// The delivery was authenticated as coming from Chapa.
+    Verified,
+    /// The delivery was rejected and will not be processed.
+    Rejected {
+        /// Why it was rejected, e.g. `invalid signature`.
+        reason: &'a str,
+    },
+    /// The event was handed to its handler successfully.
+    Dispatched {
+        /// The event type, e.g. `charge.success`.
+        event: &'a str,
+    },
+    /// The handler of the event failed.
+    HandlerError {
+        /// The event type, e.g. `charge.success`.
+        event: &'a str,
+        /// The error returned by the handler.
+        error: &'a ChapaError,
+    },
+}
+
+impl WebhookOutcome<'_> {
+    /// A stable name for the outcome, e.g. `handler_error`, usable as a metric label.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookOutcome::Received { .. } => "received",
+            WebhookOutcome::Verified => "verified",
+            WebhookOutcome::Rejected { .. } => "rejected",
+            WebhookOutcome::Dispatched { .. } => "dispatched",
+            WebhookOutcome::HandlerError { .. } => "handler_error",
+        }
+    }
+}
+
+/// A hook notified of every [`WebhookOutcome`], e.g. to feed a metrics backend.
+pub trait WebhookObserver: Debug + Send + Sync {
+    /// Records `outcome`.
+    fn observe(&self, outcome: &WebhookOutcome<'_>);
+}
+
+/// How many webhook deliveries reached each outcome.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookCounts {
+    /// How many deliveries were received.
+    pub received: u64,
+    /// How many deliveries were authenticated.
+    pub verified: u64,
+    /// How many deliveries were rejected.
+    pub rejected: u64,
+    /// How many events were handled successfully.
+    pub dispatched: u64,
+    /// How many events failed in their handler.
+    pub handler_errors: u64,
+}
+
+/// A [`WebhookObserver`] counting the outcomes. Clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookCounters {
+    counts: Arc<Mutex<WebhookCounts>>,
+}
+
+impl WebhookCounters {
+    /// Returns a copy of the current counts.
+    pub fn snapshot(&self) -> WebhookCounts {
+        self.counts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl WebhookObserver for WebhookCounters {
+    fn observe(&self, outcome: &WebhookOutcome<'_>) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        match outcome {
+            WebhookOutcome::Received { .. } => counts.received += 1,
+            WebhookOutcome::Verified => counts.verified += 1,
+            WebhookOutcome::Rejected { .. } => counts.rejected += 1,
+            WebhookOutcome::Dispatched { .. } => counts.dispatched += 1,
+            WebhookOutcome::HandlerError { .. } => counts.handler_errors += 1,
+        }
+    }
+}
+
+/// Reports `outcome` as a `tracing` event (with the `tracing` feature) and to `observer`.
+///
+/// Rejections and handler errors are logged at the `WARN` and `ERROR` levels, the other
+/// outcomes at `DEBUG`.
+pub fn report(outcome: &WebhookOutcome<'_>, observer: Option<&dyn WebhookObserver>) {
+    #[cfg(feature = "tracing")]
+    trace(outcome);
+    if let Some(observer) = observer {
+        observer.observe(outcome);
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn trace(outcome: &WebhookOutcome<'_>) {
+    const TARGET: &str = "chapa_rust::webhook";
+    let name = outcome.name();
+    match outcome {
+        WebhookOutcome::Received { bytes } => {
+            tracing::debug!(target: TARGET, outcome = name, bytes, "webhook received")
+        }
+        WebhookOutcome::Verified => {
+            tracing::debug!(target: TARGET, outcome = name, "webhook verified")
+        }
+        WebhookOutcome::Rejected { reason } => {
+            tracing::warn!(target: TARGET, outcome = name, reason, "webhook rejected")
+        }
+        WebhookOutcome::Dispatched { event } => {
+            tracing::debug!(target: TARGET, outcome = name, event, "webhook dispatched")
+        }
+        WebhookOutcome::HandlerError { event, error } => {
+            tracing::error!(
+                target: TARGET,
+                outcome = name,
+                event,
+                error = %error,
+                error_code = error.code(),
+                "webhook handler failed"
+            )
+        }
+    }
+}
+
+/// Publishes the verified webhook body `event` to `sink`, reporting whether it was dispatched
+/// or the sink failed.
+///
+/// # Errors
+/// Returns the error of the sink.
+pub async fn dispatch(
+    sink: &dyn WebhookSink,
+    event: &Value,
+    observer: Option<&dyn WebhookObserver>,
+) -> Result<()> {
+    let name = event
+        .get("event")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    match sink.publish(event).await {
+        Ok(()) => {
+            report(&WebhookOutcome::Dispatched { event: name }, observer);
+            Ok(())
+        }
+        Err(error) => {
+            report(
+                &WebhookOutcome::HandlerError {
+                    event: name,
+                    error: &error,
+                },
+                observer,
+            );
+            Err(error)
+        }
+    }
+}
+
+/// A field on which a payment webhook and the verification of the payment disagree, see
+/// [`cross_check`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Discrepancy {
+    /// The amounts differ.
+    Amount {
+        /// The amount of the webhook, as received.
+        webhook: String,
+        /// The amount returned by the verification.
+        verified: f64,
+    },
+    /// The currencies differ.
+    Currency {
+        /// The currency of the webhook.
+        webhook: String,
+        /// The currency returned by the verification, if any.
+        verified: Option<String>,
+    },
+    /// The statuses differ, once normalized (see [`PaymentStatus::parse`]).
+    Status {
+        /// The status of the webhook.
+        webhook: String,
+        /// The status returned by the verification, if any.
+        verified: Option<String>,
+    },
+}
+
+/// The result of comparing a payment webhook with the verification of the payment, see
+/// [`ChapaClient::cross_check_webhook`](crate::client::ChapaClient::cross_check_webhook).
+#[derive(Debug)]
+pub struct CrossCheckReport {
+    /// The merchant's transaction reference of the payment.
+    pub tx_ref: String,
+    /// The payment as returned by the verification.
+    pub verified: VerifyData,
+    /// The fields on which the webhook and the verification disagree.
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl CrossCheckReport {
+    /// Returns `true` if the webhook matches the verification, so it can be trusted.
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// Compares the amount, currency and status of a payment webhook with the verification of the
+/// payment, the double check Chapa recommends before fulfilling an order.
+///
+/// Amounts are compared to the cent and currencies and statuses case-insensitively.
+pub fn cross_check(event: &ChargeEvent, verified: &VerifyData) -> Vec<Discrepancy> {
+    let mut discrepancies = Vec::new();
+    let amount = event.amount.trim().parse::<f64>().ok();
+    if amount.is_none_or(|amount| (amount - verified.amount).abs() >= 0.005) {
+        discrepancies.push(Discrepancy::Amount {
+            webhook: event.amount.clone(),
+            verified: verified.amount,
+        });
+    }
+    if !verified
+        .currency
+        .as_deref()
+        .is_some_and(|currency| currency.eq_ignore_ascii_case(&event.currency))
+    {
+        discrepancies.push(Discrepancy::Currency {
+            webhook: event.currency.clone(),
+            verified: verified.currency.clone(),
+        });
+    }
+    if verified.status.as_deref().map(PaymentStatus::parse)
+        != Some(PaymentStatus::parse(&event.status))
+    {
+        discrepancies.push(Discrepancy::Status {
+            webhook: event.status.clone(),
+            verified: verified.status.clone(),
+        });
+    }
+    discrepancies
+}
+
+/// A verified webhook, deserialized into `T`, a typed [`WebhookEvent`] by default.
+///
+/// With the `axum` feature, it is an extractor verifying the signature of the request with the
+/// [`ChapaState`](crate::state::ChapaState) obtained from the router state. With the `actix`
+/// feature, it is an extractor doing the same with the `ChapaState` registered as app data, and
+/// with the `rocket` feature, a data guard using the `ChapaState` managed by Rocket.
+///
+/// Deliveries are recorded by the replay guard, if any, once extracted: release them with
+/// [`ChapaClient::release_webhook`](crate::client::ChapaClient::release_webhook) when the
+/// handler fails.
+#[derive(Debug, Clone)]
+pub struct ChapaWebhook<T = WebhookEvent>(pub T);
+
+impl<T> std::ops::Deref for ChapaWebhook<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Why a webhook request was refused by a [`ChapaWebhook`] extractor.
+#[cfg(any(feature = "axum", feature = "actix", feature = "rocket"))]
+enum Refusal {
+    /// The signature is missing or invalid, answered with `401 Unauthorized`.
+    Unauthorized(String),
+    /// The body is not a valid event, answered with `400 Bad Request`.
+    BadRequest(String),
+    /// The delivery was already received, answered with `200 OK`.
+    Duplicate(String),
+}
+
+/// Helper function shared by the extractors, verifying and deserializing a webhook request.
+#[cfg(any(feature = "axum", feature = "actix", feature = "rocket"))]
+async fn accept<T: serde::de::DeserializeOwned>(
+    chapa: &crate::client::ChapaClient,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> std::result::Result<ChapaWebhook<T>, Refusal> {
+    let observer = chapa.webhook_observer();
+    let reject = |reason: String, refusal: fn(String) -> Refusal| {
+        report(&WebhookOutcome::Rejected { reason: &reason }, observer);
+        refusal(reason)
+    };
+
+    report(&WebhookOutcome::Received { bytes: body.len() }, observer);
+    if let Err(error) = chapa.verify_webhook(headers, body) {
+        return Err(reject(error.to_string(), Refusal::Unauthorized));
+    }
+    report(&WebhookOutcome::Verified, observer);
+
+    let event: Value = serde_json::from_slice(body).map_err(|error| {
+        reject(
+            format!("invalid webhook body: {}", error),
+            Refusal::BadRequest,
+        )
+    })?;
+    match chapa.check_webhook_replay(&event).await {
+        Ok(()) => {}
+        Err(error @ ChapaError::DuplicateWebhook(_)) => {
+            return Err(reject(error.to_string(), Refusal::Duplicate));
+        }
+        Err(error) => return Err(reject(error.to_string(), Refusal::Unauthorized)),
+    }
+    let error = match T::deserialize(&event) {
+        Ok(event) => return Ok(ChapaWebhook(event)),
+        Err(error) => error.to_string(),
+    };
+    // the delivery is refused, so it must not be taken for a duplicate when resent.
+    let _ = chapa.release_webhook(&event).await;
+    Err(reject(
+        format!("invalid webhook body: {}", error),
+        Refusal::BadRequest,
+    ))
+}
+
+#[cfg(feature = "axum")]
+impl<S, T> axum::extract::FromRequest<S> for ChapaWebhook<T>
+where
+    S: Send + Sync,
+    crate::state::ChapaState: axum::extract::FromRef<S>,
+    T: serde::de::DeserializeOwned,
+{
+    type Rejection = axum::response::Response;
+
+    async fn from_request(
+        req: axum::extract::Request,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        use axum::{extract::FromRef, http::StatusCode, response::IntoResponse};
+
+        let chapa = crate::state::ChapaState::from_ref(state);
+        let headers = req.headers().clone();
+        let body = axum::body::Bytes::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        accept(&chapa, &headers, &body)
+            .await
+            .map_err(|refusal| match refusal {
+                Refusal::Unauthorized(reason) => (StatusCode::UNAUTHORIZED, reason).into_response(),
+                Refusal::BadRequest(reason) => (StatusCode::BAD_REQUEST, reason).into_response(),
+                Refusal::Duplicate(reason) => (StatusCode::OK, reason).into_response(),
+            })
+    }
+}
+
+#[cfg(feature = "actix")]
+impl<T> actix_web::FromRequest for ChapaWebhook<T>
+where
+    T: serde::de::DeserializeOwned + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = std::result::Result<Self, Self::Error>>>,
+    >;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        let chapa = crate::state::ChapaState::from_request(req, payload).into_inner();
+        // actix-web has its own header types, only the headers checked are carried over.
+        let mut headers = HeaderMap::new();
+        for name in [PAYLOAD_SIGNATURE_HEADER, SECRET_SIGNATURE_HEADER] {
+            let value = req
+                .headers()
+                .get(name)
+                .and_then(|value| http::HeaderValue::from_bytes(value.as_bytes()).ok());
+            if let Some(value) = value {
+                headers.insert(name, value);
+            }
+        }
+        let body = actix_web::web::Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let chapa = chapa?;
+            let body = body.await?;
+            accept(&chapa, &headers, &body)
+                .await
+                .map_err(|refusal| match refusal {
+                    Refusal::Unauthorized(reason) => actix_web::error::ErrorUnauthorized(reason),
+                    Refusal::BadRequest(reason) => actix_web::error::ErrorBadRequest(reason),
+                    Refusal::Duplicate(reason) => actix_web::error::InternalError::new(
+                        reason,
+                        actix_web::http::StatusCode::OK,
+                    )
+                    .into(),
+                })
+        })
+    }
+}
+
+#[cfg(feature = "rocket")]
+#[rocket::async_trait]
+impl<'r, T> rocket::data::FromData<'r> for ChapaWebhook<T>
+where
+    T: serde::de::DeserializeOwned + Send,
+{
+    type Error = String;
+
+    async fn from_data(
+        req: &'r rocket::Request<'_>,
+        data: rocket::Data<'r>,
+    ) -> rocket::data::Outcome<'r, Self> {
+        use rocket::{
+            data::{Outcome, ToByteUnit},
+            http::Status,
+        };
+
+        let Some(chapa) = req.rocket().state::<crate::state::ChapaState>() else {
+            let reason = "ChapaState is not managed by Rocket".to_string();
+            return Outcome::Error((Status::InternalServerError, reason));
+        };
+        // Rocket has its own header types, only the headers checked are carried over.
+        let mut headers = HeaderMap::new();
+        for name in [PAYLOAD_SIGNATURE_HEADER, SECRET_SIGNATURE_HEADER] {
+            let value = req
+                .headers()
+                .get_one(name)
+                .and_then(|value| http::HeaderValue::from_str(value).ok());
+            if let Some(value) = value {
+                headers.insert(name, value);
+            }
+        }
+        let limit = req.limits().get("json").unwrap_or(1.mebibytes());
+        let body = match data.open(limit).into_bytes().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => {
+                let reason = "the webhook body exceeds the JSON limit".to_string();
+                return Outcome::Error((Status::PayloadTooLarge, reason));
+            }
+            Err(error) => return Outcome::Error((Status::BadRequest, error.to_string())),
+        };
+
+        match accept(chapa, &headers, &body).await {
+            Ok(webhook) => Outcome::Success(webhook),
+            Err(Refusal::Unauthorized(reason)) => Outcome::Error((Status::Unauthorized, reason)),
+            Err(Refusal::BadRequest(reason)) => Outcome::Error((Status::BadRequest, reason)),
+            Err(Refusal::Duplicate(reason)) => Outcome::Error((Status::Ok, reason)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct FailingSink;
+
+    /// A sink keeping the events published, to assert on them.
+    #[derive(Debug, Default)]
+    struct CapturingSink {
+        events: Mutex<Vec<Value>>,
+    }
+
+    #[async_trait]
+    impl WebhookSink for CapturingSink {
+        async fn publish(&self, event: &Value) -> Result<()> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl WebhookSink for FailingSink {
+        async fn publish(&self, _event: &Value) -> Result<()> {
+            Err(ChapaError::IoError("disk full".to_string()))
+        }
+    }
+
+    /// Helper function returning the hex-encoded HMAC-SHA256 of `message` keyed with `secret`.
+    fn sign(secret: &str, message: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(message);
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let secret = "my-webhook-secret";
+        let payload = br#"{"event":"charge.success","tx_ref":"chewatatest-6669"}"#;
+        let sign = |message: &[u8]| sign(secret, message);
+
+        let signature = sign(payload);
+        assert!(verify_signature(payload, &signature, secret).is_ok());
+        assert!(verify_signature(payload, &signature.to_uppercase(), secret).is_ok());
+        assert!(verify_signature(b"{}", &signature, secret).is_err());
+        assert!(verify_signature(payload, &signature, "another-secret").is_err());
+        assert!(matches!(
+            verify_signature(payload, "not-hex", secret),
+            Err(ChapaError::InvalidSignature(_))
+        ));
+
+        let mut headers = HeaderMap::new();
+        assert!(verify_headers(&headers, payload, secret).is_err());
+        // the secret signature alone doesn't authenticate the payload.
+        headers.insert(
+            SECRET_SIGNATURE_HEADER,
+            sign(secret.as_bytes()).parse().unwrap(),
+        );
+        assert!(verify_headers(&headers, payload, secret).is_err());
+        assert!(verify_secret_signature(&sign(secret.as_bytes()), secret).is_ok());
+        assert!(verify_secret_signature(&sign(payload), secret).is_err());
+        headers.insert(PAYLOAD_SIGNATURE_HEADER, sign(b"{}").parse().unwrap());
+        assert!(verify_headers(&headers, payload, secret).is_err());
+        headers.insert(PAYLOAD_SIGNATURE_HEADER, sign(payload).parse().unwrap());
+        assert!(verify_headers(&headers, payload, secret).is_ok());
+    }
+
+    #[test]
+    fn test_verify_webhook_secret_signature() {
+        use crate::{client::ChapaClient, config::ChapaConfig};
+
+        let secret = "my-webhook-secret";
+        let client = |accept: bool| {
+            ChapaClient::from_config(
+                ChapaConfig::builder()
+                    .api_key("CHASECK_TEST-XXXXXXXXXXXXXXX")
+                    .webhook_secret(secret)
+                    .accept_secret_signature(accept)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            SECRET_SIGNATURE_HEADER,
+            sign(secret, secret.as_bytes()).parse().unwrap(),
+        );
+
+        assert!(matches!(
+            client(false).verify_webhook(&headers, b"{}"),
+            Err(ChapaError::InvalidSignature(_))
+        ));
+        assert!(client(true).verify_webhook(&headers, b"{}").is_ok());
+        // the payload signature is still checked when present.
+        headers.insert(
+            PAYLOAD_SIGNATURE_HEADER,
+            sign(secret, b"[]").parse().unwrap(),
+        );
+        assert!(client(true).verify_webhook(&headers, b"{}").is_err());
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_axum_extractor() {
+        use axum::{extract::FromRequest, http::StatusCode};
+
+        use crate::{config::ChapaConfig, state::chapa_state};
+
+        let secret = "my-webhook-secret";
+        let counters = WebhookCounters::default();
+        let state = chapa_state(
+            ChapaConfig::builder()
+                .api_key("CHASECK_TEST-XXXXXXXXXXXXXXX")
+                .webhook_secret(secret)
+                .webhook_replay_guard(ReplayGuard::new(Duration::from_secs(300)))
+                .webhook_observer(counters.clone())
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        let body = r#"{"event": "charge.success", "currency": "ETB", "amount": "100.00", "status": "success", "tx_ref": "chewatatest-6669"}"#;
+        let signature = sign(secret, body.as_bytes());
+        let request = |signature: &str| {
+            axum::http::Request::builder()
+                .method("POST")
+                .header(PAYLOAD_SIGNATURE_HEADER, signature)
+                .body(axum::body::Body::from(body))
+                .unwrap()
+        };
+
+        let ChapaWebhook(event) =
+            ChapaWebhook::<WebhookEvent>::from_request(request(&signature), &state)
+                .await
+                .unwrap();
+        assert_eq!(event.tx_ref(), Some("chewatatest-6669"));
+
+        let rejection = ChapaWebhook::<WebhookEvent>::from_request(request("00"), &state)
+            .await
+            .unwrap_err();
+        assert_eq!(rejection.status(), StatusCode::UNAUTHORIZED);
+
+        let duplicate = ChapaWebhook::<WebhookEvent>::from_request(request(&signature), &state)
+            .await
+            .unwrap_err();
+        assert_eq!(duplicate.status(), StatusCode::OK);
+
+        // once released after a failed handler, the retry reaches the handler again.
+        state.release_webhook(&event).await.unwrap();
+        assert!(
+            ChapaWebhook::<WebhookEvent>::from_request(request(&signature), &state)
+                .await
+                .is_ok()
+        );
+
+        // the configured observer saw every delivery.
+        let counts = counters.snapshot();
+        assert_eq!(
+            (counts.received, counts.verified, counts.rejected),
+            (4, 3, 2)
+        );
+    }
+
+    #[cfg(feature = "actix")]
+    #[tokio::test]
+    async fn test_actix_extractor() {
+        use actix_web::{FromRequest, http::StatusCode, test::TestRequest};
+
+        use crate::{config::ChapaConfig, state::chapa_state};
+
+        let secret = "my-webhook-secret";
+        let state = chapa_state(
+            ChapaConfig::builder()
+                .api_key("CHASECK_TEST-XXXXXXXXXXXXXXX")
+                .webhook_secret(secret)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        let body = r#"{"event": "payout.success", "currency": "ETB", "amount": "10.00", "status": "success", "reference": "3241342142sfdd"}"#;
+        let signature = sign(secret, body.as_bytes());
+        let extract = |signature: &str| {
+            let (req, mut payload) = TestRequest::post()
+                .app_data(state.clone())
+                .insert_header((PAYLOAD_SIGNATURE_HEADER, signature))
+                .set_payload(body)
+                .to_http_parts();
+            async move { ChapaWebhook::<WebhookEvent>::from_request(&req, &mut payload).await }
+        };
+
+        let ChapaWebhook(event) = extract(&signature).await.unwrap();
+        assert_eq!(event.event_type(), "payout.success");
+
+        let error = extract("00").await.unwrap_err();
+        assert_eq!(
+            error.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_guard() {
+        let guard = ReplayGuard::new(Duration::from_secs(300));
+        let now = Utc::now();
+        let event = serde_json::json!({
+            "event": "charge.success",
+            "tx_ref": "chewatatest-6669",
+            "updated_at": now.to_rfc3339(),
+        });
+        assert!(guard.check(&event).await.is_ok());
+        assert!(matches!(
+            guard.check(&event).await,
+            Err(ChapaError::DuplicateWebhook(_))
+        ));
+        // a delivery whose handler failed is accepted again.
+        guard.release(&event).await.unwrap();
+        assert!(guard.check(&event).await.is_ok());
+
+        let stale = serde_json::json!({
+            "event": "charge.success",
+            "tx_ref": "chewatatest-6670",
+            "updated_at": "2023-02-02T07:05:23.000000Z",
+        });
+        assert!(matches!(
+            guard.check(&stale).await,
+            Err(ChapaError::StaleWebhook(_))
+        ));
+        // within the tolerance for clock skew.
+        let skewed = serde_json::json!({
+            "event": "charge.success",
+            "tx_ref": "chewatatest-6671",
+            "updated_at": (now + chrono::Duration::seconds(120)).timestamp().to_string(),
+        });
+        assert!(guard.check(&skewed).await.is_ok());
+
+        // the in-memory store forgets the oldest deliveries past its capacity.
+        let store = InMemoryRegistry::with_capacity(2);
+        let guard = ReplayGuard::new(Duration::from_secs(300)).store(store.clone());
+        for reference in ["tx-1", "tx-2", "tx-3"] {
+            let event = serde_json::json!({"event": "charge.success", "tx_ref": reference});
+            guard.check(&event).await.unwrap();
+        }
+        assert_eq!(store.len(), 2);
+        assert!(!store.contains("charge.success:tx-1"));
+        assert!(store.contains("charge.success:tx-3"));
+    }
+
+    #[cfg(feature = "rocket")]
+    #[rocket::post("/webhook", data = "<webhook>")]
+    fn rocket_webhook(chapa: crate::state::ChapaState, webhook: ChapaWebhook) -> String {
+        format!("{:?} {}", chapa.environment(), webhook.event_type())
+    }
+
+    #[cfg(feature = "rocket")]
+    #[tokio::test]
+    async fn test_rocket_guards() {
+        use rocket::{
+            http::{Header, Status},
+            local::asynchronous::Client,
+        };
+
+        use crate::{config::ChapaConfig, state::chapa_state};
+
+        let secret = "my-webhook-secret";
+        let state = chapa_state(
+            ChapaConfig::builder()
+                .api_key("CHASECK_TEST-XXXXXXXXXXXXXXX")
+                .webhook_secret(secret)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        let rocket = rocket::build()
+            .attach(state.fairing())
+            .mount("/", rocket::routes![rocket_webhook]);
+        let client = Client::untracked(rocket).await.unwrap();
+
+        let body = r#"{"event": "charge.success", "currency": "ETB", "amount": "100.00", "status": "success", "tx_ref": "chewatatest-6669"}"#;
+        let signature = sign(secret, body.as_bytes());
+
+        let response = client
+            .post("/webhook")
+            .header(Header::new(PAYLOAD_SIGNATURE_HEADER, signature))
+            .body(body)
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().await.unwrap(), "Test charge.success");
+
+        let response = client.post("/webhook").body(body).dispatch().await;
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn test_parse() {
+        let event = parse(br#"{"event": "charge.success", "currency": "ETB", "amount": "100.00", "status": "success", "tx_ref": "chewatatest-6669"}"#).unwrap();
+        assert_eq!(event.tx_ref(), Some("chewatatest-6669"));
+        assert!(parse(br#"{"event": "charge.success"}"#).is_err());
+        assert!(parse(b"not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_outcomes_are_counted() {
+        let counters = WebhookCounters::default();
+        let event = serde_json::json!({"event": "charge.success", "tx_ref": "chewatatest-6669"});
+
+        report(&WebhookOutcome::Received { bytes: 64 }, Some(&counters));
+        report(
+            &WebhookOutcome::Rejected {
+                reason: "invalid signature",
+            },
+            Some(&counters),
+        );
+        let sink = CapturingSink::default();
+        dispatch(&sink, &event, Some(&counters)).await.unwrap();
+        assert_eq!(*sink.events.lock().unwrap(), vec![event.clone()]);
+        assert!(
+            dispatch(&FailingSink, &event, Some(&counters))
+                .await
+                .is_err()
+        );
+
+        assert_eq!(
+            counters.snapshot(),
+            WebhookCounts {
+                received: 1,
+                verified: 0,
+                rejected: 1,
+                dispatched: 1,
+                handler_errors: 1,
+            }
+        );
+    }
+}